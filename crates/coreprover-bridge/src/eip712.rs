@@ -0,0 +1,252 @@
+//! EIP-712 typed-data hashing for CoreProver receipts
+//!
+//! Produces the digest a contract recovers a receipt signature against.
+//! The Solidity side declares the same struct and type string:
+//!
+//! ```solidity
+//! struct CoreProverReceipt {
+//!     string sessionId;
+//!     uint256 orderAmount;
+//!     uint64 fulfillmentUnix;
+//!     uint64 fulfillmentDeadlineUnix;
+//!     uint64 settlementUnix;
+//!     uint8 discountPct;
+//!     uint64 discountExpirationUnix;
+//!     uint64 buyerChainId;
+//!     string buyerCommitTxid;
+//!     uint64 sellerChainId;
+//!     string sellerAcceptTxid;
+//!     string sellerFulfillTxid;
+//!     string sellerClaimTxid;
+//!     string sellerRefundTxid;
+//!     string buyerWithdrawTxid;
+//!     uint256 buyerReturnedAmount;
+//!     uint64 sellerBlockHeight;
+//! }
+//! ```
+//!
+//! Unset txids are empty strings. Monotonic clocks and ISO strings are
+//! off-chain detail and are not part of the typed data.
+
+use alloy_primitives::{keccak256, Address, U256};
+
+/// Type string of the domain separator (all four fields are always set)
+pub const EIP712_DOMAIN_TYPE: &str =
+    "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+
+/// Type string of [`ReceiptTypedData`], field order as in the Solidity struct
+pub const RECEIPT_TYPE: &str = "CoreProverReceipt(\
+string sessionId,\
+uint256 orderAmount,\
+uint64 fulfillmentUnix,\
+uint64 fulfillmentDeadlineUnix,\
+uint64 settlementUnix,\
+uint8 discountPct,\
+uint64 discountExpirationUnix,\
+uint64 buyerChainId,\
+string buyerCommitTxid,\
+uint64 sellerChainId,\
+string sellerAcceptTxid,\
+string sellerFulfillTxid,\
+string sellerClaimTxid,\
+string sellerRefundTxid,\
+string buyerWithdrawTxid,\
+uint256 buyerReturnedAmount,\
+uint64 sellerBlockHeight)";
+
+/// EIP-712 domain of the verifying contract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Eip712Domain {
+    pub name: String,
+    pub version: String,
+    pub chain_id: u64,
+    pub verifying_contract: Address,
+}
+
+impl Eip712Domain {
+    pub fn new(
+        name: impl Into<String>,
+        version: impl Into<String>,
+        chain_id: u64,
+        verifying_contract: Address,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            version: version.into(),
+            chain_id,
+            verifying_contract,
+        }
+    }
+
+    /// `keccak256(typeHash ‖ keccak256(name) ‖ keccak256(version) ‖ chainId ‖
+    /// verifyingContract)`
+    pub fn separator(&self) -> [u8; 32] {
+        let mut enc = Vec::with_capacity(5 * 32);
+        enc.extend_from_slice(keccak256(EIP712_DOMAIN_TYPE).as_slice());
+        enc.extend_from_slice(keccak256(&self.name).as_slice());
+        enc.extend_from_slice(keccak256(&self.version).as_slice());
+        enc.extend_from_slice(&word(U256::from(self.chain_id)));
+        enc.extend_from_slice(self.verifying_contract.into_word().as_slice());
+        keccak256(enc).0
+    }
+}
+
+/// On-chain view of a receipt, one field per member of the Solidity struct
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ReceiptTypedData {
+    pub session_id: String,
+    pub order_amount: u128,
+    pub fulfillment_unix: u64,
+    pub fulfillment_deadline_unix: u64,
+    pub settlement_unix: u64,
+    pub discount_pct: u8,
+    pub discount_expiration_unix: u64,
+    pub buyer_chain_id: u64,
+    pub buyer_commit_txid: String,
+    pub seller_chain_id: u64,
+    pub seller_accept_txid: String,
+    pub seller_fulfill_txid: String,
+    pub seller_claim_txid: String,
+    pub seller_refund_txid: String,
+    pub buyer_withdraw_txid: String,
+    /// Refunded plus withdrawn amount actually returned to the buyer
+    pub buyer_returned_amount: u128,
+    pub seller_block_height: u64,
+}
+
+impl ReceiptTypedData {
+    /// EIP-712 `hashStruct`
+    pub fn struct_hash(&self) -> [u8; 32] {
+        let uint = |v: u64| word(U256::from(v));
+        let string = |s: &str| keccak256(s).0;
+
+        let fields: [[u8; 32]; 18] = [
+            keccak256(RECEIPT_TYPE).0,
+            string(&self.session_id),
+            word(U256::from(self.order_amount)),
+            uint(self.fulfillment_unix),
+            uint(self.fulfillment_deadline_unix),
+            uint(self.settlement_unix),
+            uint(self.discount_pct.into()),
+            uint(self.discount_expiration_unix),
+            uint(self.buyer_chain_id),
+            string(&self.buyer_commit_txid),
+            uint(self.seller_chain_id),
+            string(&self.seller_accept_txid),
+            string(&self.seller_fulfill_txid),
+            string(&self.seller_claim_txid),
+            string(&self.seller_refund_txid),
+            string(&self.buyer_withdraw_txid),
+            word(U256::from(self.buyer_returned_amount)),
+            uint(self.seller_block_height),
+        ];
+        keccak256(fields.concat()).0
+    }
+
+    /// Digest to sign / verify under `domain`
+    pub fn eip712_digest(&self, domain: &Eip712Domain) -> [u8; 32] {
+        typed_data_digest(&domain.separator(), &self.struct_hash())
+    }
+}
+
+/// `keccak256(0x19 ‖ 0x01 ‖ domainSeparator ‖ structHash)`
+pub fn typed_data_digest(domain_separator: &[u8; 32], struct_hash: &[u8; 32]) -> [u8; 32] {
+    let mut enc = Vec::with_capacity(2 + 2 * 32);
+    enc.extend_from_slice(&[0x19, 0x01]);
+    enc.extend_from_slice(domain_separator);
+    enc.extend_from_slice(struct_hash);
+    keccak256(enc).0
+}
+
+fn word(value: U256) -> [u8; 32] {
+    value.to_be_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex32(s: &str) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).unwrap();
+        }
+        out
+    }
+
+    fn domain() -> Eip712Domain {
+        let vault = "0x5FbDB2315678afecb367f032d93F642f64180aa3"
+            .parse()
+            .unwrap();
+        Eip712Domain::new("CoreProver", "0.3", 369, vault)
+    }
+
+    fn receipt() -> ReceiptTypedData {
+        ReceiptTypedData {
+            session_id: "sess-1".into(),
+            order_amount: 1000,
+            fulfillment_unix: 1_731_600_000,
+            fulfillment_deadline_unix: 1_731_599_000,
+            settlement_unix: 1_731_600_100,
+            discount_pct: 0,
+            discount_expiration_unix: 0,
+            buyer_chain_id: 1,
+            buyer_commit_txid: "0xcommit".into(),
+            seller_chain_id: 369,
+            seller_accept_txid: "0xaccept".into(),
+            seller_fulfill_txid: "0xfulfill".into(),
+            seller_claim_txid: "0xclaim".into(),
+            seller_refund_txid: String::new(),
+            buyer_withdraw_txid: String::new(),
+            buyer_returned_amount: 0,
+            seller_block_height: 42,
+        }
+    }
+
+    #[test]
+    fn test_domain_separator_matches_eip712_example() {
+        // "Ether Mail" domain from the EIP-712 specification
+        let contract = "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+            .parse()
+            .unwrap();
+        let domain = Eip712Domain::new("Ether Mail", "1", 1, contract);
+        assert_eq!(
+            domain.separator(),
+            hex32("f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f")
+        );
+
+        // hashStruct(Mail) of the same example
+        let mail = hex32("c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e");
+        assert_eq!(
+            typed_data_digest(&domain.separator(), &mail),
+            hex32("be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2")
+        );
+    }
+
+    #[test]
+    fn test_receipt_digest_known_vector() {
+        assert_eq!(receipt().struct_hash(), hex32(STRUCT_HASH));
+        assert_eq!(receipt().eip712_digest(&domain()), hex32(DIGEST));
+    }
+
+    #[test]
+    fn test_digest_binds_domain_and_fields() {
+        let base = receipt().eip712_digest(&domain());
+
+        let mut other_chain = domain();
+        other_chain.chain_id = 1;
+        assert_ne!(receipt().eip712_digest(&other_chain), base);
+
+        let refunded = ReceiptTypedData {
+            seller_claim_txid: String::new(),
+            seller_refund_txid: "0xrefund".into(),
+            buyer_returned_amount: 1000,
+            ..receipt()
+        };
+        assert_ne!(refunded.eip712_digest(&domain()), base);
+    }
+
+    // Computed independently from RECEIPT_TYPE with a reference keccak256
+    const STRUCT_HASH: &str = "799764330c20bbbbbcb4a176b8acf7a53aa04d9aac027bf5cad569cf655f052f";
+    const DIGEST: &str = "e2e45efbb090e7a6dfeaef99962837312f366ebc6fb76198c03853f2368884cf";
+}
@@ -1,10 +1,12 @@
 //! CoreProver Bridge - Rust ↔ Solidity Integration
 
 pub mod client;
+pub mod eip712;
 pub mod types;
 pub mod events;
 
 pub use client::escrow_client::EscrowClient;
+pub use eip712::{Eip712Domain, ReceiptTypedData};
 pub use types::*;
 
 /// Bridge version
@@ -1,12 +1,18 @@
 //! API handlers
 
 use axum::{
-    extract::Path,
+    extract::{Path, State},
     http::StatusCode,
     response::IntoResponse,
     Json,
 };
 use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+use crate::engine::CoreProverEngine;
+
+/// Engine shared by the admin handlers
+pub type SharedEngine = Arc<Mutex<CoreProverEngine>>;
 
 /// Health check handler
 pub async fn health_check() -> impl IntoResponse {
@@ -43,6 +49,46 @@ pub async fn query_events() -> impl IntoResponse {
     Json(vec![] as Vec<EventResponse>)
 }
 
+/// Report whether new escrow commits are accepted
+pub async fn get_commit_status(State(engine): State<SharedEngine>) -> impl IntoResponse {
+    let accepting = engine.lock().unwrap().is_accepting_commits();
+    Json(CommitStatus { accepting })
+}
+
+/// Pause or resume new escrow commits (in-flight escrows are unaffected)
+pub async fn set_commit_status(
+    State(engine): State<SharedEngine>,
+    Json(payload): Json<CommitStatus>,
+) -> impl IntoResponse {
+    engine.lock().unwrap().set_accepting_commits(payload.accepting);
+    tracing::warn!("escrow commits accepting = {}", payload.accepting);
+    Json(payload)
+}
+
+/// Debug view of the engine's chain timing
+pub async fn get_config(State(engine): State<SharedEngine>) -> impl IntoResponse {
+    let engine = engine.lock().unwrap();
+    Json(ChainConfigResponse {
+        chain_id: engine.chain_id,
+        block_interval_secs: engine.block_interval_secs,
+        genesis_unix: engine.genesis_unix(),
+        current_block_height: engine.current_block_height,
+    })
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CommitStatus {
+    pub accepting: bool,
+}
+
+#[derive(Serialize)]
+struct ChainConfigResponse {
+    chain_id: u64,
+    block_interval_secs: u64,
+    genesis_unix: u64,
+    current_block_height: u64,
+}
+
 #[derive(Serialize)]
 struct HealthResponse {
     status: String,
@@ -3,4 +3,4 @@
 pub mod routes;
 pub mod handlers;
 
-pub use routes::create_router;
\ No newline at end of file
+pub use routes::{create_admin_router, create_router};
\ No newline at end of file
@@ -6,7 +6,7 @@ use axum::{
 };
 use tower_http::trace::TraceLayer;
 
-use super::handlers;
+use super::handlers::{self, SharedEngine};
 
 /// Create the API router
 pub fn create_router() -> Router {
@@ -16,4 +16,20 @@ pub fn create_router() -> Router {
         .route("/escrow", post(handlers::create_escrow))
         .route("/events", get(handlers::query_events))
         .layer(TraceLayer::new_for_http())
-}
\ No newline at end of file
+}
+
+/// Create the operator admin router
+///
+/// These routes are unauthenticated (the commit kill switch among them),
+/// so serve them on their own loopback listener, never merged into the
+/// public router. See `ServerConfig::admin_addr`.
+pub fn create_admin_router(engine: SharedEngine) -> Router {
+    Router::new()
+        .route(
+            "/admin/commits",
+            get(handlers::get_commit_status).post(handlers::set_commit_status),
+        )
+        .route("/config", get(handlers::get_config))
+        .with_state(engine)
+        .layer(TraceLayer::new_for_http())
+}
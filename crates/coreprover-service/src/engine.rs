@@ -2,9 +2,14 @@
 // CoreProver Engine (v0.3) — Borrow-Checker-Clean Version
 // ============================================================================
 
+use crate::receipt_sink::ReceiptSink;
+use crate::state_view::{StateView, StateViewReader};
 use crate::types::*;
 use chrono;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt;
+use std::sync::Arc;
 
 // ============================================================================
 // TimeTruth: deterministic triple-clock model
@@ -18,45 +23,237 @@ pub struct TimeTruth {
 }
 
 impl TimeTruth {
+    /// `iso` is left empty (and a warning logged) when `unix` is beyond
+    /// the range ISO 8601 rendering supports.
     pub fn new(mono: u64, unix: u64) -> Self {
-        let iso = iso8601(unix);
+        Self::with_offset(mono, unix, 0)
+    }
+
+    /// Same instant, ISO string rendered at a fixed UTC offset (minutes).
+    pub fn with_offset(mono: u64, unix: u64, offset_minutes: i32) -> Self {
+        let iso = iso8601_with_offset(unix, offset_minutes).unwrap_or_else(|e| {
+            tracing::warn!("{}", e);
+            String::new()
+        });
         Self { mono, unix, iso }
     }
 }
 
+// ============================================================================
+// EngineError
+// ============================================================================
+
+/// Why an engine operation was rejected. A rejected operation leaves the
+/// engine unchanged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EngineError {
+    EscrowNotFound,
+    /// `operation` is not allowed from the escrow's `current` state
+    InvalidState {
+        current: EscrowState,
+        operation: &'static str,
+    },
+    /// The named txid argument was empty
+    MissingTxid(&'static str),
+    /// The named window closed before the call
+    WindowExpired(&'static str),
+    /// The named window has not closed yet
+    WindowNotExpired(&'static str),
+    TimedReleaseDisabled,
+    /// The profile's `buyer_dispute_window_secs` is 0
+    ReclaimDisabled,
+    /// Late fulfillment re-locked the buyer's funds
+    WithdrawalLocked,
+    /// `set_accepting_commits(false)` is in effect
+    CommitsPaused,
+    /// `max_active_escrows` would be exceeded
+    EscrowLimitReached(usize),
+    /// An argument failed validation (amounts, parties, profile windows)
+    InvalidArgument(String),
+    /// Spec `index` of a `bulk_commit` batch failed; nothing was committed
+    BatchRejected {
+        index: usize,
+        source: Box<EngineError>,
+    },
+    /// No unsettled receipt stub for the order's current fulfillment
+    ReceiptStub(&'static str),
+    /// `merge_views` found the same order id in two shards
+    OrderIdCollision {
+        order_id: [u8; 32],
+        shards: (usize, usize),
+    },
+    ClockDesync(String),
+    /// The unix time cannot be rendered as an ISO 8601 date
+    TimestampOutOfRange(u64),
+    /// A snapshot or event log could not be restored
+    Restore(String),
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineError::EscrowNotFound => write!(f, "Escrow not found"),
+            EngineError::InvalidState { current, operation } => {
+                write!(f, "{} invalid in state {:?}", operation, current)
+            }
+            EngineError::MissingTxid(field) => write!(f, "{} is required", field),
+            EngineError::WindowExpired(window) => write!(f, "{} window expired", window),
+            EngineError::WindowNotExpired(window) => write!(f, "{} window not expired", window),
+            EngineError::TimedReleaseDisabled => write!(f, "timed_release disabled"),
+            EngineError::ReclaimDisabled => write!(f, "buyer_reclaim disabled"),
+            EngineError::WithdrawalLocked => {
+                write!(f, "buyer_withdraw locked: seller fulfilled after the deadline")
+            }
+            EngineError::CommitsPaused => {
+                write!(f, "commits paused: controller is not accepting new escrows")
+            }
+            EngineError::EscrowLimitReached(max) => {
+                write!(f, "active escrow limit reached ({})", max)
+            }
+            EngineError::InvalidArgument(msg) => write!(f, "{}", msg),
+            EngineError::BatchRejected { index, source } => {
+                write!(f, "bulk_commit rejected spec {}: {}", index, source)
+            }
+            EngineError::ReceiptStub(msg) => write!(f, "receipt stub {}", msg),
+            EngineError::OrderIdCollision { order_id, shards } => {
+                let hex: String = order_id.iter().map(|b| format!("{:02x}", b)).collect();
+                write!(f, "order 0x{} appears in shards {} and {}", hex, shards.0, shards.1)
+            }
+            EngineError::ClockDesync(msg) => write!(f, "clock desync: {}", msg),
+            EngineError::TimestampOutOfRange(unix) => {
+                write!(f, "unix timestamp {} out of range for ISO 8601", unix)
+            }
+            EngineError::Restore(msg) => write!(f, "restore failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for EngineError {}
+
 // ============================================================================
 // CoreProverEngine
 // ============================================================================
 
 pub struct CoreProverEngine {
-    escrows: Vec<Escrow>,
+    escrows: HashMap<[u8; 32], Escrow>,
+    // order ids in insertion order, for deterministic iteration
+    escrow_order: Vec<[u8; 32]>,
     receipts: Vec<ReceiptMetadata>,
     next_session_counter: u64,
+    // engine-wide operation log, oldest first; in-memory only
+    events: Vec<EngineEvent>,
 
     // deterministic clocks
     current_mono: u64,
     current_unix: u64,
+    genesis_unix: u64,
 
     // blockchain params
     pub chain_id: u64,
     pub block_interval_secs: u64,
     pub current_block_height: u64,
+
+    // policy
+    pub allow_self_dealing: bool,
+    accepting_commits: bool,
+    /// Cap on non-terminal escrows; None = unbounded
+    pub max_active_escrows: Option<usize>,
+    /// UTC offset (minutes) for rendered ISO timestamps; unix stays authoritative
+    pub iso_offset_minutes: i32,
+    /// Extra seconds past the claim window before `timed_release` may
+    /// auto-claim, absorbing watcher clock skew
+    pub timed_release_grace_secs: u64,
+    /// Floors on a committed profile's windows, so a seller-supplied profile
+    /// cannot expire before the other party can act (0 = no floor)
+    pub min_acceptance_window_secs: u64,
+    pub min_fulfillment_window_secs: u64,
+    pub min_claim_window_secs: u64,
+
+    // finalized-receipt feed
+    sinks: Vec<Box<dyn ReceiptSink>>,
+    // receives receipts removed by prune_terminal
+    archive_sink: Option<Box<dyn ReceiptSink>>,
+
+    // read path: copy-on-write state map, published to readers
+    states: Arc<HashMap<[u8; 32], EscrowState>>,
+    state_reader: StateViewReader,
 }
 
 impl CoreProverEngine {
     pub fn new(chain_id: u64, block_interval_secs: u64, genesis_unix: u64) -> Self {
         Self {
-            escrows: Vec::new(),
+            escrows: HashMap::new(),
+            escrow_order: Vec::new(),
             receipts: Vec::new(),
             next_session_counter: 1,
+            events: Vec::new(),
             current_mono: 0,
             current_unix: genesis_unix,
+            genesis_unix,
             chain_id,
             block_interval_secs,
             current_block_height: 1,
+            allow_self_dealing: false,
+            accepting_commits: true,
+            max_active_escrows: None,
+            iso_offset_minutes: 0,
+            timed_release_grace_secs: 0,
+            min_acceptance_window_secs: 0,
+            min_fulfillment_window_secs: 0,
+            min_claim_window_secs: 0,
+            sinks: Vec::new(),
+            archive_sink: None,
+            states: Arc::new(HashMap::new()),
+            state_reader: StateViewReader::default(),
         }
     }
 
+    /// Handle for contention-free state reads; see `StateView`.
+    pub fn state_reader(&self) -> StateViewReader {
+        self.state_reader.clone()
+    }
+
+    /// Sync one escrow's entry in the read-path map and publish it.
+    fn publish_state(&mut self, order_id: &[u8; 32]) {
+        let state = self.escrows.get(order_id).map(|e| e.state);
+        let states = Arc::make_mut(&mut self.states);
+        match state {
+            Some(state) => states.insert(*order_id, state),
+            None => states.remove(order_id),
+        };
+        self.state_reader.publish(StateView::new(self.states.clone()));
+    }
+
+    fn publish_all_states(&mut self) {
+        self.states = Arc::new(self.escrows.values().map(|e| (e.order_id, e.state)).collect());
+        self.state_reader.publish(StateView::new(self.states.clone()));
+    }
+
+    /// Kill switch: when false, `buyer_commit` is rejected while in-flight
+    /// escrows can still be accepted, fulfilled and settled.
+    pub fn set_accepting_commits(&mut self, accepting: bool) {
+        self.accepting_commits = accepting;
+    }
+
+    pub fn is_accepting_commits(&self) -> bool {
+        self.accepting_commits
+    }
+
+    /// Unix time at block 1 / mono 0
+    pub fn genesis_unix(&self) -> u64 {
+        self.genesis_unix
+    }
+
+    /// Register a sink to receive every receipt as it is finalized.
+    pub fn add_sink(&mut self, sink: Box<dyn ReceiptSink>) {
+        self.sinks.push(sink);
+    }
+
+    /// Sink that receives receipts of escrows removed by `prune_terminal`.
+    pub fn set_archive_sink(&mut self, sink: Box<dyn ReceiptSink>) {
+        self.archive_sink = Some(sink);
+    }
+
     // ------------------------------------------------------------------------
     // Time Advancement
     // ------------------------------------------------------------------------
@@ -64,47 +261,330 @@ impl CoreProverEngine {
     pub fn advance_time(&mut self, secs: u64) {
         self.current_mono += secs;
         self.current_unix += secs;
-        self.current_block_height += secs / self.block_interval_secs;
+        // derived from mono so sub-block advances accumulate
+        self.current_block_height = 1 + self.current_mono / self.block_interval_secs.max(1);
+    }
+
+    /// Mono and unix must have advanced by the same amount since genesis,
+    /// and block height must equal `1 + mono / block_interval_secs`.
+    pub fn verify_clock_consistency(&self) -> Result<(), EngineError> {
+        if self.block_interval_secs == 0 {
+            return Err(EngineError::ClockDesync("block_interval_secs must be > 0".into()));
+        }
+
+        let unix_advance = self
+            .current_unix
+            .checked_sub(self.genesis_unix)
+            .ok_or_else(|| EngineError::ClockDesync("unix is before genesis".into()))?;
+        if unix_advance != self.current_mono {
+            return Err(EngineError::ClockDesync(format!(
+                "unix advanced {}s since genesis but mono is {}",
+                unix_advance, self.current_mono
+            )));
+        }
+
+        let expected_height = 1 + self.current_mono / self.block_interval_secs;
+        if self.current_block_height != expected_height {
+            return Err(EngineError::ClockDesync(format!(
+                "block height {} but mono {} implies {}",
+                self.current_block_height, self.current_mono, expected_height
+            )));
+        }
+
+        Ok(())
     }
 
     fn now(&self) -> TimeTruth {
-        TimeTruth::new(self.current_mono, self.current_unix)
+        TimeTruth::with_offset(self.current_mono, self.current_unix, self.iso_offset_minutes)
+    }
+
+    fn log_event(&mut self, order_id: &[u8; 32], kind: EngineEventKind) {
+        self.events.push(EngineEvent {
+            order_id: *order_id,
+            kind,
+            mono: self.current_mono,
+            unix: self.current_unix,
+            block_height: self.current_block_height,
+        });
     }
 
     // ------------------------------------------------------------------------
     // Escrow Lookup Helpers
     // ------------------------------------------------------------------------
 
-    fn get_escrow(&self, order_id: &[u8; 32]) -> Result<&Escrow, String> {
+    fn get_escrow(&self, order_id: &[u8; 32]) -> Result<&Escrow, EngineError> {
         self.escrows
-            .iter()
-            .find(|e| &e.order_id == order_id)
-            .ok_or_else(|| "Escrow not found".to_string())
+            .get(order_id)
+            .ok_or(EngineError::EscrowNotFound)
     }
 
-    fn get_escrow_mut(&mut self, order_id: &[u8; 32]) -> Result<&mut Escrow, String> {
+    fn get_escrow_mut(&mut self, order_id: &[u8; 32]) -> Result<&mut Escrow, EngineError> {
         self.escrows
-            .iter_mut()
-            .find(|e| &e.order_id == order_id)
-            .ok_or_else(|| "Escrow not found".to_string())
+            .get_mut(order_id)
+            .ok_or(EngineError::EscrowNotFound)
+    }
+
+    /// Escrows in insertion order
+    fn escrows_in_order(&self) -> impl Iterator<Item = &Escrow> + '_ {
+        self.escrow_order.iter().filter_map(move |id| self.escrows.get(id))
+    }
+
+    fn insert_escrow(&mut self, escrow: Escrow) {
+        self.escrow_order.push(escrow.order_id);
+        self.escrows.insert(escrow.order_id, escrow);
+    }
+
+    /// Replace the whole store, keeping `escrows`' order as insertion order.
+    fn replace_escrows(&mut self, escrows: Vec<Escrow>) {
+        self.escrows.clear();
+        self.escrow_order.clear();
+        for escrow in escrows {
+            self.insert_escrow(escrow);
+        }
     }
 
-    fn generate_order_id(&mut self) -> [u8; 32] {
+    /// Bytes 0..8: the session counter (little-endian). Bytes 8..32: keccak
+    /// of the commit inputs, so ids stay deterministic for replays but do
+    /// not repeat across engines or counter resets.
+    fn generate_order_id(&mut self, buyer: &str, seller: &str, amount: u64) -> [u8; 32] {
+        use tiny_keccak::{Hasher, Keccak};
+
+        let mut hasher = Keccak::v256();
+        hasher.update(b"coreprover-order-v1");
+        hasher.update(&self.chain_id.to_be_bytes());
+        // Length prefixes keep ("ab","c") and ("a","bc") distinct
+        for part in [buyer, seller] {
+            hasher.update(&(part.len() as u64).to_be_bytes());
+            hasher.update(part.as_bytes());
+        }
+        hasher.update(&amount.to_be_bytes());
+        hasher.update(&self.current_unix.to_be_bytes());
+        let mut digest = [0u8; 32];
+        hasher.finalize(&mut digest);
+
         let mut id = [0u8; 32];
-        id[0] = (self.next_session_counter & 0xFF) as u8;
-        id[1] = ((self.next_session_counter >> 8) & 0xFF) as u8;
+        id[..8].copy_from_slice(&self.next_session_counter.to_le_bytes());
+        id[8..].copy_from_slice(&digest[..24]);
         self.next_session_counter += 1;
         id
     }
 }
 
+// ============================================================================
+// Engine Snapshots
+// ============================================================================
+
+/// Current snapshot format. Bump when `EngineState` changes incompatibly.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Persisted engine state. `body` is the exact JSON of the engine state and
+/// `checksum` is the hex keccak256 of those bytes, so any corruption of the
+/// body is caught on load. Receipt sinks are not persisted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EngineSnapshot {
+    pub version: u32,
+    pub checksum: String,
+    pub body: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EngineState {
+    escrows: Vec<Escrow>,
+    receipts: Vec<ReceiptMetadata>,
+    next_session_counter: u64,
+    current_mono: u64,
+    current_unix: u64,
+    #[serde(default)]
+    genesis_unix: Option<u64>,
+    chain_id: u64,
+    block_interval_secs: u64,
+    current_block_height: u64,
+    allow_self_dealing: bool,
+    accepting_commits: bool,
+    #[serde(default)]
+    max_active_escrows: Option<usize>,
+    #[serde(default)]
+    iso_offset_minutes: i32,
+    #[serde(default)]
+    timed_release_grace_secs: u64,
+    #[serde(default)]
+    min_acceptance_window_secs: u64,
+    #[serde(default)]
+    min_fulfillment_window_secs: u64,
+    #[serde(default)]
+    min_claim_window_secs: u64,
+}
+
+fn snapshot_checksum(body: &str) -> String {
+    use tiny_keccak::{Hasher, Keccak};
+
+    let mut hasher = Keccak::v256();
+    hasher.update(body.as_bytes());
+    let mut digest = [0u8; 32];
+    hasher.finalize(&mut digest);
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
 // ============================================================================
 // ISO8601 Utility
 // ============================================================================
-fn iso8601(unix: u64) -> String {
-    let dt = chrono::NaiveDateTime::from_timestamp_opt(unix as i64, 0)
-        .unwrap_or(chrono::NaiveDateTime::from_timestamp_opt(0, 0).unwrap());
-    chrono::DateTime::<chrono::Utc>::from_utc(dt, chrono::Utc).to_rfc3339()
+fn utc_datetime(unix: u64) -> Result<chrono::DateTime<chrono::Utc>, EngineError> {
+    i64::try_from(unix)
+        .ok()
+        .and_then(|secs| chrono::DateTime::<chrono::Utc>::from_timestamp(secs, 0))
+        .ok_or(EngineError::TimestampOutOfRange(unix))
+}
+
+fn iso8601(unix: u64) -> Result<String, EngineError> {
+    Ok(utc_datetime(unix)?.to_rfc3339())
+}
+
+/// Offset 0 renders exactly as `iso8601`. Out-of-range offsets fall back to UTC.
+fn iso8601_with_offset(unix: u64, offset_minutes: i32) -> Result<String, EngineError> {
+    if offset_minutes == 0 {
+        return iso8601(unix);
+    }
+    let Some(offset) = chrono::FixedOffset::east_opt(offset_minutes * 60) else {
+        return iso8601(unix);
+    };
+    Ok(utc_datetime(unix)?.with_timezone(&offset).to_rfc3339())
+}
+
+// ============================================================================
+// Transition Guards (shared by real operations and dry-run checks)
+// ============================================================================
+
+fn invalid_state(escrow: &Escrow, operation: &'static str) -> EngineError {
+    EngineError::InvalidState {
+        current: escrow.state,
+        operation,
+    }
+}
+
+fn guard_seller_accept(escrow: &Escrow, now: &TimeTruth) -> Result<(), EngineError> {
+    if escrow.state != EscrowState::BuyerCommitted {
+        return Err(invalid_state(escrow, "seller_accept"));
+    }
+    if now.mono > escrow.acceptance_deadline_mono {
+        return Err(EngineError::WindowExpired("acceptance"));
+    }
+    Ok(())
+}
+
+fn guard_seller_fulfill(escrow: &Escrow) -> Result<(), EngineError> {
+    if !escrow.state.can_fulfill() {
+        return Err(invalid_state(escrow, "seller_fulfill"));
+    }
+    Ok(())
+}
+
+fn guard_settlement(escrow: &Escrow, action: &'static str) -> Result<(), EngineError> {
+    if !matches!(escrow.state, EscrowState::SellerFulfilled | EscrowState::LateFulfilled) {
+        return Err(invalid_state(escrow, action));
+    }
+    Ok(())
+}
+
+/// Claims may also finish a partially claimed escrow; refunds may not.
+fn guard_claim(escrow: &Escrow, action: &'static str) -> Result<(), EngineError> {
+    if escrow.state == EscrowState::PartiallyClaimed {
+        return Ok(());
+    }
+    guard_settlement(escrow, action)
+}
+
+fn guard_buyer_withdraw(escrow: &Escrow, now: &TimeTruth) -> Result<(), EngineError> {
+    if escrow.state == EscrowState::LateFulfilled {
+        return Err(EngineError::WithdrawalLocked);
+    }
+    if !matches!(escrow.state, EscrowState::BuyerCommitted | EscrowState::FulfillmentExpired) {
+        return Err(invalid_state(escrow, "buyer_withdraw"));
+    }
+    if escrow.state == EscrowState::BuyerCommitted && now.mono <= escrow.acceptance_deadline_mono {
+        return Err(EngineError::WindowNotExpired("acceptance"));
+    }
+    Ok(())
+}
+
+/// Fulfilled, nothing claimed yet, and the seller let the buyer's dispute
+/// window (measured from fulfillment) lapse.
+fn guard_buyer_reclaim(escrow: &Escrow, now: &TimeTruth) -> Result<(), EngineError> {
+    let window = escrow.profile.timing.buyer_dispute_window_secs;
+    if window == 0 {
+        return Err(EngineError::ReclaimDisabled);
+    }
+    guard_settlement(escrow, "buyer_reclaim")?;
+    let fulfill_mono = escrow.fulfillment_mono.unwrap_or(0);
+    if now.mono.saturating_sub(fulfill_mono) < window {
+        return Err(EngineError::WindowNotExpired("buyer_dispute"));
+    }
+    Ok(())
+}
+
+/// (buyer refund, seller penalty) for a withdrawal in the escrow's current
+/// state. Acceptance timeout routes the configured penalty to the seller.
+fn withdrawal_split(escrow: &Escrow) -> (u64, u64) {
+    let penalty = if escrow.state == EscrowState::BuyerCommitted {
+        (escrow.amount as u128 * escrow.profile.acceptance_timeout_buyer_penalty_bps as u128
+            / 10_000) as u64
+    } else {
+        0
+    };
+    (escrow.amount - penalty, penalty)
+}
+
+/// The next deadline relevant to an escrow's current state:
+/// acceptance, fulfillment, or the claim window (timed release).
+fn next_deadline_mono(escrow: &Escrow) -> Option<u64> {
+    match escrow.state {
+        EscrowState::BuyerCommitted => Some(escrow.acceptance_deadline_mono),
+        EscrowState::SellerAccepted => escrow.fulfillment_deadline_mono,
+        EscrowState::SellerFulfilled
+        | EscrowState::LateFulfilled
+        | EscrowState::PartiallyClaimed => escrow
+            .fulfillment_mono
+            .map(|f| f + escrow.profile.timing.claim_window_secs),
+        _ => None,
+    }
+}
+
+// ============================================================================
+// Statistics
+// ============================================================================
+
+/// Nearest-rank percentiles of `values`, one per entry in `pcts` (0.0..=100.0).
+/// Returns an empty Vec when `values` is empty.
+pub fn percentiles(values: &[u64], pcts: &[f64]) -> Vec<u64> {
+    if values.is_empty() {
+        return Vec::new();
+    }
+
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    let n = sorted.len();
+
+    pcts.iter()
+        .map(|p| {
+            let rank = ((p.clamp(0.0, 100.0) / 100.0) * n as f64).ceil() as usize;
+            sorted[rank.clamp(1, n) - 1]
+        })
+        .collect()
+}
+
+/// Combined reporting view over several engine shards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergedView {
+    pub shard_count: usize,
+    pub escrow_count: usize,
+    /// Escrow count per state, in `EscrowState::ALL` order
+    pub state_counts: Vec<(EscrowState, usize)>,
+    /// Sum of amounts held by non-terminal escrows
+    pub total_value_locked: u64,
+    pub receipt_count: usize,
+    /// Receipts with a claim, refund or buyer withdraw txid
+    pub finalized_receipt_count: usize,
+    /// Settlement latencies of every shard, concatenated in shard order
+    pub settlement_latencies: Vec<u64>,
 }
 
 // ============================================================================
@@ -120,16 +600,25 @@ impl CoreProverEngine {
         profile: PaymentProfile,
         buyer_chain_id: u64,
         buyer_commit_txid: String,
-    ) -> Result<[u8; 32], String> {
+    ) -> Result<[u8; 32], EngineError> {
         let now = self.now();
 
-        if buyer_commit_txid.trim().is_empty() {
-            return Err("buyer_commit_txid is required".into());
+        if !self.accepting_commits {
+            return Err(EngineError::CommitsPaused);
         }
 
-        let order_id = self.generate_order_id();
+        if let Some(max) = self.max_active_escrows {
+            if self.active_escrow_count() >= max {
+                return Err(EngineError::EscrowLimitReached(max));
+            }
+        }
+
+        self.check_commit_parties(&buyer, &seller, &buyer_commit_txid)?;
+        self.check_profile(&profile)?;
+
+        let order_id = self.generate_order_id(&buyer, &seller, amount);
 
-        let escrow = Escrow::new(
+        let mut escrow = Escrow::new(
             order_id,
             buyer,
             seller,
@@ -139,11 +628,155 @@ impl CoreProverEngine {
             buyer_commit_txid,
             now.mono,
         );
+        escrow.events.push(escrow.committed_event());
 
-        self.escrows.push(escrow);
+        self.insert_escrow(escrow);
+        self.log_event(&order_id, EngineEventKind::Committed);
+        self.publish_state(&order_id);
         Ok(order_id)
     }
 
+    fn check_commit_parties(
+        &self,
+        buyer: &str,
+        seller: &str,
+        txid: &str,
+    ) -> Result<(), EngineError> {
+        if txid.trim().is_empty() {
+            return Err(EngineError::MissingTxid("buyer_commit_txid"));
+        }
+        self.check_not_self_dealing(buyer, seller)
+    }
+
+    fn check_not_self_dealing(&self, buyer: &str, seller: &str) -> Result<(), EngineError> {
+        if buyer == seller && !self.allow_self_dealing {
+            return Err(EngineError::InvalidArgument(
+                "buyer and seller must differ (self-dealing not allowed)".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_profile(&self, profile: &PaymentProfile) -> Result<(), EngineError> {
+        if profile.acceptance_timeout_buyer_penalty_bps as u32 > MAX_FEE_BPS {
+            return Err(EngineError::InvalidArgument(format!(
+                "acceptance timeout penalty of {} bps exceeds {} bps",
+                profile.acceptance_timeout_buyer_penalty_bps, MAX_FEE_BPS
+            )));
+        }
+
+        let t = &profile.timing;
+        let floors = [
+            ("acceptance", t.acceptance_window_secs, self.min_acceptance_window_secs),
+            ("fulfillment", t.fulfillment_window_secs, self.min_fulfillment_window_secs),
+            ("claim", t.claim_window_secs, self.min_claim_window_secs),
+        ];
+        for (name, secs, min) in floors {
+            if secs < min {
+                return Err(EngineError::InvalidArgument(format!(
+                    "{} window {}s is below the controller minimum of {}s",
+                    name, secs, min
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    // ============================================================================
+    // BUYER → Bulk Commit (seeding / migration)
+    // ============================================================================
+
+    /// Commit every spec or none: all specs are validated up front, and any
+    /// failure while committing rolls the whole batch back.
+    pub fn bulk_commit(&mut self, specs: Vec<CommitSpec>) -> Result<Vec<[u8; 32]>, EngineError> {
+        if !self.accepting_commits {
+            return Err(EngineError::CommitsPaused);
+        }
+
+        if let Some(max) = self.max_active_escrows {
+            if self.active_escrow_count() + specs.len() > max {
+                return Err(EngineError::EscrowLimitReached(max));
+            }
+        }
+
+        let rejected = |index: usize, e: EngineError| EngineError::BatchRejected {
+            index,
+            source: Box::new(e),
+        };
+
+        for (i, spec) in specs.iter().enumerate() {
+            self.check_commit_parties(&spec.buyer, &spec.seller, &spec.buyer_commit_txid)
+                .and_then(|_| self.check_profile(&spec.profile))
+                .map_err(|e| rejected(i, e))?;
+            if specs[..i].iter().any(|s| s.buyer_commit_txid == spec.buyer_commit_txid) {
+                let duplicate = "duplicate buyer_commit_txid in batch".to_string();
+                return Err(rejected(i, EngineError::InvalidArgument(duplicate)));
+            }
+        }
+
+        let escrow_len = self.escrow_order.len();
+        let events_len = self.events.len();
+        let counter = self.next_session_counter;
+
+        let mut order_ids = Vec::with_capacity(specs.len());
+        for spec in specs {
+            let result = self.buyer_commit(
+                spec.buyer,
+                spec.seller,
+                spec.amount,
+                spec.profile,
+                spec.buyer_chain_id,
+                spec.buyer_commit_txid,
+            );
+            match result {
+                Ok(id) => order_ids.push(id),
+                Err(e) => {
+                    for id in self.escrow_order.split_off(escrow_len) {
+                        self.escrows.remove(&id);
+                    }
+                    self.next_session_counter = counter;
+                    self.events.truncate(events_len);
+                    self.publish_all_states();
+                    return Err(rejected(order_ids.len(), e));
+                }
+            }
+        }
+
+        Ok(order_ids)
+    }
+
+    // ============================================================================
+    // MARKETPLACE → Reassign Seller (before acceptance only)
+    // ============================================================================
+
+    pub fn reassign_seller(
+        &mut self,
+        order_id: &[u8; 32],
+        new_seller: String,
+    ) -> Result<(), EngineError> {
+        let now = self.now();
+        let escrow = self.get_escrow(order_id)?;
+
+        if escrow.state != EscrowState::BuyerCommitted {
+            return Err(invalid_state(escrow, "reassign_seller"));
+        }
+        if new_seller.trim().is_empty() {
+            return Err(EngineError::InvalidArgument("new_seller is required".into()));
+        }
+        self.check_not_self_dealing(&escrow.buyer, &new_seller)?;
+
+        let escrow = self.get_escrow_mut(order_id)?;
+        let previous = std::mem::replace(&mut escrow.seller, new_seller.clone());
+        escrow.events.push(EscrowEvent::SellerReassigned {
+            from: previous,
+            to: new_seller,
+            at_mono: now.mono,
+        });
+
+        self.log_event(order_id, EngineEventKind::SellerReassigned);
+        Ok(())
+    }
+
     // ============================================================================
     // SELLER → Accept
     // ============================================================================
@@ -152,26 +785,46 @@ impl CoreProverEngine {
         &mut self,
         order_id: &[u8; 32],
         seller_accept_txid: String,
-    ) -> Result<(), String> {
+    ) -> Result<(), EngineError> {
+        self.seller_accept_with_fee(order_id, seller_accept_txid, 0)
+    }
+
+    /// Accept with the controller fee from the negotiated offer, in basis
+    /// points of the amount `seller_claim` releases
+    pub fn seller_accept_with_fee(
+        &mut self,
+        order_id: &[u8; 32],
+        seller_accept_txid: String,
+        fee_bps: u32,
+    ) -> Result<(), EngineError> {
+        if fee_bps > MAX_FEE_BPS {
+            return Err(EngineError::InvalidArgument(format!(
+                "fee of {} bps exceeds {} bps",
+                fee_bps, MAX_FEE_BPS
+            )));
+        }
+
         let now = self.now();
         let chain_id = self.chain_id; // <-- extract BEFORE borrow
 
         {
             let escrow = self.get_escrow_mut(order_id)?;
 
-            if escrow.state != EscrowState::BuyerCommitted {
-                return Err("seller_accept only valid from BuyerCommitted".into());
-            }
+            guard_seller_accept(escrow, &now)?;
             if seller_accept_txid.trim().is_empty() {
-                return Err("seller_accept_txid is required".into());
-            }
-            if now.mono > escrow.acceptance_deadline_mono {
-                return Err("acceptance window expired".into());
+                return Err(EngineError::MissingTxid("seller_accept_txid"));
             }
 
             escrow.seller_chain_id = chain_id;
+            escrow.fee_bps = fee_bps;
             escrow.seller_accept_mono = Some(now.mono);
-            escrow.seller_accept_txid = Some(seller_accept_txid);
+            escrow.seller_accept_txid = Some(seller_accept_txid.clone());
+            escrow.events.push(EscrowEvent::SellerAccepted {
+                seller_chain_id: chain_id,
+                txid: seller_accept_txid,
+                fee_bps,
+                at_mono: now.mono,
+            });
 
             escrow.fulfillment_deadline_mono =
                 Some(now.mono + escrow.profile.timing.fulfillment_window_secs);
@@ -179,6 +832,8 @@ impl CoreProverEngine {
             escrow.state = EscrowState::SellerAccepted;
         }
 
+        self.log_event(order_id, EngineEventKind::Accepted);
+        self.publish_state(order_id);
         Ok(())
     }
 
@@ -190,19 +845,17 @@ impl CoreProverEngine {
         &mut self,
         order_id: &[u8; 32],
         seller_fulfill_txid: String,
-    ) -> Result<(), String> {
+    ) -> Result<(), EngineError> {
         let now = self.now();
         let mut is_late = false;
 
         {
             let escrow = self.get_escrow_mut(order_id)?;
 
-            if !escrow.state.can_fulfill() {
-                return Err(format!("seller_fulfill invalid in state {:?}", escrow.state));
-            }
+            guard_seller_fulfill(escrow)?;
 
             if seller_fulfill_txid.trim().is_empty() {
-                return Err("seller_fulfill_txid is required".into());
+                return Err(EngineError::MissingTxid("seller_fulfill_txid"));
             }
 
             is_late = match escrow.fulfillment_deadline_mono {
@@ -211,16 +864,22 @@ impl CoreProverEngine {
             };
 
             escrow.fulfillment_mono = Some(now.mono);
-            escrow.seller_fulfill_txid = Some(seller_fulfill_txid);
+            escrow.seller_fulfill_txid = Some(seller_fulfill_txid.clone());
+            escrow.events.push(EscrowEvent::SellerFulfilled {
+                txid: seller_fulfill_txid,
+                at_mono: now.mono,
+            });
 
             escrow.state = if is_late {
-                EscrowState::FulfillmentExpired
+                EscrowState::LateFulfilled
             } else {
                 EscrowState::SellerFulfilled
             };
         }
 
         self.create_receipt_stub(order_id, is_late)?;
+        self.log_event(order_id, EngineEventKind::Fulfilled);
+        self.publish_state(order_id);
         Ok(())
     }
 
@@ -232,7 +891,7 @@ impl CoreProverEngine {
         &mut self,
         order_id: &[u8; 32],
         is_late: bool,
-    ) -> Result<(), String> {
+    ) -> Result<(), EngineError> {
         let now = self.now();
         let escrow = self.get_escrow(order_id)?;
 
@@ -242,6 +901,12 @@ impl CoreProverEngine {
             0
         };
 
+        // mono and unix advance together, so deadlines map across by offset
+        let fulfillment_deadline_unix = escrow
+            .fulfillment_deadline_mono
+            .map(|d| now.unix - now.mono + d)
+            .unwrap_or(0);
+
         let discount_expiration_unix = if late_discount > 0 {
             now.unix + escrow.profile.discount_expiration_days * 86400
         } else {
@@ -254,6 +919,7 @@ impl CoreProverEngine {
             fulfillment_mono: escrow.fulfillment_mono.unwrap_or(now.mono),
             fulfillment_unix: now.unix,
             fulfillment_iso: now.iso.clone(),
+            fulfillment_deadline_unix,
             settlement_mono: 0,
             settlement_unix: 0,
             settlement_iso: "".into(),
@@ -267,7 +933,15 @@ impl CoreProverEngine {
             seller_fulfill_txid: escrow.seller_fulfill_txid.clone().unwrap_or_default(),
             seller_claim_txid: None,
             seller_refund_txid: None,
+            partial_claim_txids: Vec::new(),
             buyer_withdraw_txid: None,
+            buyer_refund_amount: 0,
+            seller_penalty_amount: 0,
+            seller_amount: 0,
+            fee_amount: 0,
+            decline_reason: None,
+            dispute_evidence_hash: None,
+            dispute_resolver: None,
             seller_block_height: 0,
         };
 
@@ -279,37 +953,133 @@ impl CoreProverEngine {
     // SELLER → Claim
     // ============================================================================
 
+    /// Release the unclaimed balance, minus the controller fee agreed at
+    /// accept (see `record_payout`).
     pub fn seller_claim(
         &mut self,
         order_id: &[u8; 32],
         seller_claim_txid: String,
-    ) -> Result<u64, String> {
+    ) -> Result<Settlement, EngineError> {
         let now = self.now();
         let block_height = self.current_block_height; // extract BEFORE borrow
-        let amount;
+        let settlement;
 
         {
             let escrow = self.get_escrow_mut(order_id)?;
 
-            if !matches!(escrow.state, EscrowState::SellerFulfilled | EscrowState::FulfillmentExpired)
-            {
-                return Err("seller_claim only valid after fulfillment".into());
-            }
+            guard_claim(escrow, "seller_claim")?;
 
             if seller_claim_txid.trim().is_empty() {
-                return Err("seller_claim_txid is required".into());
+                return Err(EngineError::MissingTxid("seller_claim_txid"));
             }
 
-            escrow.seller_claim_txid = Some(seller_claim_txid);
+            // Only what partial claims have not yet released
+            settlement = Settlement::split(escrow.amount - escrow.claimed_so_far, escrow.fee_bps);
+            escrow.claimed_so_far = escrow.amount;
+            escrow.seller_claim_txid = Some(seller_claim_txid.clone());
             escrow.settlement_mono = Some(now.mono);
             escrow.seller_block_height = Some(block_height);
             escrow.state = EscrowState::SellerClaimed;
-
-            amount = escrow.amount;
+            escrow.events.push(EscrowEvent::SellerClaimed {
+                txid: seller_claim_txid,
+                block_height,
+                at_mono: now.mono,
+            });
         }
 
+        self.record_payout(order_id, settlement)?;
         self.finalize_receipt(order_id, false)?;
-        Ok(amount)
+        self.log_event(order_id, EngineEventKind::Claimed);
+        self.publish_state(order_id);
+        Ok(settlement)
+    }
+
+    /// [`seller_claim`](Self::seller_claim) for callers that only need what
+    /// the seller receives
+    pub fn seller_claim_amount(
+        &mut self,
+        order_id: &[u8; 32],
+        seller_claim_txid: String,
+    ) -> Result<u64, EngineError> {
+        self.seller_claim(order_id, seller_claim_txid)
+            .map(|settlement| settlement.seller_amount)
+    }
+
+    // ============================================================================
+    // SELLER → Partial Claim
+    // ============================================================================
+
+    /// Claim `amount` of the unclaimed balance. The escrow stays
+    /// PartiallyClaimed until the full amount is claimed, then becomes
+    /// SellerClaimed and its receipt is finalized. Every partial txid is
+    /// recorded on the receipt, and each partial amount pays the controller
+    /// fee. Returns the balance still unclaimed.
+    pub fn seller_claim_partial(
+        &mut self,
+        order_id: &[u8; 32],
+        amount: u64,
+        seller_claim_txid: String,
+    ) -> Result<u64, EngineError> {
+        let now = self.now();
+        let block_height = self.current_block_height;
+        let remaining;
+        let settlement;
+
+        {
+            let escrow = self.get_escrow_mut(order_id)?;
+
+            guard_claim(escrow, "seller_claim_partial")?;
+
+            if seller_claim_txid.trim().is_empty() {
+                return Err(EngineError::MissingTxid("seller_claim_txid"));
+            }
+
+            let unclaimed = escrow.amount - escrow.claimed_so_far;
+            if amount == 0 {
+                return Err(EngineError::InvalidArgument(
+                    "partial claim amount must be positive".into(),
+                ));
+            }
+            if amount > unclaimed {
+                return Err(EngineError::InvalidArgument(format!(
+                    "partial claim of {} exceeds unclaimed balance {}",
+                    amount, unclaimed
+                )));
+            }
+
+            remaining = unclaimed - amount;
+            settlement = Settlement::split(amount, escrow.fee_bps);
+            escrow.claimed_so_far += amount;
+            if remaining == 0 {
+                escrow.seller_claim_txid = Some(seller_claim_txid.clone());
+                escrow.settlement_mono = Some(now.mono);
+                escrow.seller_block_height = Some(block_height);
+                escrow.state = EscrowState::SellerClaimed;
+            } else {
+                escrow.state = EscrowState::PartiallyClaimed;
+            }
+            escrow.events.push(EscrowEvent::SellerClaimedPartial {
+                txid: seller_claim_txid.clone(),
+                amount,
+                block_height,
+                at_mono: now.mono,
+            });
+        }
+
+        let (fulfillment_mono, fulfill_txid) = {
+            let escrow = self.get_escrow(order_id)?;
+            (escrow.fulfillment_mono, escrow.seller_fulfill_txid.clone())
+        };
+        let idx = self.receipt_stub_index(order_id, fulfillment_mono, fulfill_txid.as_deref())?;
+        self.receipts[idx].partial_claim_txids.push(seller_claim_txid);
+        self.record_payout(order_id, settlement)?;
+
+        if remaining == 0 {
+            self.finalize_receipt(order_id, false)?;
+        }
+        self.log_event(order_id, EngineEventKind::PartiallyClaimed);
+        self.publish_state(order_id);
+        Ok(remaining)
     }
 
     // ============================================================================
@@ -320,7 +1090,7 @@ impl CoreProverEngine {
         &mut self,
         order_id: &[u8; 32],
         seller_refund_txid: String,
-    ) -> Result<u64, String> {
+    ) -> Result<u64, EngineError> {
         let now = self.now();
         let block_height = self.current_block_height;
         let amount;
@@ -328,24 +1098,28 @@ impl CoreProverEngine {
         {
             let escrow = self.get_escrow_mut(order_id)?;
 
-            if !matches!(escrow.state, EscrowState::SellerFulfilled | EscrowState::FulfillmentExpired)
-            {
-                return Err("seller_refund only valid after fulfillment".into());
-            }
+            guard_settlement(escrow, "seller_refund")?;
 
             if seller_refund_txid.trim().is_empty() {
-                return Err("seller_refund_txid is required".into());
+                return Err(EngineError::MissingTxid("seller_refund_txid"));
             }
 
-            escrow.seller_refund_txid = Some(seller_refund_txid);
+            escrow.seller_refund_txid = Some(seller_refund_txid.clone());
             escrow.settlement_mono = Some(now.mono);
             escrow.seller_block_height = Some(block_height);
             escrow.state = EscrowState::SellerRefunded;
+            escrow.events.push(EscrowEvent::SellerRefunded {
+                txid: seller_refund_txid,
+                block_height,
+                at_mono: now.mono,
+            });
 
             amount = escrow.amount;
         }
 
         self.finalize_receipt(order_id, true)?;
+        self.log_event(order_id, EngineEventKind::Refunded);
+        self.publish_state(order_id);
         Ok(amount)
     }
 
@@ -357,23 +1131,19 @@ impl CoreProverEngine {
         &mut self,
         order_id: &[u8; 32],
         buyer_withdraw_txid: Option<String>,
-    ) -> Result<u64, String> {
+    ) -> Result<u64, EngineError> {
         let now = self.now();
-        let amount;
+        let refund;
+        let penalty;
+        let acceptance_timeout;
 
         {
             let escrow = self.get_escrow_mut(order_id)?;
 
-            if !matches!(escrow.state, EscrowState::BuyerCommitted | EscrowState::FulfillmentExpired)
-            {
-                return Err("buyer_withdraw not allowed".into());
-            }
+            guard_buyer_withdraw(escrow, &now)?;
 
-            if escrow.state == EscrowState::BuyerCommitted
-                && now.mono <= escrow.acceptance_deadline_mono
-            {
-                return Err("buyer_withdraw not yet allowed".into());
-            }
+            acceptance_timeout = escrow.state == EscrowState::BuyerCommitted;
+            (refund, penalty) = withdrawal_split(escrow);
 
             if let Some(tx) = buyer_withdraw_txid {
                 escrow.buyer_withdraw_txid = Some(tx);
@@ -382,58 +1152,428 @@ impl CoreProverEngine {
             escrow.state = EscrowState::BuyerWithdrawn;
             escrow.settlement_mono = Some(now.mono);
             escrow.seller_block_height = None;
+            escrow.events.push(EscrowEvent::BuyerWithdrew {
+                txid: escrow.buyer_withdraw_txid.clone(),
+                at_mono: now.mono,
+            });
+        }
 
-            amount = escrow.amount;
+        if acceptance_timeout {
+            self.create_withdrawal_receipt(order_id, refund, penalty)?;
         }
 
-        Ok(amount)
+        self.log_event(order_id, EngineEventKind::Withdrew);
+        self.publish_state(order_id);
+        Ok(refund)
     }
 
     // ============================================================================
-    // TIMED RELEASE
+    // BUYER → Reclaim (fulfilled, unclaimed, dispute window lapsed)
     // ============================================================================
 
-    pub fn timed_release(&mut self, order_id: &[u8; 32]) -> Result<u64, String> {
+    pub fn buyer_reclaim(
+        &mut self,
+        order_id: &[u8; 32],
+        buyer_reclaim_txid: String,
+    ) -> Result<u64, EngineError> {
         let now = self.now();
-        let block_height = self.current_block_height;
         let amount;
 
         {
             let escrow = self.get_escrow_mut(order_id)?;
 
-            if !escrow.profile.allows_timed_release {
-                return Err("timed_release disabled".into());
-            }
-
-            if !matches!(escrow.state, EscrowState::SellerFulfilled | EscrowState::FulfillmentExpired)
-            {
-                return Err("timed_release only after fulfillment".into());
-            }
-
-            let fulfill_mono = escrow.fulfillment_mono.unwrap_or(0);
-            let elapsed = now.mono.saturating_sub(fulfill_mono);
+            guard_buyer_reclaim(escrow, &now)?;
 
-            if elapsed < escrow.profile.timing.claim_window_secs {
-                return Err("claim window not expired".into());
+            if buyer_reclaim_txid.trim().is_empty() {
+                return Err(EngineError::MissingTxid("buyer_reclaim_txid"));
             }
 
-            escrow.seller_claim_txid = Some(format!("auto_claim_{}", now.mono));
-            escrow.settlement_mono = Some(now.mono);
-            escrow.seller_block_height = Some(block_height);
-            escrow.state = EscrowState::SellerClaimed;
-
             amount = escrow.amount;
+            escrow.buyer_withdraw_txid = Some(buyer_reclaim_txid.clone());
+            escrow.settlement_mono = Some(now.mono);
+            escrow.seller_block_height = None;
+            escrow.state = EscrowState::BuyerReclaimed;
+            escrow.events.push(EscrowEvent::BuyerReclaimed {
+                txid: buyer_reclaim_txid,
+                at_mono: now.mono,
+            });
         }
 
-        self.finalize_receipt(order_id, false)?;
+        self.finalize_receipt(order_id, true)?;
+        self.log_event(order_id, EngineEventKind::Reclaimed);
+        self.publish_state(order_id);
+        Ok(amount)
+    }
+
+    // ============================================================================
+    // Withdrawal Receipt (acceptance timeout, no fulfillment stub exists)
+    // ============================================================================
+
+    fn create_withdrawal_receipt(
+        &mut self,
+        order_id: &[u8; 32],
+        refund: u64,
+        penalty: u64,
+    ) -> Result<(), EngineError> {
+        let now = self.now();
+        let escrow = self.get_escrow(order_id)?;
+
+        let meta = ReceiptMetadata {
+            session_id: escrow.order_id,
+            order_amount: escrow.amount as u128,
+            fulfillment_mono: 0,
+            fulfillment_unix: 0,
+            fulfillment_iso: "".into(),
+            fulfillment_deadline_unix: 0,
+            settlement_mono: escrow.settlement_mono.unwrap_or(now.mono),
+            settlement_unix: now.unix,
+            settlement_iso: now.iso.clone(),
+            late_fulfilled: false,
+            discount_pct: 0,
+            discount_expiration_unix: 0,
+            buyer_chain_id: escrow.buyer_chain_id,
+            buyer_commit_txid: escrow.buyer_commit_txid.clone(),
+            seller_chain_id: escrow.seller_chain_id,
+            seller_accept_txid: "".into(),
+            seller_fulfill_txid: "".into(),
+            seller_claim_txid: None,
+            seller_refund_txid: None,
+            partial_claim_txids: Vec::new(),
+            buyer_withdraw_txid: escrow.buyer_withdraw_txid.clone(),
+            buyer_refund_amount: refund as u128,
+            seller_penalty_amount: penalty as u128,
+            seller_amount: 0,
+            fee_amount: 0,
+            decline_reason: None,
+            dispute_evidence_hash: None,
+            dispute_resolver: None,
+            seller_block_height: 0,
+        };
+
+        for sink in self.sinks.iter_mut() {
+            sink.emit(&meta);
+        }
+        self.receipts.push(meta);
+        Ok(())
+    }
+
+    // ============================================================================
+    // SELLER → Decline (accepted, before fulfillment)
+    // ============================================================================
+
+    /// Seller gives up an accepted order instead of letting the fulfillment
+    /// window lapse. The buyer is refunded in full immediately and the
+    /// reason is kept on the receipt.
+    pub fn seller_decline(
+        &mut self,
+        order_id: &[u8; 32],
+        seller_refund_txid: String,
+        reason: String,
+    ) -> Result<u64, EngineError> {
+        let now = self.now();
+        let block_height = self.current_block_height;
+        let amount;
+
+        {
+            let escrow = self.get_escrow_mut(order_id)?;
+
+            if escrow.state != EscrowState::SellerAccepted {
+                return Err(invalid_state(escrow, "seller_decline"));
+            }
+
+            if seller_refund_txid.trim().is_empty() {
+                return Err(EngineError::MissingTxid("seller_refund_txid"));
+            }
+
+            escrow.seller_refund_txid = Some(seller_refund_txid.clone());
+            escrow.settlement_mono = Some(now.mono);
+            escrow.seller_block_height = Some(block_height);
+            escrow.state = EscrowState::SellerRefunded;
+            escrow.events.push(EscrowEvent::SellerRefunded {
+                txid: seller_refund_txid,
+                block_height,
+                at_mono: now.mono,
+            });
+
+            amount = escrow.amount;
+        }
+
+        self.create_decline_receipt(order_id, reason)?;
+        self.log_event(order_id, EngineEventKind::Declined);
+        self.publish_state(order_id);
+        Ok(amount)
+    }
+
+    fn create_decline_receipt(
+        &mut self,
+        order_id: &[u8; 32],
+        reason: String,
+    ) -> Result<(), EngineError> {
+        let now = self.now();
+        let escrow = self.get_escrow(order_id)?;
+
+        let meta = ReceiptMetadata {
+            session_id: escrow.order_id,
+            order_amount: escrow.amount as u128,
+            fulfillment_mono: 0,
+            fulfillment_unix: 0,
+            fulfillment_iso: "".into(),
+            fulfillment_deadline_unix: 0,
+            settlement_mono: escrow.settlement_mono.unwrap_or(now.mono),
+            settlement_unix: now.unix,
+            settlement_iso: now.iso.clone(),
+            late_fulfilled: false,
+            discount_pct: 0,
+            discount_expiration_unix: 0,
+            buyer_chain_id: escrow.buyer_chain_id,
+            buyer_commit_txid: escrow.buyer_commit_txid.clone(),
+            seller_chain_id: escrow.seller_chain_id,
+            seller_accept_txid: escrow.seller_accept_txid.clone().unwrap_or_default(),
+            seller_fulfill_txid: "".into(),
+            seller_claim_txid: None,
+            seller_refund_txid: escrow.seller_refund_txid.clone(),
+            partial_claim_txids: Vec::new(),
+            buyer_withdraw_txid: None,
+            buyer_refund_amount: escrow.amount as u128,
+            seller_penalty_amount: 0,
+            seller_amount: 0,
+            fee_amount: 0,
+            decline_reason: Some(reason),
+            dispute_evidence_hash: None,
+            dispute_resolver: None,
+            seller_block_height: escrow.seller_block_height.unwrap_or(0),
+        };
+
+        for sink in self.sinks.iter_mut() {
+            sink.emit(&meta);
+        }
+        self.receipts.push(meta);
+        Ok(())
+    }
+
+    // ============================================================================
+    // DISPUTES (after fulfillment, before settlement)
+    // ============================================================================
+
+    /// Freeze a fulfilled order until `resolve_dispute`. Claims, refunds
+    /// and `timed_release` are rejected while the dispute is open.
+    pub fn open_dispute(
+        &mut self,
+        order_id: &[u8; 32],
+        opener: Party,
+        evidence_hash: [u8; 32],
+    ) -> Result<(), EngineError> {
+        let now = self.now();
+
+        {
+            let escrow = self.get_escrow_mut(order_id)?;
+
+            if !matches!(escrow.state, EscrowState::SellerFulfilled | EscrowState::LateFulfilled) {
+                return Err(invalid_state(escrow, "open_dispute"));
+            }
+
+            escrow.dispute_opener = Some(opener);
+            escrow.dispute_evidence_hash = Some(evidence_hash);
+            escrow.state = EscrowState::Disputed;
+            escrow.events.push(EscrowEvent::DisputeOpened {
+                opener,
+                evidence_hash,
+                at_mono: now.mono,
+            });
+        }
+
+        self.log_event(order_id, EngineEventKind::DisputeOpened);
+        self.publish_state(order_id);
+        Ok(())
+    }
+
+    /// Settle a disputed order as a seller claim or a buyer refund. The
+    /// evidence hash and `resolver` are recorded on the receipt. Returns
+    /// what the seller receives after the fee, or the amount refunded.
+    pub fn resolve_dispute(
+        &mut self,
+        order_id: &[u8; 32],
+        outcome: DisputeOutcome,
+        resolver: String,
+        txid: String,
+    ) -> Result<u64, EngineError> {
+        let now = self.now();
+        let block_height = self.current_block_height;
+        let amount;
+        let payout;
+        let evidence_hash;
+        let stub_key;
+
+        {
+            let escrow = self.get_escrow_mut(order_id)?;
+
+            if escrow.state != EscrowState::Disputed {
+                return Err(invalid_state(escrow, "resolve_dispute"));
+            }
+            if resolver.trim().is_empty() {
+                return Err(EngineError::InvalidArgument("dispute resolver is required".into()));
+            }
+            if txid.trim().is_empty() {
+                return Err(EngineError::MissingTxid("dispute txid"));
+            }
+
+            match outcome {
+                DisputeOutcome::PaySeller => {
+                    payout = Some(Settlement::split(
+                        escrow.amount - escrow.claimed_so_far,
+                        escrow.fee_bps,
+                    ));
+                    escrow.claimed_so_far = escrow.amount;
+                    escrow.seller_claim_txid = Some(txid.clone());
+                    escrow.state = EscrowState::SellerClaimed;
+                }
+                DisputeOutcome::RefundBuyer => {
+                    payout = None;
+                    escrow.seller_refund_txid = Some(txid.clone());
+                    escrow.state = EscrowState::SellerRefunded;
+                }
+            }
+            escrow.settlement_mono = Some(now.mono);
+            escrow.seller_block_height = Some(block_height);
+            escrow.events.push(EscrowEvent::DisputeResolved {
+                outcome,
+                resolver: resolver.clone(),
+                txid,
+                block_height,
+                at_mono: now.mono,
+            });
+
+            amount = payout.map_or(escrow.amount, |p| p.seller_amount);
+            evidence_hash = escrow.dispute_evidence_hash;
+            stub_key = (escrow.fulfillment_mono, escrow.seller_fulfill_txid.clone());
+        }
+
+        let idx = self.receipt_stub_index(order_id, stub_key.0, stub_key.1.as_deref())?;
+        self.receipts[idx].dispute_evidence_hash = evidence_hash;
+        self.receipts[idx].dispute_resolver = Some(resolver);
+        if let Some(settlement) = payout {
+            self.record_payout(order_id, settlement)?;
+        }
+
+        self.finalize_receipt(order_id, outcome == DisputeOutcome::RefundBuyer)?;
+        self.log_event(order_id, EngineEventKind::DisputeResolved);
+        self.publish_state(order_id);
         Ok(amount)
     }
 
+    // ============================================================================
+    // TIMED RELEASE
+    // ============================================================================
+
+    pub fn timed_release(&mut self, order_id: &[u8; 32]) -> Result<u64, EngineError> {
+        let now = self.now();
+        let block_height = self.current_block_height;
+        let grace = self.timed_release_grace_secs;
+        let settlement;
+
+        {
+            let escrow = self.get_escrow_mut(order_id)?;
+
+            if !escrow.profile.allows_timed_release {
+                return Err(EngineError::TimedReleaseDisabled);
+            }
+
+            if !matches!(
+                escrow.state,
+                EscrowState::SellerFulfilled
+                    | EscrowState::LateFulfilled
+                    | EscrowState::PartiallyClaimed
+            ) {
+                return Err(invalid_state(escrow, "timed_release"));
+            }
+
+            let fulfill_mono = escrow.fulfillment_mono.unwrap_or(0);
+            let elapsed = now.mono.saturating_sub(fulfill_mono);
+
+            if elapsed < escrow.profile.timing.claim_window_secs {
+                return Err(EngineError::WindowNotExpired("claim"));
+            }
+            if elapsed < escrow.profile.timing.claim_window_secs + grace {
+                return Err(EngineError::WindowNotExpired("timed_release grace"));
+            }
+
+            let auto_txid = format!("auto_claim_{}", now.mono);
+            settlement = Settlement::split(escrow.amount - escrow.claimed_so_far, escrow.fee_bps);
+            escrow.claimed_so_far = escrow.amount;
+            escrow.seller_claim_txid = Some(auto_txid.clone());
+            escrow.settlement_mono = Some(now.mono);
+            escrow.seller_block_height = Some(block_height);
+            escrow.state = EscrowState::SellerClaimed;
+            escrow.events.push(EscrowEvent::SellerClaimed {
+                txid: auto_txid,
+                block_height,
+                at_mono: now.mono,
+            });
+        }
+
+        self.record_payout(order_id, settlement)?;
+        self.finalize_receipt(order_id, false)?;
+        self.log_event(order_id, EngineEventKind::TimedRelease);
+        self.publish_state(order_id);
+        Ok(settlement.seller_amount)
+    }
+
     // ============================================================================
     // Receipt Finalization
     // ============================================================================
 
-    fn finalize_receipt(&mut self, order_id: &[u8; 32], refunded: bool) -> Result<(), String> {
+    /// Add one payout to the seller/fee split on the order's receipt stub.
+    /// Every path that pays the seller (full, partial, timed release and
+    /// a PaySeller dispute) charges the fee on the amount it releases.
+    fn record_payout(
+        &mut self,
+        order_id: &[u8; 32],
+        payout: Settlement,
+    ) -> Result<(), EngineError> {
+        let (fulfillment_mono, fulfill_txid) = {
+            let escrow = self.get_escrow(order_id)?;
+            (escrow.fulfillment_mono, escrow.seller_fulfill_txid.clone())
+        };
+        let idx = self.receipt_stub_index(order_id, fulfillment_mono, fulfill_txid.as_deref())?;
+        self.receipts[idx].seller_amount += payout.seller_amount as u128;
+        self.receipts[idx].fee_amount += payout.fee_amount as u128;
+        Ok(())
+    }
+
+    /// Index of the unsettled stub written by this order's current
+    /// fulfillment (same order id, fulfillment time and fulfill txid).
+    fn receipt_stub_index(
+        &self,
+        order_id: &[u8; 32],
+        fulfillment_mono: Option<u64>,
+        seller_fulfill_txid: Option<&str>,
+    ) -> Result<usize, EngineError> {
+        let (Some(fulfillment_mono), Some(fulfill_txid)) = (fulfillment_mono, seller_fulfill_txid)
+        else {
+            return Err(EngineError::ReceiptStub("not found: order has no fulfillment"));
+        };
+
+        let idx = self
+            .receipts
+            .iter()
+            .rposition(|m| {
+                &m.session_id == order_id
+                    && m.fulfillment_mono == fulfillment_mono
+                    && m.seller_fulfill_txid == fulfill_txid
+            })
+            .ok_or(EngineError::ReceiptStub("not found for this fulfillment"))?;
+
+        let stub = &self.receipts[idx];
+        if stub.seller_claim_txid.is_some()
+            || stub.seller_refund_txid.is_some()
+            || stub.buyer_withdraw_txid.is_some()
+        {
+            return Err(EngineError::ReceiptStub("already finalized"));
+        }
+        Ok(idx)
+    }
+
+    fn finalize_receipt(&mut self, order_id: &[u8; 32], refunded: bool) -> Result<(), EngineError> {
     let now = self.now();
 
     // ---- FIRST: extract all fields we need from escrow (immutable borrow ends here!) ----
@@ -442,6 +1582,9 @@ impl CoreProverEngine {
         seller_block_height,
         seller_claim_txid,
         seller_refund_txid,
+        fulfillment_mono,
+        seller_fulfill_txid,
+        reclaim,
     ) = {
         let escrow = self.get_escrow(order_id)?;
 
@@ -450,16 +1593,17 @@ impl CoreProverEngine {
             escrow.seller_block_height.unwrap_or(0),
             escrow.seller_claim_txid.clone(),
             escrow.seller_refund_txid.clone(),
+            escrow.fulfillment_mono,
+            escrow.seller_fulfill_txid.clone(),
+            (escrow.state == EscrowState::BuyerReclaimed)
+                .then(|| (escrow.buyer_withdraw_txid.clone(), escrow.amount)),
         )
     }; 
     // <-- immutable borrow ends here
 
     // ---- SECOND: now safely borrow receipts mutably ----
     let idx = self
-        .receipts
-        .iter()
-        .rposition(|m| &m.session_id == order_id)
-        .ok_or("receipt stub not found")?;
+        .receipt_stub_index(order_id, fulfillment_mono, seller_fulfill_txid.as_deref())?;
 
     let meta = &mut self.receipts[idx];
 
@@ -468,12 +1612,20 @@ impl CoreProverEngine {
     meta.settlement_iso = now.iso.clone();
     meta.seller_block_height = seller_block_height;
 
-    if refunded {
+    if let Some((buyer_txid, amount)) = reclaim {
+        meta.buyer_withdraw_txid = buyer_txid;
+        meta.buyer_refund_amount = amount as u128;
+    } else if refunded {
         meta.seller_refund_txid = seller_refund_txid;
     } else {
         meta.seller_claim_txid = seller_claim_txid;
     }
 
+    let meta = &self.receipts[idx];
+    for sink in self.sinks.iter_mut() {
+        sink.emit(meta);
+    }
+
     Ok(())
 }
 
@@ -481,33 +1633,2142 @@ impl CoreProverEngine {
     // STATE UPDATE
     // ============================================================================
 
-    pub fn update_state(&mut self, order_id: &[u8; 32]) -> Result<(), String> {
+    pub fn update_state(&mut self, order_id: &[u8; 32]) -> Result<(), EngineError> {
         let now = self.now();
 
+        let mut expired = false;
         let escrow = self.get_escrow_mut(order_id)?;
         if escrow.state == EscrowState::SellerAccepted {
             if let Some(deadline) = escrow.fulfillment_deadline_mono {
                 if now.mono > deadline {
                     escrow.state = EscrowState::FulfillmentExpired;
+                    escrow.events.push(EscrowEvent::FulfillmentExpired { at_mono: now.mono });
+                    expired = true;
                 }
             }
         }
+        if expired {
+            self.log_event(order_id, EngineEventKind::FulfillmentExpired);
+        }
+        self.publish_state(order_id);
         Ok(())
     }
 
     // ============================================================================
-    // GETTERS
+    // LEGACY NAMES (v0.2 pizza scenarios)
     // ============================================================================
 
-    pub fn get_state(&self, order_id: &[u8; 32]) -> Result<EscrowState, String> {
-        Ok(self.get_escrow(order_id)?.state)
+    /// v0.2 name for [`update_state`](Self::update_state).
+    pub fn update_escrow_state(&mut self, order_id: &[u8; 32]) -> Result<(), EngineError> {
+        self.update_state(order_id)
     }
 
-    pub fn get_receipt(&self, order_id: &[u8; 32]) -> Option<&ReceiptMetadata> {
-        self.receipts.iter().find(|r| &r.session_id == order_id)
+    /// v0.2 name for [`timed_release`](Self::timed_release).
+    pub fn trigger_timed_release(&mut self, order_id: &[u8; 32]) -> Result<u64, EngineError> {
+        self.timed_release(order_id)
     }
 
-    pub fn get_receipts(&self) -> &Vec<ReceiptMetadata> {
-        &self.receipts
+    // ============================================================================
+    // DRY-RUN CHECKS (no mutation; txid presence is checked by the real call)
+    // ============================================================================
+
+    pub fn can_seller_accept(&self, order_id: &[u8; 32]) -> Result<(), EngineError> {
+        guard_seller_accept(self.get_escrow(order_id)?, &self.now())
+    }
+
+    pub fn can_seller_fulfill(&self, order_id: &[u8; 32]) -> Result<(), EngineError> {
+        guard_seller_fulfill(self.get_escrow(order_id)?)
+    }
+
+    pub fn can_seller_claim(&self, order_id: &[u8; 32]) -> Result<(), EngineError> {
+        guard_claim(self.get_escrow(order_id)?, "seller_claim")
+    }
+
+    pub fn can_seller_refund(&self, order_id: &[u8; 32]) -> Result<(), EngineError> {
+        guard_settlement(self.get_escrow(order_id)?, "seller_refund")
+    }
+
+    pub fn can_buyer_withdraw(&self, order_id: &[u8; 32]) -> Result<(), EngineError> {
+        guard_buyer_withdraw(self.get_escrow(order_id)?, &self.now())
+    }
+
+    /// Buyer-facing answer to "can I get my money back, and if not, why?".
+    /// `Eligible` exactly when `buyer_withdraw` would succeed now.
+    pub fn refund_eligibility(
+        &self,
+        order_id: &[u8; 32],
+    ) -> Result<RefundEligibility, EngineError> {
+        let escrow = self.get_escrow(order_id)?;
+        let now = self.now();
+
+        if escrow.state.is_terminal() {
+            return Ok(RefundEligibility::TerminalAlready(escrow.state));
+        }
+        if guard_buyer_withdraw(escrow, &now).is_ok() {
+            let (amount, _) = withdrawal_split(escrow);
+            return Ok(RefundEligibility::Eligible { amount });
+        }
+
+        Ok(match escrow.state {
+            EscrowState::BuyerCommitted => RefundEligibility::NotYet {
+                seconds_remaining: escrow.acceptance_deadline_mono + 1 - now.mono,
+            },
+            EscrowState::SellerAccepted => RefundEligibility::Locked {
+                reason: "seller accepted; withdrawal opens if the fulfillment window lapses"
+                    .into(),
+            },
+            state => RefundEligibility::Locked {
+                reason: format!("seller fulfilled ({:?}); funds settle by claim or refund", state),
+            },
+        })
+    }
+
+    // ============================================================================
+    // DEADLINE QUERIES
+    // ============================================================================
+
+    /// Orders whose next deadline falls within `secs` of `now_mono`,
+    /// with the seconds remaining. Already-passed deadlines are excluded.
+    /// Sorted by (deadline, order_id).
+    pub fn escrows_expiring_within(
+        &self,
+        secs: u64,
+        now_mono: u64,
+    ) -> Vec<([u8; 32], EscrowState, u64)> {
+        let mut out: Vec<_> = self
+            .escrows
+            .values()
+            .filter_map(|e| {
+                let deadline = next_deadline_mono(e)?;
+                let remaining = deadline.checked_sub(now_mono)?;
+                (remaining <= secs).then_some((e.order_id, e.state, remaining))
+            })
+            .collect();
+        // equal remaining <=> equal deadline
+        out.sort_by_key(|(id, _, remaining)| (*remaining, *id));
+        out
+    }
+
+    /// Apply `update_state` to every escrow and return the ones that moved
+    /// to FulfillmentExpired as (order_id, deadline_mono), sorted by
+    /// (deadline_mono, order_id) so the result does not depend on storage order.
+    pub fn sweep_expirations(&mut self) -> Vec<([u8; 32], u64)> {
+        let now = self.now();
+
+        let mut expired: Vec<([u8; 32], u64)> = self
+            .escrows
+            .values_mut()
+            .filter_map(|escrow| {
+                if escrow.state != EscrowState::SellerAccepted {
+                    return None;
+                }
+                let deadline = escrow.fulfillment_deadline_mono?;
+                if now.mono <= deadline {
+                    return None;
+                }
+                escrow.state = EscrowState::FulfillmentExpired;
+                escrow.events.push(EscrowEvent::FulfillmentExpired { at_mono: now.mono });
+                Some((escrow.order_id, deadline))
+            })
+            .collect();
+
+        expired.sort_by_key(|(id, deadline)| (*deadline, *id));
+        for (id, _) in &expired {
+            self.log_event(id, EngineEventKind::FulfillmentExpired);
+            self.publish_state(id);
+        }
+        expired
+    }
+
+    // ============================================================================
+    // METRICS
+    // ============================================================================
+
+    /// Seconds from buyer commit to settlement for every finalized
+    /// (claimed, refunded, withdrawn or reclaimed) receipt, in receipt order.
+    pub fn settlement_latencies(&self) -> Vec<u64> {
+        self.receipts
+            .iter()
+            .filter(|r| r.is_finalized())
+            .filter_map(|r| {
+                let escrow = self.get_escrow(&r.session_id).ok()?;
+                Some(r.settlement_mono.saturating_sub(escrow.buyer_commit_mono))
+            })
+            .collect()
+    }
+
+    /// Merge this engine with `others` into one reporting view. Shards must
+    /// hold disjoint order ids; a collision is an error.
+    pub fn merge_views(&self, others: &[&CoreProverEngine]) -> Result<MergedView, EngineError> {
+        let shards: Vec<&CoreProverEngine> =
+            std::iter::once(self).chain(others.iter().copied()).collect();
+
+        let mut owner: HashMap<[u8; 32], usize> = HashMap::new();
+        for (shard, engine) in shards.iter().enumerate() {
+            for escrow in engine.escrows_in_order() {
+                if let Some(first) = owner.insert(escrow.order_id, shard) {
+                    return Err(EngineError::OrderIdCollision {
+                        order_id: escrow.order_id,
+                        shards: (first, shard),
+                    });
+                }
+            }
+        }
+
+        let escrows = || shards.iter().flat_map(|e| e.escrows.values());
+        let receipts = || shards.iter().flat_map(|e| e.receipts.iter());
+
+        Ok(MergedView {
+            shard_count: shards.len(),
+            escrow_count: owner.len(),
+            state_counts: EscrowState::ALL
+                .iter()
+                .map(|s| (*s, escrows().filter(|e| e.state == *s).count()))
+                .collect(),
+            total_value_locked: escrows()
+                .filter(|e| !e.state.is_terminal())
+                .map(|e| e.amount - e.claimed_so_far)
+                .sum(),
+            receipt_count: receipts().count(),
+            finalized_receipt_count: receipts().filter(|r| r.is_finalized()).count(),
+            settlement_latencies: shards.iter().flat_map(|e| e.settlement_latencies()).collect(),
+        })
+    }
+
+    // ============================================================================
+    // PRUNING
+    // ============================================================================
+
+    /// Remove terminal escrows settled before `older_than_unix`, together
+    /// with their receipts (archived to the archive sink first, if set).
+    /// Non-terminal escrows are never pruned. Returns the number removed.
+    pub fn prune_terminal(&mut self, older_than_unix: u64) -> usize {
+        let now = self.now();
+        // mono and unix advance together, so the offset is fixed
+        let to_unix = |mono: u64| now.unix - now.mono + mono;
+
+        let pruned: Vec<[u8; 32]> = self
+            .escrows_in_order()
+            .filter(|e| e.state.is_terminal())
+            .filter(|e| e.settlement_mono.is_some_and(|m| to_unix(m) < older_than_unix))
+            .map(|e| e.order_id)
+            .collect();
+
+        if pruned.is_empty() {
+            return 0;
+        }
+
+        let (archived, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.receipts)
+            .into_iter()
+            .partition(|r| pruned.contains(&r.session_id));
+        self.receipts = kept;
+
+        if let Some(sink) = self.archive_sink.as_mut() {
+            for receipt in &archived {
+                sink.emit(receipt);
+            }
+        }
+
+        self.escrows.retain(|id, _| !pruned.contains(id));
+        self.escrow_order.retain(|id| !pruned.contains(id));
+        self.publish_all_states();
+        pruned.len()
+    }
+
+    // ============================================================================
+    // SNAPSHOTS
+    // ============================================================================
+
+    pub fn snapshot(&self) -> Result<EngineSnapshot, EngineError> {
+        let state = EngineState {
+            escrows: self.escrows_in_order().cloned().collect(),
+            receipts: self.receipts.clone(),
+            next_session_counter: self.next_session_counter,
+            current_mono: self.current_mono,
+            current_unix: self.current_unix,
+            genesis_unix: Some(self.genesis_unix),
+            chain_id: self.chain_id,
+            block_interval_secs: self.block_interval_secs,
+            current_block_height: self.current_block_height,
+            allow_self_dealing: self.allow_self_dealing,
+            accepting_commits: self.accepting_commits,
+            max_active_escrows: self.max_active_escrows,
+            iso_offset_minutes: self.iso_offset_minutes,
+            timed_release_grace_secs: self.timed_release_grace_secs,
+            min_acceptance_window_secs: self.min_acceptance_window_secs,
+            min_fulfillment_window_secs: self.min_fulfillment_window_secs,
+            min_claim_window_secs: self.min_claim_window_secs,
+        };
+        let body =
+            serde_json::to_string(&state).map_err(|e| EngineError::Restore(e.to_string()))?;
+
+        Ok(EngineSnapshot {
+            version: SNAPSHOT_VERSION,
+            checksum: snapshot_checksum(&body),
+            body,
+        })
+    }
+
+    /// Restore an engine, rejecting unknown versions and checksum mismatches.
+    pub fn from_snapshot(snapshot: &EngineSnapshot) -> Result<Self, EngineError> {
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(EngineError::Restore(format!(
+                "unsupported snapshot version {}",
+                snapshot.version
+            )));
+        }
+        if snapshot_checksum(&snapshot.body) != snapshot.checksum {
+            return Err(EngineError::Restore("snapshot checksum mismatch (corrupted body)".into()));
+        }
+
+        let state: EngineState = serde_json::from_str(&snapshot.body)
+            .map_err(|e| EngineError::Restore(format!("invalid snapshot body: {}", e)))?;
+
+        let mut engine = Self {
+            escrows: HashMap::new(),
+            escrow_order: Vec::new(),
+            receipts: state.receipts,
+            next_session_counter: state.next_session_counter,
+            events: Vec::new(),
+            current_mono: state.current_mono,
+            current_unix: state.current_unix,
+            // pre-genesis snapshots: assume the clocks were in step
+            genesis_unix: state
+                .genesis_unix
+                .unwrap_or(state.current_unix.saturating_sub(state.current_mono)),
+            chain_id: state.chain_id,
+            block_interval_secs: state.block_interval_secs,
+            current_block_height: state.current_block_height,
+            allow_self_dealing: state.allow_self_dealing,
+            accepting_commits: state.accepting_commits,
+            max_active_escrows: state.max_active_escrows,
+            iso_offset_minutes: state.iso_offset_minutes,
+            timed_release_grace_secs: state.timed_release_grace_secs,
+            min_acceptance_window_secs: state.min_acceptance_window_secs,
+            min_fulfillment_window_secs: state.min_fulfillment_window_secs,
+            min_claim_window_secs: state.min_claim_window_secs,
+            sinks: Vec::new(),
+            archive_sink: None,
+            states: Arc::new(HashMap::new()),
+            state_reader: StateViewReader::default(),
+        };
+        // pre-LateFulfilled snapshots left late-fulfilled escrows expired
+        let mut escrows = state.escrows;
+        for e in &mut escrows {
+            if e.state == EscrowState::FulfillmentExpired && e.fulfillment_mono.is_some() {
+                e.state = EscrowState::LateFulfilled;
+            }
+        }
+        engine.replace_escrows(escrows);
+        engine
+            .verify_clock_consistency()
+            .map_err(|e| EngineError::Restore(format!("snapshot rejected: {}", e)))?;
+        engine.publish_all_states();
+        Ok(engine)
+    }
+
+    /// Receipt stubs with a fulfillment but no settlement (claim, refund or
+    /// withdrawal). After a restore these are the orders a recovering
+    /// controller must reconcile against the chain.
+    pub fn orphaned_stubs(&self) -> Vec<[u8; 32]> {
+        self.receipts
+            .iter()
+            .filter(|r| !r.is_finalized())
+            .map(|r| r.session_id)
+            .collect()
+    }
+
+    // ============================================================================
+    // EVENT SOURCING
+    // ============================================================================
+
+    /// Replace every escrow with one rebuilt from its event log (see
+    /// `Escrow::replay_events`). All logs are replayed before anything is
+    /// replaced, so a bad log leaves the engine untouched. Receipts are not
+    /// part of the log and are kept as they are.
+    pub fn rebuild_from_events(
+        &mut self,
+        logs: HashMap<[u8; 32], Vec<EscrowEvent>>,
+    ) -> Result<(), EngineError> {
+        let mut logs: Vec<_> = logs.into_iter().collect();
+        logs.sort_by_key(|(id, _)| *id);
+
+        let mut escrows = Vec::with_capacity(logs.len());
+        for (order_id, log) in &logs {
+            let hex: String = order_id.iter().map(|b| format!("{:02x}", b)).collect();
+            let escrow = Escrow::replay_events(log)
+                .map_err(|e| EngineError::Restore(format!("order 0x{}: {}", hex, e)))?;
+            if &escrow.order_id != order_id {
+                return Err(EngineError::Restore(format!(
+                    "order 0x{}: log belongs to a different order",
+                    hex
+                )));
+            }
+            escrows.push(escrow);
+        }
+
+        // keep generate_order_id clear of the rebuilt ids
+        let highest = escrows
+            .iter()
+            .map(|e| u64::from_le_bytes(e.order_id[..8].try_into().unwrap()))
+            .max();
+        if let Some(n) = highest {
+            self.next_session_counter = self.next_session_counter.max(n + 1);
+        }
+
+        self.replace_escrows(escrows);
+        self.publish_all_states();
+        Ok(())
     }
-}
\ No newline at end of file
+
+    // ============================================================================
+    // AUDIT REPORT
+    // ============================================================================
+
+    /// Plain-English lifecycle summary of one order for support and legal
+    /// review: parties, amounts, timing, lateness, settlement, discount and
+    /// every txid with its timestamp.
+    pub fn audit_report(&self, order_id: &[u8; 32]) -> Result<String, EngineError> {
+        let e = self.get_escrow(order_id)?;
+        let receipt = self.get_receipt(order_id);
+        // mono and unix advance together from genesis
+        let at = |mono: u64| iso8601_with_offset(self.genesis_unix + mono, self.iso_offset_minutes);
+
+        let id_hex: String = order_id.iter().map(|b| format!("{:02x}", b)).collect();
+        let mut lines = vec![
+            format!("Audit report for order 0x{}", id_hex),
+            format!("Current state: {:?}", e.state),
+            String::new(),
+            format!(
+                "Buyer {} committed {} on chain {} at {}.",
+                e.buyer,
+                e.amount,
+                e.buyer_chain_id,
+                at(e.buyer_commit_mono)?
+            ),
+        ];
+
+        match e.seller_accept_mono {
+            Some(m) => lines.push(format!("Seller {} accepted the order at {}.", e.seller, at(m)?)),
+            None => lines.push(format!("Seller {} has not accepted the order.", e.seller)),
+        }
+
+        match (e.fulfillment_mono, e.fulfillment_deadline_mono) {
+            (Some(m), Some(d)) if m > d => lines.push(format!(
+                "Seller fulfilled the order LATE at {}, after the fulfillment deadline of {}.",
+                at(m)?,
+                at(d)?
+            )),
+            (Some(m), _) => {
+                lines.push(format!("Seller fulfilled the order on time at {}.", at(m)?))
+            }
+            (None, _) => lines.push("The order has not been fulfilled.".into()),
+        }
+
+        let settled_at = e.settlement_mono.map(at).transpose()?.unwrap_or_default();
+        match e.state {
+            EscrowState::SellerClaimed => {
+                let (paid, fee) = receipt
+                    .map_or((e.amount as u128, 0), |r| (r.seller_amount, r.fee_amount));
+                lines.push(format!(
+                    "Settlement: the seller was paid {} at {}, after a controller fee of {}.",
+                    paid, settled_at, fee
+                ));
+            }
+            EscrowState::SellerRefunded => {
+                lines.push(format!(
+                    "Settlement: the seller refunded {} to the buyer at {}.",
+                    e.amount, settled_at
+                ));
+                if let Some(reason) = receipt.and_then(|r| r.decline_reason.as_ref()) {
+                    lines.push(format!("The seller declined the order: {}.", reason));
+                }
+            }
+            EscrowState::BuyerWithdrawn => {
+                lines.push(format!("Settlement: the buyer withdrew at {}.", settled_at));
+                if let Some(r) = receipt {
+                    lines.push(format!(
+                        "The buyer received {} and the seller was paid a penalty of {}.",
+                        r.buyer_refund_amount, r.seller_penalty_amount
+                    ));
+                }
+            }
+            EscrowState::BuyerReclaimed => lines.push(format!(
+                "Settlement: the seller never claimed; the buyer reclaimed {} at {}.",
+                e.amount, settled_at
+            )),
+            EscrowState::PartiallyClaimed => lines.push(format!(
+                "Settlement: in progress, the seller has claimed {} of {}.",
+                e.claimed_so_far, e.amount
+            )),
+            state => lines.push(format!("Settlement: not settled yet ({:?}).", state)),
+        }
+
+        if let (Some(opener), Some(hash)) = (e.dispute_opener, e.dispute_evidence_hash) {
+            let opener = match opener {
+                Party::Buyer => "buyer",
+                Party::Seller => "seller",
+            };
+            let hash: String = hash.iter().map(|b| format!("{:02x}", b)).collect();
+            lines.push(format!("Dispute: opened by the {} with evidence 0x{}.", opener, hash));
+            if let Some(resolver) = receipt.and_then(|r| r.dispute_resolver.as_ref()) {
+                lines.push(format!("The dispute was resolved by {}.", resolver));
+            }
+        }
+
+        match receipt.filter(|r| r.discount_pct > 0) {
+            Some(r) => lines.push(format!(
+                "Discount: the buyer is owed a {}% discount for late fulfillment, valid until {}.",
+                r.discount_pct,
+                iso8601_with_offset(r.discount_expiration_unix, self.iso_offset_minutes)?
+            )),
+            None => lines.push("Discount: none.".into()),
+        }
+
+        lines.push(String::new());
+        lines.push("Transactions:".into());
+        let txids = [
+            ("buyer_commit", Some(&e.buyer_commit_txid), Some(e.buyer_commit_mono)),
+            ("seller_accept", e.seller_accept_txid.as_ref(), e.seller_accept_mono),
+            ("seller_fulfill", e.seller_fulfill_txid.as_ref(), e.fulfillment_mono),
+            ("seller_claim", e.seller_claim_txid.as_ref(), e.settlement_mono),
+            ("seller_refund", e.seller_refund_txid.as_ref(), e.settlement_mono),
+            ("buyer_withdraw", e.buyer_withdraw_txid.as_ref(), e.settlement_mono),
+        ];
+        for (label, txid, mono) in txids {
+            if let Some(txid) = txid {
+                let when = mono.map(at).transpose()?.unwrap_or_else(|| "unknown time".into());
+                lines.push(format!("  {:<15} {} at {}", label, txid, when));
+            }
+        }
+
+        Ok(lines.join("\n"))
+    }
+
+    // ============================================================================
+    // GETTERS
+    // ============================================================================
+
+    pub fn get_state(&self, order_id: &[u8; 32]) -> Result<EscrowState, EngineError> {
+        Ok(self.get_escrow(order_id)?.state)
+    }
+
+    pub fn get_receipt(&self, order_id: &[u8; 32]) -> Option<&ReceiptMetadata> {
+        self.receipts.iter().find(|r| &r.session_id == order_id)
+    }
+
+    /// Escrows not yet in a terminal state
+    pub fn active_escrow_count(&self) -> usize {
+        self.escrows.values().filter(|e| !e.state.is_terminal()).count()
+    }
+
+    pub fn get_receipts(&self) -> &Vec<ReceiptMetadata> {
+        &self.receipts
+    }
+
+    /// Every txid the engine has recorded, with its kind and order id, for
+    /// reconciling against chain data. Escrow order, then lifecycle order.
+    pub fn all_txids(&self) -> Vec<(TxidKind, String, [u8; 32])> {
+        let mut txids = Vec::new();
+        for e in self.escrows_in_order() {
+            let recorded = [
+                (TxidKind::BuyerCommit, Some(&e.buyer_commit_txid)),
+                (TxidKind::SellerAccept, e.seller_accept_txid.as_ref()),
+                (TxidKind::SellerFulfill, e.seller_fulfill_txid.as_ref()),
+                (TxidKind::SellerClaim, e.seller_claim_txid.as_ref()),
+                (TxidKind::SellerRefund, e.seller_refund_txid.as_ref()),
+                (TxidKind::BuyerWithdraw, e.buyer_withdraw_txid.as_ref()),
+            ];
+            for (kind, txid) in recorded {
+                if let Some(txid) = txid {
+                    txids.push((kind, txid.clone(), e.order_id));
+                }
+            }
+        }
+        txids
+    }
+
+    pub fn get_events(&self, order_id: &[u8; 32]) -> Result<&[EscrowEvent], EngineError> {
+        Ok(&self.get_escrow(order_id)?.events)
+    }
+
+    /// Engine-wide log entries for one order, oldest first. Unlike
+    /// `get_events`, these survive `prune_terminal`; they are not part of
+    /// snapshots, so a restored engine starts with an empty log.
+    pub fn events_for(&self, order_id: &[u8; 32]) -> Vec<&EngineEvent> {
+        self.events.iter().filter(|e| &e.order_id == order_id).collect()
+    }
+
+    /// Every engine-wide log entry, oldest first
+    pub fn all_events(&self) -> &[EngineEvent] {
+        &self.events
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GENESIS: u64 = 1_700_000_000;
+
+    fn commit(engine: &mut CoreProverEngine, profile: PaymentProfile, amount: u64) -> [u8; 32] {
+        engine
+            .buyer_commit(
+                "buyer".into(),
+                "seller".into(),
+                amount,
+                profile,
+                1,
+                "0xcommit".into(),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn acceptance_timeout_penalty_splits_withdrawal() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let mut profile = PaymentProfile::pizza_delivery();
+        profile.acceptance_timeout_buyer_penalty_bps = 200;
+
+        let order_id = commit(&mut engine, profile.clone(), 10_000);
+        engine.advance_time(profile.timing.acceptance_window_secs + 1);
+
+        let refund = engine.buyer_withdraw(&order_id, Some("0xwithdraw".into())).unwrap();
+        assert_eq!(refund, 9_800);
+        assert_eq!(engine.get_state(&order_id).unwrap(), EscrowState::BuyerWithdrawn);
+
+        let receipt = engine.get_receipt(&order_id).unwrap();
+        assert_eq!(receipt.buyer_refund_amount, 9_800);
+        assert_eq!(receipt.seller_penalty_amount, 200);
+        assert_eq!(receipt.buyer_withdraw_txid.as_deref(), Some("0xwithdraw"));
+    }
+
+    #[test]
+    fn acceptance_timeout_penalty_above_100_percent_is_rejected() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let mut profile = PaymentProfile::pizza_delivery();
+        profile.acceptance_timeout_buyer_penalty_bps = 10_001;
+
+        let err = engine
+            .buyer_commit(
+                "buyer".into(),
+                "seller".into(),
+                10_000,
+                profile.clone(),
+                1,
+                "0xcommit".into(),
+            )
+            .unwrap_err();
+        assert!(
+            matches!(&err, EngineError::InvalidArgument(msg) if msg.contains("10001 bps")),
+            "{}",
+            err
+        );
+
+        let spec = CommitSpec {
+            buyer: "buyer".into(),
+            seller: "seller".into(),
+            amount: 10_000,
+            profile: profile.clone(),
+            buyer_chain_id: 1,
+            buyer_commit_txid: "0xcommit".into(),
+        };
+        assert!(engine.bulk_commit(vec![spec]).is_err());
+        assert_eq!(engine.active_escrow_count(), 0);
+
+        // 100% is the ceiling: the whole amount goes to the seller
+        profile.acceptance_timeout_buyer_penalty_bps = 10_000;
+        let order_id = commit(&mut engine, profile.clone(), 10_000);
+        engine.advance_time(profile.timing.acceptance_window_secs + 1);
+        assert_eq!(engine.buyer_withdraw(&order_id, None).unwrap(), 0);
+    }
+
+    #[test]
+    fn acceptance_timeout_without_penalty_refunds_in_full() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let profile = PaymentProfile::pizza_delivery();
+
+        let order_id = commit(&mut engine, profile.clone(), 10_000);
+        engine.advance_time(profile.timing.acceptance_window_secs + 1);
+
+        assert_eq!(engine.buyer_withdraw(&order_id, None).unwrap(), 10_000);
+        assert_eq!(engine.get_receipt(&order_id).unwrap().seller_penalty_amount, 0);
+    }
+
+    #[test]
+    fn can_seller_accept_mirrors_seller_accept() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let profile = PaymentProfile::pizza_delivery();
+
+        // Unknown order
+        assert!(engine.can_seller_accept(&[9u8; 32]).is_err());
+        assert!(engine.seller_accept(&[9u8; 32], "0xaccept".into()).is_err());
+
+        // BuyerCommitted, within window: dry-run succeeds without mutating
+        let order_id = commit(&mut engine, profile.clone(), 1_000);
+        assert!(engine.can_seller_accept(&order_id).is_ok());
+        assert_eq!(engine.get_state(&order_id).unwrap(), EscrowState::BuyerCommitted);
+        assert!(engine.seller_accept(&order_id, "0xaccept".into()).is_ok());
+
+        // SellerAccepted: both reject
+        let dry = engine.can_seller_accept(&order_id);
+        let real = engine.seller_accept(&order_id, "0xaccept2".into());
+        assert!(dry.is_err());
+        assert_eq!(dry, real);
+
+        // Acceptance window expired: both reject
+        let late_id = commit(&mut engine, profile.clone(), 1_000);
+        engine.advance_time(profile.timing.acceptance_window_secs + 1);
+        let dry = engine.can_seller_accept(&late_id);
+        let real = engine.seller_accept(&late_id, "0xaccept3".into());
+        assert!(dry.is_err());
+        assert_eq!(dry, real);
+        assert_eq!(engine.get_state(&late_id).unwrap(), EscrowState::BuyerCommitted);
+    }
+
+    #[test]
+    fn dry_run_checks_follow_lifecycle() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let order_id = commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+
+        assert!(engine.can_seller_fulfill(&order_id).is_err());
+        assert!(engine.can_seller_claim(&order_id).is_err());
+        assert!(engine.can_buyer_withdraw(&order_id).is_err());
+
+        engine.seller_accept(&order_id, "0xaccept".into()).unwrap();
+        assert!(engine.can_seller_fulfill(&order_id).is_ok());
+
+        engine.seller_fulfill(&order_id, "0xfulfill".into()).unwrap();
+        assert!(engine.can_seller_claim(&order_id).is_ok());
+        assert!(engine.can_seller_refund(&order_id).is_ok());
+
+        engine.seller_claim(&order_id, "0xclaim".into()).unwrap();
+        assert!(engine.can_seller_claim(&order_id).is_err());
+        assert!(engine.can_seller_refund(&order_id).is_err());
+    }
+
+    #[test]
+    fn reassign_seller_before_acceptance() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let order_id = commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+
+        engine.advance_time(10);
+        engine.reassign_seller(&order_id, "seller2".into()).unwrap();
+
+        assert_eq!(
+            engine.get_events(&order_id).unwrap().last(),
+            Some(&EscrowEvent::SellerReassigned {
+                from: "seller".into(),
+                to: "seller2".into(),
+                at_mono: 10,
+            })
+        );
+        assert!(engine.seller_accept(&order_id, "0xaccept".into()).is_ok());
+    }
+
+    #[test]
+    fn reassign_seller_rejected_after_acceptance() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let order_id = commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+        engine.seller_accept(&order_id, "0xaccept".into()).unwrap();
+
+        assert!(engine.reassign_seller(&order_id, "seller2".into()).is_err());
+        assert!(!engine
+            .get_events(&order_id)
+            .unwrap()
+            .iter()
+            .any(|e| matches!(e, EscrowEvent::SellerReassigned { .. })));
+    }
+
+    #[test]
+    fn self_dealing_rejected_unless_allowed() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let commit_self = |engine: &mut CoreProverEngine| {
+            engine.buyer_commit(
+                "alice".into(),
+                "alice".into(),
+                1_000,
+                PaymentProfile::pizza_delivery(),
+                1,
+                "0xcommit".into(),
+            )
+        };
+
+        assert!(matches!(
+            commit_self(&mut engine),
+            Err(EngineError::InvalidArgument(msg)) if msg.contains("self-dealing")
+        ));
+
+        engine.allow_self_dealing = true;
+        assert!(commit_self(&mut engine).is_ok());
+    }
+
+    #[test]
+    fn reassign_seller_to_buyer_is_self_dealing() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let order_id = commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+
+        assert!(matches!(
+            engine.reassign_seller(&order_id, "buyer".into()),
+            Err(EngineError::InvalidArgument(msg)) if msg.contains("self-dealing")
+        ));
+        assert_eq!(engine.get_escrow(&order_id).unwrap().seller, "seller");
+
+        engine.allow_self_dealing = true;
+        assert!(engine.reassign_seller(&order_id, "buyer".into()).is_ok());
+    }
+
+    #[test]
+    fn escrows_expiring_within_window() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let profile = PaymentProfile::pizza_delivery(); // acceptance 1800, fulfillment 3600
+
+        // Committed at t=0: acceptance deadline 1800
+        let early = commit(&mut engine, profile.clone(), 1_000);
+
+        // Committed at t=1500: acceptance deadline 3300
+        engine.advance_time(1500);
+        let late = commit(&mut engine, profile.clone(), 1_000);
+
+        // Accepted at t=1500: fulfillment deadline 5100
+        let accepted = commit(&mut engine, profile.clone(), 1_000);
+        engine.seller_accept(&accepted, "0xaccept".into()).unwrap();
+
+        let now = 1500;
+        let near = engine.escrows_expiring_within(600, now);
+        assert_eq!(near, vec![(early, EscrowState::BuyerCommitted, 300)]);
+
+        let wider = engine.escrows_expiring_within(1800, now);
+        assert_eq!(wider.len(), 2);
+        assert!(wider.contains(&(late, EscrowState::BuyerCommitted, 1800)));
+
+        // Passed deadlines are not "upcoming"
+        assert!(engine
+            .escrows_expiring_within(600, 2000)
+            .iter()
+            .all(|(id, _, _)| id != &early));
+    }
+
+    #[test]
+    fn settlement_latency_median() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+
+        for latency in [100, 400, 200, 500, 300] {
+            let order_id = commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+            engine.seller_accept(&order_id, "0xaccept".into()).unwrap();
+            engine.advance_time(latency);
+            engine.seller_fulfill(&order_id, "0xfulfill".into()).unwrap();
+            engine.seller_claim(&order_id, "0xclaim".into()).unwrap();
+        }
+
+        // Unsettled orders are not counted
+        commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+
+        let latencies = engine.settlement_latencies();
+        assert_eq!(latencies, vec![100, 400, 200, 500, 300]);
+        assert_eq!(percentiles(&latencies, &[50.0]), vec![300]);
+        assert_eq!(percentiles(&latencies, &[0.0, 90.0, 100.0]), vec![100, 500, 500]);
+        assert!(percentiles(&[], &[50.0]).is_empty());
+    }
+
+    #[test]
+    fn settlement_latency_counts_buyer_exits() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let profile = PaymentProfile::pizza_delivery();
+
+        // Acceptance timeout withdrawal
+        let withdrawn = commit(&mut engine, profile.clone(), 1_000);
+        engine.advance_time(profile.timing.acceptance_window_secs + 1);
+        engine.buyer_withdraw(&withdrawn, Some("0xwithdraw".into())).unwrap();
+
+        // Reclaim after the seller let the dispute window lapse
+        let mut reclaim = reclaim_profile();
+        reclaim.allows_timed_release = false;
+        let reclaimed = commit(&mut engine, reclaim.clone(), 1_000);
+        engine.seller_accept(&reclaimed, "0xaccept".into()).unwrap();
+        engine.seller_fulfill(&reclaimed, "0xfulfill".into()).unwrap();
+        engine.advance_time(reclaim.timing.buyer_dispute_window_secs);
+        engine.buyer_reclaim(&reclaimed, "0xreclaim".into()).unwrap();
+
+        assert_eq!(
+            engine.settlement_latencies(),
+            vec![
+                profile.timing.acceptance_window_secs + 1,
+                reclaim.timing.buyer_dispute_window_secs
+            ]
+        );
+        assert!(engine.orphaned_stubs().is_empty());
+    }
+
+    #[test]
+    fn claim_emits_one_receipt_to_each_sink() {
+        use crate::receipt_sink::{NdjsonFileSink, VecSink};
+
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let collector = VecSink::new();
+        engine.add_sink(Box::new(collector.clone()));
+
+        let path = std::env::temp_dir().join(format!("receipts-{}.ndjson", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        engine.add_sink(Box::new(NdjsonFileSink::open(&path).unwrap()));
+
+        let order_id = commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+        engine.seller_accept(&order_id, "0xaccept".into()).unwrap();
+        engine.seller_fulfill(&order_id, "0xfulfill".into()).unwrap();
+
+        // Fulfillment creates the stub but does not finalize it
+        assert!(collector.is_empty());
+
+        engine.seller_claim(&order_id, "0xclaim".into()).unwrap();
+
+        let emitted = collector.receipts();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(emitted[0].session_id, order_id);
+        assert_eq!(emitted[0].seller_claim_txid.as_deref(), Some("0xclaim"));
+
+        let lines = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(lines.lines().count(), 1);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn acceptance_timeout_withdrawal_emits_receipt_to_sinks() {
+        use crate::receipt_sink::VecSink;
+
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let collector = VecSink::new();
+        engine.add_sink(Box::new(collector.clone()));
+
+        let profile = PaymentProfile::pizza_delivery();
+        let order_id = commit(&mut engine, profile.clone(), 1_000);
+        engine.advance_time(profile.timing.acceptance_window_secs + 1);
+        let refund = engine.buyer_withdraw(&order_id, Some("0xwithdraw".into())).unwrap();
+
+        let emitted = collector.receipts();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(emitted[0].session_id, order_id);
+        assert_eq!(emitted[0].buyer_withdraw_txid.as_deref(), Some("0xwithdraw"));
+        assert_eq!(emitted[0].buyer_refund_amount, refund as u128);
+    }
+
+    #[test]
+    fn update_escrow_state_matches_update_state() {
+        let profile = PaymentProfile::pizza_delivery();
+        let mut current = CoreProverEngine::new(369, 12, GENESIS);
+        let mut legacy = CoreProverEngine::new(369, 12, GENESIS);
+
+        let accepted_then_late = |engine: &mut CoreProverEngine| {
+            let id = commit(engine, profile.clone(), 1_000);
+            engine.seller_accept(&id, "0xaccept".into()).unwrap();
+            engine.advance_time(profile.timing.fulfillment_window_secs + 1);
+            id
+        };
+        let current_id = accepted_then_late(&mut current);
+        let legacy_id = accepted_then_late(&mut legacy);
+
+        current.update_state(&current_id).unwrap();
+        legacy.update_escrow_state(&legacy_id).unwrap();
+
+        assert_eq!(current.get_state(&current_id).unwrap(), EscrowState::FulfillmentExpired);
+        assert_eq!(legacy.get_state(&legacy_id).unwrap(), EscrowState::FulfillmentExpired);
+        assert!(legacy.update_escrow_state(&[9u8; 32]).is_err());
+    }
+
+    #[test]
+    fn trigger_timed_release_matches_timed_release() {
+        let profile = PaymentProfile::pizza_delivery();
+        let mut current = CoreProverEngine::new(369, 12, GENESIS);
+        let mut legacy = CoreProverEngine::new(369, 12, GENESIS);
+
+        let fulfilled = |engine: &mut CoreProverEngine| {
+            let id = commit(engine, profile.clone(), 1_000);
+            engine.seller_accept(&id, "0xaccept".into()).unwrap();
+            engine.seller_fulfill(&id, "0xfulfill".into()).unwrap();
+            id
+        };
+        let current_id = fulfilled(&mut current);
+        let legacy_id = fulfilled(&mut legacy);
+
+        // Both refuse inside the claim window
+        assert_eq!(
+            current.timed_release(&current_id),
+            legacy.trigger_timed_release(&legacy_id)
+        );
+
+        current.advance_time(profile.timing.claim_window_secs);
+        legacy.advance_time(profile.timing.claim_window_secs);
+
+        assert_eq!(current.timed_release(&current_id), Ok(1_000));
+        assert_eq!(legacy.trigger_timed_release(&legacy_id), Ok(1_000));
+        assert_eq!(legacy.get_state(&legacy_id).unwrap(), EscrowState::SellerClaimed);
+    }
+
+    #[test]
+    fn paused_commits_still_settle_in_flight_escrows() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let order_id = commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+        engine.seller_accept(&order_id, "0xaccept".into()).unwrap();
+
+        engine.set_accepting_commits(false);
+        assert!(!engine.is_accepting_commits());
+
+        let err = engine
+            .buyer_commit(
+                "buyer".into(),
+                "seller".into(),
+                1_000,
+                PaymentProfile::pizza_delivery(),
+                1,
+                "0xcommit2".into(),
+            )
+            .unwrap_err();
+        assert_eq!(err, EngineError::CommitsPaused);
+
+        engine.seller_fulfill(&order_id, "0xfulfill".into()).unwrap();
+        assert_eq!(engine.seller_claim_amount(&order_id, "0xclaim".into()), Ok(1_000));
+
+        engine.set_accepting_commits(true);
+        commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+    }
+
+    #[test]
+    fn sweep_expirations_orders_ties_by_order_id() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let profile = PaymentProfile::pizza_delivery();
+
+        // Three accepted at t=0 share a deadline; one accepted later
+        let mut ids: Vec<[u8; 32]> = (0..3)
+            .map(|_| commit(&mut engine, profile.clone(), 1_000))
+            .collect();
+        for id in &ids {
+            engine.seller_accept(id, "0xaccept".into()).unwrap();
+        }
+        engine.advance_time(100);
+        let later = commit(&mut engine, profile.clone(), 1_000);
+        engine.seller_accept(&later, "0xaccept".into()).unwrap();
+
+        // Scramble storage so insertion order != order_id order
+        engine.escrow_order.reverse();
+
+        engine.advance_time(profile.timing.fulfillment_window_secs + 1);
+        let swept = engine.sweep_expirations();
+
+        let deadline = profile.timing.fulfillment_window_secs;
+        ids.sort();
+        let mut expected: Vec<_> = ids.iter().map(|id| (*id, deadline)).collect();
+        expected.push((later, deadline + 100));
+        assert_eq!(swept, expected);
+
+        // Already expired escrows are not swept twice
+        assert!(engine.sweep_expirations().is_empty());
+        assert_eq!(engine.get_state(&later).unwrap(), EscrowState::FulfillmentExpired);
+    }
+
+    #[test]
+    fn snapshot_roundtrip_and_corruption() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let order_id = commit(&mut engine, PaymentProfile::pizza_delivery(), 12_345);
+        engine.seller_accept(&order_id, "0xaccept".into()).unwrap();
+        engine.advance_time(60);
+
+        let json = serde_json::to_string(&engine.snapshot().unwrap()).unwrap();
+
+        let restored =
+            CoreProverEngine::from_snapshot(&serde_json::from_str(&json).unwrap()).unwrap();
+        assert_eq!(restored.get_state(&order_id).unwrap(), EscrowState::SellerAccepted);
+        assert_eq!(restored.now().mono, 60);
+
+        // Flip one byte of the body; the JSON stays well-formed
+        let corrupted = json.replacen("12345", "12845", 1);
+        assert_ne!(corrupted, json);
+        let snapshot: EngineSnapshot = serde_json::from_str(&corrupted).unwrap();
+        let err = CoreProverEngine::from_snapshot(&snapshot).err().unwrap();
+        assert!(matches!(err, EngineError::Restore(msg) if msg.contains("checksum")));
+
+        let mut future = engine.snapshot().unwrap();
+        future.version = SNAPSHOT_VERSION + 1;
+        assert!(CoreProverEngine::from_snapshot(&future).is_err());
+    }
+
+    #[test]
+    fn max_active_escrows_caps_commits_until_one_settles() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        engine.max_active_escrows = Some(2);
+
+        let first = commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+        commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+        assert_eq!(engine.active_escrow_count(), 2);
+
+        let err = engine
+            .buyer_commit(
+                "buyer".into(),
+                "seller".into(),
+                1_000,
+                PaymentProfile::pizza_delivery(),
+                1,
+                "0xcommit3".into(),
+            )
+            .unwrap_err();
+        assert_eq!(err, EngineError::EscrowLimitReached(2));
+
+        engine.seller_accept(&first, "0xaccept".into()).unwrap();
+        engine.seller_fulfill(&first, "0xfulfill".into()).unwrap();
+        engine.seller_claim(&first, "0xclaim".into()).unwrap();
+
+        // Claimed escrow is terminal and no longer counts
+        assert_eq!(engine.active_escrow_count(), 1);
+        commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+    }
+
+    #[test]
+    fn prune_terminal_removes_only_old_settled_escrows() {
+        use crate::receipt_sink::VecSink;
+
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let archive = VecSink::new();
+        engine.set_archive_sink(Box::new(archive.clone()));
+
+        let settle = |engine: &mut CoreProverEngine| {
+            let id = commit(engine, PaymentProfile::pizza_delivery(), 1_000);
+            engine.seller_accept(&id, "0xaccept".into()).unwrap();
+            engine.seller_fulfill(&id, "0xfulfill".into()).unwrap();
+            engine.seller_claim(&id, "0xclaim".into()).unwrap();
+            id
+        };
+
+        let old = settle(&mut engine); // settled at GENESIS
+        let open = commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+        engine.advance_time(86_400);
+        let recent = settle(&mut engine); // settled at GENESIS + 1 day
+
+        let pruned = engine.prune_terminal(GENESIS + 3_600);
+        assert_eq!(pruned, 1);
+
+        assert!(engine.get_state(&old).is_err());
+        assert!(engine.get_receipt(&old).is_none());
+        assert_eq!(engine.get_state(&recent).unwrap(), EscrowState::SellerClaimed);
+        assert!(engine.get_receipt(&recent).is_some());
+        assert_eq!(engine.get_state(&open).unwrap(), EscrowState::BuyerCommitted);
+
+        let archived = archive.receipts();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].session_id, old);
+
+        // Non-terminal escrows survive any cutoff
+        assert_eq!(engine.prune_terminal(u64::MAX), 1);
+        assert_eq!(engine.get_state(&open).unwrap(), EscrowState::BuyerCommitted);
+    }
+
+    #[test]
+    fn state_view_reads_never_block_on_writes() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let ids: Vec<[u8; 32]> = (0..50)
+            .map(|_| commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000))
+            .collect();
+
+        let reader = engine.state_reader();
+        let done = Arc::new(AtomicBool::new(false));
+
+        let readers: Vec<_> = (0..8)
+            .map(|_| {
+                let reader = reader.clone();
+                let ids = ids.clone();
+                let done = done.clone();
+                std::thread::spawn(move || {
+                    let mut reads = 0u64;
+                    while !done.load(Ordering::Acquire) || reads < 10_000 {
+                        let view = reader.load();
+                        for id in &ids {
+                            let state = view.get(id).expect("committed escrow visible");
+                            assert!(matches!(
+                                state,
+                                EscrowState::BuyerCommitted | EscrowState::SellerAccepted
+                            ));
+                            reads += 1;
+                        }
+                    }
+                    reads
+                })
+            })
+            .collect();
+
+        for id in &ids {
+            engine.seller_accept(id, "0xaccept".into()).unwrap();
+        }
+        done.store(true, Ordering::Release);
+
+        for handle in readers {
+            assert!(handle.join().unwrap() >= 10_000);
+        }
+
+        // A view taken after the writes sees every acceptance
+        let view = reader.load();
+        assert_eq!(view.len(), ids.len());
+        assert!(ids.iter().all(|id| view.get(id) == Some(EscrowState::SellerAccepted)));
+        assert_eq!(view.get(&ids[0]), engine.get_state(&ids[0]).ok());
+    }
+
+    #[test]
+    fn iso_offset_renders_local_time_with_same_unix() {
+        // 1_700_000_000 = 2023-11-14T22:13:20Z
+        let mut engine = CoreProverEngine::new(369, 12, 1_700_000_000);
+        assert_eq!(engine.now().iso, "2023-11-14T22:13:20+00:00");
+
+        engine.iso_offset_minutes = 120;
+        let now = engine.now();
+        assert_eq!(now.iso, "2023-11-15T00:13:20+02:00");
+        assert_eq!(now.unix, 1_700_000_000);
+
+        engine.iso_offset_minutes = -330;
+        assert_eq!(engine.now().iso, "2023-11-14T16:43:20-05:30");
+    }
+
+    #[test]
+    fn sub_interval_advances_move_block_height() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let order_id = commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+        engine.seller_accept(&order_id, "0xaccept".into()).unwrap();
+        engine.seller_fulfill(&order_id, "0xfulfill".into()).unwrap();
+
+        // Each 5s step is under the 12s interval, but 15s in total crosses
+        // a block boundary
+        for _ in 0..3 {
+            engine.advance_time(5);
+        }
+        assert_eq!(engine.current_block_height, 2);
+
+        engine.seller_claim(&order_id, "0xclaim".into()).unwrap();
+        assert_eq!(engine.get_escrow(&order_id).unwrap().seller_block_height, Some(2));
+
+        engine.advance_time(9);
+        assert_eq!(engine.current_block_height, 3);
+        assert!(engine.verify_clock_consistency().is_ok());
+    }
+
+    #[test]
+    fn desynced_snapshot_clock_is_rejected() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        for _ in 0..5 {
+            engine.advance_time(5); // sub-block steps still accumulate
+        }
+        assert_eq!(engine.current_block_height, 1 + 25 / 12);
+        assert!(engine.verify_clock_consistency().is_ok());
+
+        let snapshot = engine.snapshot().unwrap();
+        assert!(CoreProverEngine::from_snapshot(&snapshot).is_ok());
+
+        // Re-checksummed body whose unix clock ran ahead of mono
+        let mut body: serde_json::Value = serde_json::from_str(&snapshot.body).unwrap();
+        body["current_unix"] = serde_json::json!(GENESIS + 25 + 3_600);
+        let body = body.to_string();
+        let desynced = EngineSnapshot {
+            version: SNAPSHOT_VERSION,
+            checksum: snapshot_checksum(&body),
+            body,
+        };
+
+        let err = CoreProverEngine::from_snapshot(&desynced).err().unwrap();
+        assert!(matches!(err, EngineError::Restore(msg) if msg.contains("clock desync")));
+
+        engine.current_block_height = 99;
+        assert!(engine.verify_clock_consistency().is_err());
+    }
+
+    #[test]
+    fn bulk_commit_is_all_or_nothing() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let spec = |txid: &str, seller: &str| CommitSpec {
+            buyer: "buyer".into(),
+            seller: seller.into(),
+            amount: 1_000,
+            profile: PaymentProfile::pizza_delivery(),
+            buyer_chain_id: 1,
+            buyer_commit_txid: txid.into(),
+        };
+
+        // Third spec is self-dealing: nothing is committed
+        let err = engine
+            .bulk_commit(vec![spec("0xa", "seller"), spec("0xb", "seller"), spec("0xc", "buyer")])
+            .unwrap_err();
+        assert!(matches!(err, EngineError::BatchRejected { index: 2, .. }), "{}", err);
+        assert_eq!(engine.active_escrow_count(), 0);
+        assert!(engine.state_reader().load().is_empty());
+
+        let ids = engine
+            .bulk_commit(vec![spec("0xa", "seller"), spec("0xb", "seller"), spec("0xc", "seller")])
+            .unwrap();
+        assert_eq!(ids.len(), 3);
+        for id in &ids {
+            assert_eq!(engine.get_state(id).unwrap(), EscrowState::BuyerCommitted);
+            assert_eq!(
+                engine.get_escrow(id).unwrap().acceptance_deadline_mono,
+                PaymentProfile::pizza_delivery().timing.acceptance_window_secs
+            );
+        }
+    }
+
+    #[test]
+    fn snapshot_between_fulfill_and_claim_keeps_stub() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let settled = commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+        engine.seller_accept(&settled, "0xaccept".into()).unwrap();
+        engine.seller_fulfill(&settled, "0xfulfill".into()).unwrap();
+        engine.seller_claim(&settled, "0xclaim".into()).unwrap();
+
+        let pending = commit(&mut engine, PaymentProfile::pizza_delivery(), 2_000);
+        engine.seller_accept(&pending, "0xaccept2".into()).unwrap();
+        engine.seller_fulfill(&pending, "0xfulfill2".into()).unwrap();
+
+        let mut restored = CoreProverEngine::from_snapshot(&engine.snapshot().unwrap()).unwrap();
+        let stub = restored.get_receipt(&pending).unwrap();
+        assert_eq!(stub.seller_fulfill_txid, "0xfulfill2");
+        assert_eq!(stub.settlement_unix, 0);
+        assert_eq!(restored.orphaned_stubs(), vec![pending]);
+
+        // The restored stub finalizes normally
+        restored.seller_claim(&pending, "0xclaim2".into()).unwrap();
+        assert!(restored.orphaned_stubs().is_empty());
+    }
+
+    #[test]
+    fn all_txids_lists_a_claimed_order() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let order_id = commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+        engine.seller_accept(&order_id, "0xaccept".into()).unwrap();
+        engine.seller_fulfill(&order_id, "0xfulfill".into()).unwrap();
+        engine.seller_claim(&order_id, "0xclaim".into()).unwrap();
+
+        let open = commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+
+        assert_eq!(
+            engine.all_txids(),
+            vec![
+                (TxidKind::BuyerCommit, "0xcommit".to_string(), order_id),
+                (TxidKind::SellerAccept, "0xaccept".to_string(), order_id),
+                (TxidKind::SellerFulfill, "0xfulfill".to_string(), order_id),
+                (TxidKind::SellerClaim, "0xclaim".to_string(), order_id),
+                (TxidKind::BuyerCommit, "0xcommit".to_string(), open),
+            ]
+        );
+    }
+
+    #[test]
+    fn timed_release_waits_out_grace() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        engine.timed_release_grace_secs = 30;
+        let profile = PaymentProfile::pizza_delivery();
+
+        let order_id = commit(&mut engine, profile.clone(), 1_000);
+        engine.seller_accept(&order_id, "0xaccept".into()).unwrap();
+        engine.seller_fulfill(&order_id, "0xfulfill".into()).unwrap();
+
+        engine.advance_time(profile.timing.claim_window_secs + 29);
+        let err = engine.timed_release(&order_id).unwrap_err();
+        assert_eq!(err, EngineError::WindowNotExpired("timed_release grace"));
+        assert_eq!(engine.get_state(&order_id).unwrap(), EscrowState::SellerFulfilled);
+
+        engine.advance_time(1);
+        assert_eq!(engine.timed_release(&order_id), Ok(1_000));
+        assert_eq!(engine.get_state(&order_id).unwrap(), EscrowState::SellerClaimed);
+    }
+
+    #[test]
+    fn seller_decline_refunds_buyer_before_fulfillment() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let order_id = commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+        engine.seller_accept(&order_id, "0xaccept".into()).unwrap();
+
+        let refund = engine
+            .seller_decline(&order_id, "0xdecline".into(), "oven broken".into())
+            .unwrap();
+        assert_eq!(refund, 1_000);
+        assert_eq!(engine.get_state(&order_id).unwrap(), EscrowState::SellerRefunded);
+
+        let receipt = engine.get_receipt(&order_id).unwrap();
+        assert_eq!(receipt.decline_reason.as_deref(), Some("oven broken"));
+        assert_eq!(receipt.seller_refund_txid.as_deref(), Some("0xdecline"));
+        assert_eq!(receipt.buyer_refund_amount, 1_000);
+        assert!(engine.orphaned_stubs().is_empty());
+
+        // Too late once fulfilled
+        let fulfilled = commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+        engine.seller_accept(&fulfilled, "0xaccept2".into()).unwrap();
+        engine.seller_fulfill(&fulfilled, "0xfulfill2".into()).unwrap();
+        let err = engine
+            .seller_decline(&fulfilled, "0xdecline2".into(), "changed mind".into())
+            .unwrap_err();
+        assert_eq!(
+            err,
+            EngineError::InvalidState {
+                current: EscrowState::SellerFulfilled,
+                operation: "seller_decline"
+            }
+        );
+    }
+
+    #[test]
+    fn audit_report_describes_late_claim() {
+        let mut engine = CoreProverEngine::new(1, 12, GENESIS);
+        let profile = PaymentProfile::pizza_delivery();
+
+        let order_id = commit(&mut engine, profile.clone(), 10_000);
+        engine.seller_accept(&order_id, "0xaccept".into()).unwrap();
+        engine.advance_time(profile.timing.fulfillment_window_secs + 60);
+        engine.seller_fulfill(&order_id, "0xfulfill".into()).unwrap();
+        engine.seller_claim(&order_id, "0xclaim".into()).unwrap();
+
+        let report = engine.audit_report(&order_id).unwrap();
+        assert!(report.contains("Buyer buyer committed 10000"));
+        assert!(report.contains("fulfilled the order LATE"));
+        assert!(report.contains(&format!("{}% discount", profile.late_discount_pct)));
+        assert!(report.contains("the seller was paid 10000"));
+        assert!(report.contains("controller fee of 0."));
+        for txid in ["0xcommit", "0xaccept", "0xfulfill", "0xclaim"] {
+            assert!(report.contains(txid), "missing {} in:\n{}", txid, report);
+        }
+
+        assert!(engine.audit_report(&[0xff; 32]).is_err());
+    }
+
+    #[test]
+    fn audit_report_shows_fee_split_on_claim() {
+        let mut engine = CoreProverEngine::new(1, 12, GENESIS);
+        let order_id = commit(&mut engine, PaymentProfile::pizza_delivery(), 10_000);
+        engine.seller_accept_with_fee(&order_id, "0xaccept".into(), 250).unwrap();
+        engine.seller_fulfill(&order_id, "0xfulfill".into()).unwrap();
+        engine.seller_claim(&order_id, "0xclaim".into()).unwrap();
+
+        let report = engine.audit_report(&order_id).unwrap();
+        assert!(report.contains("the seller was paid 9750"), "{}", report);
+        assert!(report.contains("controller fee of 250."), "{}", report);
+    }
+
+    #[test]
+    fn profile_windows_below_floor_are_rejected() {
+        let mut engine = CoreProverEngine::new(1, 12, GENESIS);
+        engine.min_acceptance_window_secs = 600;
+        engine.min_fulfillment_window_secs = 1800;
+        engine.min_claim_window_secs = 3600;
+
+        let mut profile = PaymentProfile::pizza_delivery();
+        profile.timing.acceptance_window_secs = 599;
+        let err = engine
+            .buyer_commit("buyer".into(), "seller".into(), 100, profile, 1, "0xa".into())
+            .unwrap_err();
+        let below_floor = "acceptance window 599s";
+        assert!(
+            matches!(&err, EngineError::InvalidArgument(msg) if msg.contains(below_floor)),
+            "{}",
+            err
+        );
+
+        let mut profile = PaymentProfile::pizza_delivery();
+        profile.timing.claim_window_secs = 60;
+        assert!(matches!(
+            engine.buyer_commit("buyer".into(), "seller".into(), 100, profile, 1, "0xb".into()),
+            Err(EngineError::InvalidArgument(msg)) if msg.contains("claim window")
+        ));
+        assert_eq!(engine.active_escrow_count(), 0);
+
+        // Exactly at every floor
+        let mut profile = PaymentProfile::pizza_delivery();
+        profile.timing.acceptance_window_secs = 600;
+        profile.timing.fulfillment_window_secs = 1800;
+        profile.timing.claim_window_secs = 3600;
+        commit(&mut engine, profile, 100);
+        assert_eq!(engine.active_escrow_count(), 1);
+    }
+
+    #[test]
+    fn rebuild_from_events_matches_direct_calls() {
+        let mut engine = CoreProverEngine::new(1, 12, GENESIS);
+        let profile = PaymentProfile::pizza_delivery();
+
+        let claimed = commit(&mut engine, profile.clone(), 1_000);
+        let expired = commit(&mut engine, profile.clone(), 2_000);
+        engine.reassign_seller(&claimed, "seller2".into()).unwrap();
+        engine.seller_accept(&claimed, "0xaccept1".into()).unwrap();
+        engine.seller_accept(&expired, "0xaccept2".into()).unwrap();
+        engine.advance_time(profile.timing.fulfillment_window_secs + 1);
+        engine.update_state(&expired).unwrap();
+        engine.seller_fulfill(&claimed, "0xfulfill1".into()).unwrap();
+        engine.seller_claim(&claimed, "0xclaim1".into()).unwrap();
+        engine.buyer_withdraw(&expired, Some("0xwithdraw2".into())).unwrap();
+
+        let logs: HashMap<_, _> = engine
+            .escrows
+            .values()
+            .map(|e| (e.order_id, e.events.clone()))
+            .collect();
+
+        let mut rebuilt = CoreProverEngine::new(1, 12, GENESIS);
+        rebuilt.rebuild_from_events(logs.clone()).unwrap();
+
+        for id in [claimed, expired] {
+            assert_eq!(rebuilt.get_escrow(&id).unwrap(), engine.get_escrow(&id).unwrap());
+        }
+        assert_eq!(rebuilt.get_state(&claimed).unwrap(), EscrowState::SellerClaimed);
+        assert_eq!(rebuilt.get_state(&expired).unwrap(), EscrowState::BuyerWithdrawn);
+
+        // new commits do not collide with rebuilt ids
+        let next = commit(&mut rebuilt, profile, 3_000);
+        assert!(next != claimed && next != expired);
+
+        // a log with an invalid transition leaves the engine untouched
+        let mut bad = logs;
+        bad.get_mut(&claimed).unwrap().swap(2, 3);
+        assert!(rebuilt.rebuild_from_events(bad).is_err());
+        assert_eq!(rebuilt.active_escrow_count(), 1);
+    }
+
+    #[test]
+    fn refund_eligibility_follows_withdraw_guards() {
+        let mut engine = CoreProverEngine::new(1, 12, GENESIS);
+        let mut profile = PaymentProfile::pizza_delivery();
+        profile.acceptance_timeout_buyer_penalty_bps = 500;
+        let window = profile.timing.acceptance_window_secs;
+
+        let waiting = commit(&mut engine, profile.clone(), 1_000);
+        let accepted = commit(&mut engine, profile.clone(), 1_000);
+        engine.seller_accept(&accepted, "0xaccept".into()).unwrap();
+
+        engine.advance_time(window - 10);
+        assert_eq!(
+            engine.refund_eligibility(&waiting).unwrap(),
+            RefundEligibility::NotYet { seconds_remaining: 11 }
+        );
+        assert!(matches!(
+            engine.refund_eligibility(&accepted).unwrap(),
+            RefundEligibility::Locked { .. }
+        ));
+
+        // Past the acceptance deadline: eligible, net of the penalty
+        engine.advance_time(11);
+        assert_eq!(
+            engine.refund_eligibility(&waiting).unwrap(),
+            RefundEligibility::Eligible { amount: 950 }
+        );
+        assert_eq!(engine.buyer_withdraw(&waiting, None).unwrap(), 950);
+        assert_eq!(
+            engine.refund_eligibility(&waiting).unwrap(),
+            RefundEligibility::TerminalAlready(EscrowState::BuyerWithdrawn)
+        );
+
+        // Fulfillment window lapses: eligible in full
+        engine.advance_time(profile.timing.fulfillment_window_secs);
+        engine.update_state(&accepted).unwrap();
+        assert_eq!(engine.get_state(&accepted).unwrap(), EscrowState::FulfillmentExpired);
+        assert_eq!(
+            engine.refund_eligibility(&accepted).unwrap(),
+            RefundEligibility::Eligible { amount: 1_000 }
+        );
+    }
+
+    #[test]
+    fn swept_unfulfilled_escrow_cannot_settle() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let profile = PaymentProfile::pizza_delivery();
+        let id = commit(&mut engine, profile.clone(), 1_000);
+        engine.seller_accept(&id, "0xaccept".into()).unwrap();
+
+        engine.advance_time(profile.timing.fulfillment_window_secs + 1);
+        engine.sweep_expirations();
+
+        assert!(engine.seller_claim(&id, "0xclaim".into()).is_err());
+        assert!(engine.seller_refund(&id, "0xrefund".into()).is_err());
+        assert_eq!(engine.get_state(&id).unwrap(), EscrowState::FulfillmentExpired);
+        assert!(engine.get_receipt(&id).is_none());
+    }
+
+    #[test]
+    fn merge_views_combines_disjoint_shards() {
+        let profile = PaymentProfile::pizza_delivery();
+        let mut a = CoreProverEngine::new(369, 12, GENESIS);
+        let mut b = CoreProverEngine::new(369, 12, GENESIS);
+
+        // Shard b starts its session counter past shard a's ids
+        b.next_session_counter = 1_000;
+
+        let settled = commit(&mut a, profile.clone(), 1_000);
+        a.seller_accept(&settled, "0xaccept".into()).unwrap();
+        a.seller_fulfill(&settled, "0xfulfill".into()).unwrap();
+        a.seller_claim(&settled, "0xclaim".into()).unwrap();
+        commit(&mut a, profile.clone(), 2_000);
+        commit(&mut b, profile.clone(), 3_000);
+        commit(&mut b, profile.clone(), 4_000);
+
+        // Acceptance timeout withdrawal on shard b
+        let withdrawn = commit(&mut b, profile.clone(), 5_000);
+        b.advance_time(profile.timing.acceptance_window_secs + 1);
+        b.buyer_withdraw(&withdrawn, Some("0xwithdraw".into())).unwrap();
+
+        let view = a.merge_views(&[&b]).unwrap();
+        assert_eq!(view.shard_count, 2);
+        assert_eq!(view.escrow_count, 5);
+        assert_eq!(view.total_value_locked, 9_000);
+        assert_eq!(view.receipt_count, 2);
+        assert_eq!(view.finalized_receipt_count, 2);
+        assert_eq!(view.settlement_latencies.len(), 2);
+        assert!(view.state_counts.contains(&(EscrowState::BuyerCommitted, 3)));
+        assert!(view.state_counts.contains(&(EscrowState::SellerClaimed, 1)));
+        assert!(view.state_counts.contains(&(EscrowState::BuyerWithdrawn, 1)));
+    }
+
+    #[test]
+    fn merge_views_rejects_order_id_collisions() {
+        let mut a = CoreProverEngine::new(369, 12, GENESIS);
+        let mut b = CoreProverEngine::new(369, 12, GENESIS);
+        commit(&mut a, PaymentProfile::pizza_delivery(), 1_000);
+        commit(&mut b, PaymentProfile::pizza_delivery(), 1_000);
+
+        let err = a.merge_views(&[&b]).unwrap_err();
+        assert!(matches!(err, EngineError::OrderIdCollision { shards: (0, 1), .. }), "{}", err);
+    }
+
+    #[test]
+    fn escrow_store_keeps_insertion_order() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let ids: Vec<[u8; 32]> = (0..50)
+            .map(|i| {
+                engine
+                    .buyer_commit(
+                        "buyer".into(),
+                        "seller".into(),
+                        1_000,
+                        PaymentProfile::pizza_delivery(),
+                        1,
+                        format!("0xcommit{}", i),
+                    )
+                    .unwrap()
+            })
+            .collect();
+
+        let listed: Vec<[u8; 32]> = engine.all_txids().iter().map(|(_, _, id)| *id).collect();
+        assert_eq!(listed, ids);
+
+        // Restored engines iterate in the same order and checksum identically
+        let snapshot = engine.snapshot().unwrap();
+        let restored = CoreProverEngine::from_snapshot(&snapshot).unwrap();
+        assert_eq!(restored.all_txids(), engine.all_txids());
+        assert_eq!(restored.snapshot().unwrap(), snapshot);
+        assert_eq!(restored.get_state(&ids[49]).unwrap(), EscrowState::BuyerCommitted);
+    }
+
+    #[test]
+    fn out_of_order_claims_finalize_their_own_receipts() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let profile = PaymentProfile::pizza_delivery();
+        let first = commit(&mut engine, profile.clone(), 1_000);
+        let second = commit(&mut engine, profile, 2_000);
+
+        for (id, n) in [(first, 1), (second, 2)] {
+            engine.seller_accept(&id, format!("0xaccept{}", n)).unwrap();
+            engine.seller_fulfill(&id, format!("0xfulfill{}", n)).unwrap();
+        }
+
+        engine.advance_time(60);
+        engine.seller_claim(&second, "0xclaim2".into()).unwrap();
+        engine.advance_time(60);
+        engine.seller_claim(&first, "0xclaim1".into()).unwrap();
+
+        for (id, n) in [(first, 1), (second, 2)] {
+            let receipt = engine.get_receipt(&id).unwrap();
+            assert_eq!(receipt.seller_fulfill_txid, format!("0xfulfill{}", n));
+            assert_eq!(receipt.seller_claim_txid, Some(format!("0xclaim{}", n)));
+        }
+        let first_receipt = engine.get_receipt(&first).unwrap();
+        let second_receipt = engine.get_receipt(&second).unwrap();
+        assert!(second_receipt.settlement_mono < first_receipt.settlement_mono);
+
+        // Each stub is finalized once
+        let fulfilled_at = Some(first_receipt.fulfillment_mono);
+        assert_eq!(
+            engine.receipt_stub_index(&first, fulfilled_at, Some("0xfulfill1")),
+            Err(EngineError::ReceiptStub("already finalized"))
+        );
+        assert!(engine.receipt_stub_index(&first, Some(0), Some("0xother")).is_err());
+        assert!(engine.receipt_stub_index(&first, None, None).is_err());
+    }
+
+    #[test]
+    fn late_fulfillment_relocks_withdrawal() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let profile = PaymentProfile::pizza_delivery();
+        let swept = commit(&mut engine, profile.clone(), 1_000);
+        let direct = commit(&mut engine, profile.clone(), 1_000);
+        engine.seller_accept(&swept, "0xaccept1".into()).unwrap();
+        engine.seller_accept(&direct, "0xaccept2".into()).unwrap();
+
+        engine.advance_time(profile.timing.fulfillment_window_secs + 1);
+        engine.sweep_expirations();
+        assert_eq!(engine.get_state(&swept).unwrap(), EscrowState::FulfillmentExpired);
+        assert!(matches!(
+            engine.refund_eligibility(&swept).unwrap(),
+            RefundEligibility::Eligible { .. }
+        ));
+
+        // FulfillmentExpired -> LateFulfilled re-locks the buyer's funds
+        engine.seller_fulfill(&swept, "0xfulfill1".into()).unwrap();
+        assert_eq!(engine.get_state(&swept).unwrap(), EscrowState::LateFulfilled);
+        let err = engine.buyer_withdraw(&swept, Some("0xwithdraw".into())).unwrap_err();
+        assert_eq!(err, EngineError::WithdrawalLocked);
+        assert!(engine.seller_fulfill(&swept, "0xfulfill1b".into()).is_err());
+
+        // Unswept escrows go straight from SellerAccepted to LateFulfilled
+        engine.seller_fulfill(&direct, "0xfulfill2".into()).unwrap();
+        assert_eq!(engine.get_state(&direct).unwrap(), EscrowState::LateFulfilled);
+
+        let receipt = engine.get_receipt(&swept).unwrap();
+        assert!(receipt.late_fulfilled);
+        assert_eq!(receipt.discount_pct, profile.late_discount_pct);
+
+        engine.seller_claim(&swept, "0xclaim".into()).unwrap();
+        assert_eq!(engine.get_state(&swept).unwrap(), EscrowState::SellerClaimed);
+
+        engine.advance_time(profile.timing.claim_window_secs);
+        assert_eq!(engine.timed_release(&direct).unwrap(), 1_000);
+        assert_eq!(engine.get_state(&direct).unwrap(), EscrowState::SellerClaimed);
+    }
+
+    #[test]
+    fn order_ids_do_not_collide_past_the_old_16_bit_counter() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+
+        // Identical commit inputs: only the counter tells the ids apart
+        let ids: std::collections::HashSet<[u8; 32]> = (0..100_000)
+            .map(|_| engine.generate_order_id("buyer", "seller", 1_000))
+            .collect();
+        assert_eq!(ids.len(), 100_000);
+
+        // Deterministic for identical inputs and counter
+        let mut a = CoreProverEngine::new(369, 12, GENESIS);
+        let mut b = CoreProverEngine::new(369, 12, GENESIS);
+        assert_eq!(
+            a.generate_order_id("buyer", "seller", 1_000),
+            b.generate_order_id("buyer", "seller", 1_000)
+        );
+        assert_ne!(
+            a.generate_order_id("buyer", "seller", 1_000),
+            b.generate_order_id("buyer", "seller2", 1_000)
+        );
+    }
+
+    #[test]
+    fn partial_claims_settle_in_two_halves() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let id = commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+        engine.seller_accept(&id, "0xaccept".into()).unwrap();
+
+        // Not before fulfillment
+        assert!(engine.seller_claim_partial(&id, 500, "0xpart1".into()).is_err());
+        engine.seller_fulfill(&id, "0xfulfill".into()).unwrap();
+
+        assert_eq!(engine.seller_claim_partial(&id, 500, "0xpart1".into()).unwrap(), 500);
+        assert_eq!(engine.get_state(&id).unwrap(), EscrowState::PartiallyClaimed);
+        assert!(engine.seller_refund(&id, "0xrefund".into()).is_err());
+        assert!(engine.get_receipt(&id).unwrap().seller_claim_txid.is_none());
+
+        assert_eq!(engine.seller_claim_partial(&id, 500, "0xpart2".into()).unwrap(), 0);
+        assert_eq!(engine.get_state(&id).unwrap(), EscrowState::SellerClaimed);
+
+        let receipt = engine.get_receipt(&id).unwrap();
+        assert_eq!(receipt.partial_claim_txids, vec!["0xpart1", "0xpart2"]);
+        assert_eq!(receipt.seller_claim_txid.as_deref(), Some("0xpart2"));
+
+        // The event log rebuilds the same escrow
+        let rebuilt = Escrow::replay_events(engine.get_events(&id).unwrap()).unwrap();
+        assert_eq!(&rebuilt, engine.get_escrow(&id).unwrap());
+    }
+
+    #[test]
+    fn partial_over_claim_is_rejected() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let id = commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+        engine.seller_accept(&id, "0xaccept".into()).unwrap();
+        engine.seller_fulfill(&id, "0xfulfill".into()).unwrap();
+
+        assert!(engine.seller_claim_partial(&id, 1_001, "0xtoo_much".into()).is_err());
+        assert!(engine.seller_claim_partial(&id, 0, "0xnothing".into()).is_err());
+        assert_eq!(engine.get_state(&id).unwrap(), EscrowState::SellerFulfilled);
+
+        engine.seller_claim_partial(&id, 600, "0xpart1".into()).unwrap();
+        let err = engine.seller_claim_partial(&id, 401, "0xpart2".into()).unwrap_err();
+        let over = "unclaimed balance 400";
+        assert!(
+            matches!(&err, EngineError::InvalidArgument(msg) if msg.contains(over)),
+            "{}",
+            err
+        );
+
+        // A full claim releases only the remainder
+        assert_eq!(engine.seller_claim_amount(&id, "0xclaim".into()).unwrap(), 400);
+        assert_eq!(engine.get_escrow(&id).unwrap().claimed_so_far, 1_000);
+    }
+
+    #[test]
+    fn seller_claim_deducts_negotiated_fee() {
+        // (fee_bps, seller_amount, fee_amount) for a 10_001 escrow
+        let cases = [(0, 10_001, 0), (1, 10_000, 1), (30, 9_971, 30), (250, 9_751, 250)];
+
+        for (fee_bps, seller_amount, fee_amount) in cases {
+            let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+            let id = commit(&mut engine, PaymentProfile::pizza_delivery(), 10_001);
+            engine.seller_accept_with_fee(&id, "0xaccept".into(), fee_bps).unwrap();
+            engine.seller_fulfill(&id, "0xfulfill".into()).unwrap();
+
+            let settlement = engine.seller_claim(&id, "0xclaim".into()).unwrap();
+            assert_eq!(settlement, Settlement { seller_amount, fee_amount }, "{} bps", fee_bps);
+            assert_eq!(settlement.total(), 10_001);
+
+            let receipt = engine.get_receipt(&id).unwrap();
+            assert_eq!(receipt.seller_amount, seller_amount as u128);
+            assert_eq!(receipt.fee_amount, fee_amount as u128);
+
+            // The fee survives a replay of the event log
+            let rebuilt = Escrow::replay_events(engine.get_events(&id).unwrap()).unwrap();
+            assert_eq!(rebuilt.fee_bps, fee_bps);
+        }
+    }
+
+    #[test]
+    fn fee_applies_to_every_partial_claim() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let id = commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+        engine.seller_accept_with_fee(&id, "0xaccept".into(), 100).unwrap();
+        engine.seller_fulfill(&id, "0xfulfill".into()).unwrap();
+
+        engine.seller_claim_partial(&id, 600, "0xpart1".into()).unwrap();
+        assert_eq!(engine.seller_claim_amount(&id, "0xclaim".into()), Ok(396));
+
+        let receipt = engine.get_receipt(&id).unwrap();
+        assert_eq!(receipt.seller_amount, 594 + 396);
+        assert_eq!(receipt.fee_amount, 6 + 4);
+
+        // Taking everything through partial claims still pays the fee
+        let id = commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+        engine.seller_accept_with_fee(&id, "0xaccept".into(), 100).unwrap();
+        engine.seller_fulfill(&id, "0xfulfill".into()).unwrap();
+        engine.seller_claim_partial(&id, 500, "0xpart1".into()).unwrap();
+        engine.seller_claim_partial(&id, 500, "0xpart2".into()).unwrap();
+
+        let receipt = engine.get_receipt(&id).unwrap();
+        assert_eq!(receipt.seller_amount, 990);
+        assert_eq!(receipt.fee_amount, 10);
+    }
+
+    #[test]
+    fn timed_release_deducts_negotiated_fee() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let profile = PaymentProfile::pizza_delivery();
+        let id = commit(&mut engine, profile.clone(), 1_000);
+        engine.seller_accept_with_fee(&id, "0xaccept".into(), 250).unwrap();
+        engine.seller_fulfill(&id, "0xfulfill".into()).unwrap();
+        engine.advance_time(profile.timing.claim_window_secs);
+
+        assert_eq!(engine.timed_release(&id), Ok(975));
+
+        let receipt = engine.get_receipt(&id).unwrap();
+        assert_eq!(receipt.seller_amount, 975);
+        assert_eq!(receipt.fee_amount, 25);
+    }
+
+    #[test]
+    fn dispute_payouts_deduct_fee_only_when_paying_seller() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let profile = PaymentProfile::pizza_delivery();
+
+        let paid = commit(&mut engine, profile.clone(), 1_000);
+        let refunded = commit(&mut engine, profile, 1_000);
+        for id in [&paid, &refunded] {
+            engine.seller_accept_with_fee(id, "0xaccept".into(), 30).unwrap();
+            engine.seller_fulfill(id, "0xfulfill".into()).unwrap();
+            engine.open_dispute(id, Party::Buyer, [1u8; 32]).unwrap();
+        }
+
+        let pay = DisputeOutcome::PaySeller;
+        assert_eq!(engine.resolve_dispute(&paid, pay, "arbiter".into(), "0xp".into()), Ok(997));
+        let receipt = engine.get_receipt(&paid).unwrap();
+        assert_eq!((receipt.seller_amount, receipt.fee_amount), (997, 3));
+
+        let refund = DisputeOutcome::RefundBuyer;
+        let returned = engine.resolve_dispute(&refunded, refund, "arbiter".into(), "0xr".into());
+        assert_eq!(returned, Ok(1_000));
+        let receipt = engine.get_receipt(&refunded).unwrap();
+        assert_eq!((receipt.seller_amount, receipt.fee_amount), (0, 0));
+    }
+
+    #[test]
+    fn fee_above_100_percent_is_rejected() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let id = commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+
+        let err = engine.seller_accept_with_fee(&id, "0xaccept".into(), 10_001).unwrap_err();
+        assert!(matches!(err, EngineError::InvalidArgument(_)), "{}", err);
+        assert_eq!(engine.get_state(&id).unwrap(), EscrowState::BuyerCommitted);
+    }
+
+    fn disputed(engine: &mut CoreProverEngine, opener: Party) -> [u8; 32] {
+        let id = commit(engine, PaymentProfile::pizza_delivery(), 1_000);
+        engine.seller_accept(&id, "0xaccept".into()).unwrap();
+        engine.seller_fulfill(&id, "0xfulfill".into()).unwrap();
+        engine.open_dispute(&id, opener, [7u8; 32]).unwrap();
+        id
+    }
+
+    #[test]
+    fn dispute_cannot_open_before_fulfillment_or_after_settlement() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let id = commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+
+        let err = engine.open_dispute(&id, Party::Buyer, [1u8; 32]).unwrap_err();
+        assert!(
+            matches!(err, EngineError::InvalidState { current: EscrowState::BuyerCommitted, .. }),
+            "{}",
+            err
+        );
+        engine.seller_accept(&id, "0xaccept".into()).unwrap();
+        assert!(engine.open_dispute(&id, Party::Buyer, [1u8; 32]).is_err());
+
+        engine.seller_fulfill(&id, "0xfulfill".into()).unwrap();
+        engine.seller_claim(&id, "0xclaim".into()).unwrap();
+        assert!(engine.open_dispute(&id, Party::Seller, [1u8; 32]).is_err());
+        assert!(engine
+            .resolve_dispute(&id, DisputeOutcome::RefundBuyer, "arbiter".into(), "0xr".into())
+            .is_err());
+    }
+
+    #[test]
+    fn dispute_resolves_to_seller() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let id = disputed(&mut engine, Party::Buyer);
+        assert_eq!(engine.get_state(&id).unwrap(), EscrowState::Disputed);
+
+        // Frozen: no settlement, no timed release, even past the claim window
+        engine.advance_time(PaymentProfile::pizza_delivery().timing.claim_window_secs * 2);
+        assert!(engine.seller_claim(&id, "0xclaim".into()).is_err());
+        assert!(engine.seller_refund(&id, "0xrefund".into()).is_err());
+        let err = engine.timed_release(&id).unwrap_err();
+        assert_eq!(
+            err,
+            EngineError::InvalidState {
+                current: EscrowState::Disputed,
+                operation: "timed_release"
+            }
+        );
+
+        let paid = engine
+            .resolve_dispute(&id, DisputeOutcome::PaySeller, "arbiter-1".into(), "0xruling".into())
+            .unwrap();
+        assert_eq!(paid, 1_000);
+        assert_eq!(engine.get_state(&id).unwrap(), EscrowState::SellerClaimed);
+
+        let receipt = engine.get_receipt(&id).unwrap();
+        assert_eq!(receipt.seller_claim_txid.as_deref(), Some("0xruling"));
+        assert_eq!(receipt.dispute_evidence_hash, Some([7u8; 32]));
+        assert_eq!(receipt.dispute_resolver.as_deref(), Some("arbiter-1"));
+
+        let rebuilt = Escrow::replay_events(engine.get_events(&id).unwrap()).unwrap();
+        assert_eq!(&rebuilt, engine.get_escrow(&id).unwrap());
+    }
+
+    #[test]
+    fn dispute_resolves_to_buyer() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let id = disputed(&mut engine, Party::Seller);
+
+        assert!(engine.buyer_withdraw(&id, Some("0xwithdraw".into())).is_err());
+        let outcome = DisputeOutcome::RefundBuyer;
+        let refunded = engine
+            .resolve_dispute(&id, outcome, "arbiter-2".into(), "0xruling".into())
+            .unwrap();
+        assert_eq!(refunded, 1_000);
+        assert_eq!(engine.get_state(&id).unwrap(), EscrowState::SellerRefunded);
+
+        let receipt = engine.get_receipt(&id).unwrap();
+        assert_eq!(receipt.seller_refund_txid.as_deref(), Some("0xruling"));
+        assert!(receipt.seller_claim_txid.is_none());
+        assert_eq!(receipt.dispute_resolver.as_deref(), Some("arbiter-2"));
+
+        let report = engine.audit_report(&id).unwrap();
+        assert!(report.contains("Dispute: opened by the seller"), "{}", report);
+    }
+
+    #[test]
+    fn engine_event_log_follows_lifecycle() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let profile = PaymentProfile::pizza_delivery();
+
+        let claimed = commit(&mut engine, profile.clone(), 1_000);
+        engine.advance_time(60);
+        engine.seller_accept(&claimed, "0xaccept".into()).unwrap();
+        engine.advance_time(60);
+        engine.seller_fulfill(&claimed, "0xfulfill".into()).unwrap();
+        engine.seller_claim(&claimed, "0xclaim".into()).unwrap();
+
+        let refunded = commit(&mut engine, profile.clone(), 1_000);
+        engine.seller_accept(&refunded, "0xaccept".into()).unwrap();
+        engine.seller_fulfill(&refunded, "0xfulfill".into()).unwrap();
+        engine.seller_refund(&refunded, "0xrefund".into()).unwrap();
+
+        let released = commit(&mut engine, profile.clone(), 1_000);
+        engine.seller_accept(&released, "0xaccept".into()).unwrap();
+        engine.seller_fulfill(&released, "0xfulfill".into()).unwrap();
+        engine.advance_time(profile.timing.claim_window_secs);
+        engine.timed_release(&released).unwrap();
+
+        let withdrawn = commit(&mut engine, profile.clone(), 1_000);
+        engine.advance_time(profile.timing.acceptance_window_secs + 1);
+        engine.buyer_withdraw(&withdrawn, None).unwrap();
+
+        // failed operations leave no trace
+        assert!(engine.seller_claim(&withdrawn, "0xclaim".into()).is_err());
+
+        let kinds = |id: &[u8; 32]| -> Vec<EngineEventKind> {
+            engine.events_for(id).iter().map(|e| e.kind).collect()
+        };
+        use EngineEventKind::*;
+        assert_eq!(kinds(&claimed), vec![Committed, Accepted, Fulfilled, Claimed]);
+        assert_eq!(kinds(&refunded), vec![Committed, Accepted, Fulfilled, Refunded]);
+        assert_eq!(kinds(&released), vec![Committed, Accepted, Fulfilled, TimedRelease]);
+        assert_eq!(kinds(&withdrawn), vec![Committed, Withdrew]);
+        assert_eq!(engine.all_events().len(), 14);
+
+        let log = engine.events_for(&claimed);
+        assert_eq!((log[0].mono, log[0].unix, log[0].block_height), (0, GENESIS, 1));
+        assert_eq!((log[1].mono, log[1].unix, log[1].block_height), (60, GENESIS + 60, 6));
+        assert_eq!((log[3].mono, log[3].unix, log[3].block_height), (120, GENESIS + 120, 11));
+
+        // the engine-wide log is ordered across orders
+        let monos: Vec<u64> = engine.all_events().iter().map(|e| e.mono).collect();
+        assert!(monos.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(engine.all_events()[4].order_id, refunded);
+    }
+
+    #[test]
+    fn engine_event_log_survives_prune() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let profile = PaymentProfile::pizza_delivery();
+
+        let id = commit(&mut engine, profile, 1_000);
+        engine.seller_accept(&id, "0xaccept".into()).unwrap();
+        engine.seller_decline(&id, "0xrefund".into(), "out of stock".into()).unwrap();
+        engine.advance_time(3_600);
+        assert_eq!(engine.prune_terminal(GENESIS + 1), 1);
+
+        assert!(engine.get_events(&id).is_err());
+        let kinds: Vec<EngineEventKind> = engine.events_for(&id).iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![EngineEventKind::Committed, EngineEventKind::Accepted, EngineEventKind::Declined]
+        );
+        assert_eq!(engine.all_events().len(), 3);
+    }
+
+    fn reclaim_profile() -> PaymentProfile {
+        let mut profile = PaymentProfile::pizza_delivery();
+        profile.timing.buyer_dispute_window_secs = 7_200;
+        profile
+    }
+
+    #[test]
+    fn buyer_reclaim_too_early() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let profile = reclaim_profile();
+
+        let order_id = commit(&mut engine, profile.clone(), 1_000);
+        assert_eq!(
+            engine.buyer_reclaim(&order_id, "0xreclaim".into()),
+            Err(EngineError::InvalidState {
+                current: EscrowState::BuyerCommitted,
+                operation: "buyer_reclaim",
+            })
+        );
+
+        engine.seller_accept(&order_id, "0xaccept".into()).unwrap();
+        engine.seller_fulfill(&order_id, "0xfulfill".into()).unwrap();
+        engine.advance_time(profile.timing.buyer_dispute_window_secs - 1);
+        assert_eq!(
+            engine.buyer_reclaim(&order_id, "0xreclaim".into()),
+            Err(EngineError::WindowNotExpired("buyer_dispute"))
+        );
+        assert_eq!(engine.get_state(&order_id).unwrap(), EscrowState::SellerFulfilled);
+
+        // the seller can still claim inside the window
+        assert_eq!(engine.seller_claim_amount(&order_id, "0xclaim".into()), Ok(1_000));
+        engine.advance_time(1);
+        assert!(engine.buyer_reclaim(&order_id, "0xreclaim".into()).is_err());
+    }
+
+    #[test]
+    fn buyer_reclaim_after_window() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let mut profile = reclaim_profile();
+        profile.allows_timed_release = false;
+
+        let order_id = commit(&mut engine, profile.clone(), 1_000);
+        engine.seller_accept(&order_id, "0xaccept".into()).unwrap();
+        engine.seller_fulfill(&order_id, "0xfulfill".into()).unwrap();
+        engine.advance_time(profile.timing.buyer_dispute_window_secs);
+
+        assert_eq!(
+            engine.buyer_reclaim(&order_id, " ".into()),
+            Err(EngineError::MissingTxid("buyer_reclaim_txid"))
+        );
+        assert_eq!(engine.buyer_reclaim(&order_id, "0xreclaim".into()), Ok(1_000));
+        assert_eq!(engine.get_state(&order_id).unwrap(), EscrowState::BuyerReclaimed);
+        assert!(engine.seller_claim(&order_id, "0xclaim".into()).is_err());
+
+        let receipt = engine.get_receipt(&order_id).unwrap();
+        assert_eq!(receipt.buyer_withdraw_txid.as_deref(), Some("0xreclaim"));
+        assert_eq!(receipt.buyer_refund_amount, 1_000);
+        assert!(receipt.seller_claim_txid.is_none());
+        assert!(receipt.seller_refund_txid.is_none());
+        assert_eq!(receipt.settlement_mono, profile.timing.buyer_dispute_window_secs);
+
+        let kinds: Vec<_> = engine.events_for(&order_id).iter().map(|e| e.kind).collect();
+        assert_eq!(kinds.last(), Some(&EngineEventKind::Reclaimed));
+
+        let rebuilt = Escrow::replay_events(engine.get_events(&order_id).unwrap()).unwrap();
+        assert_eq!(&rebuilt, engine.get_escrow(&order_id).unwrap());
+    }
+
+    #[test]
+    fn buyer_reclaim_disabled_by_default() {
+        let mut engine = CoreProverEngine::new(369, 12, GENESIS);
+        let order_id = commit(&mut engine, PaymentProfile::pizza_delivery(), 1_000);
+        engine.seller_accept(&order_id, "0xaccept".into()).unwrap();
+        engine.seller_fulfill(&order_id, "0xfulfill".into()).unwrap();
+        engine.advance_time(365 * 86_400);
+
+        assert_eq!(
+            engine.buyer_reclaim(&order_id, "0xreclaim".into()),
+            Err(EngineError::ReclaimDisabled)
+        );
+    }
+
+    #[test]
+    fn iso8601_renders_in_range_timestamps() {
+        assert_eq!(iso8601(GENESIS).unwrap(), "2023-11-14T22:13:20+00:00");
+        assert_eq!(iso8601(0).unwrap(), "1970-01-01T00:00:00+00:00");
+        assert_eq!(iso8601_with_offset(0, 60).unwrap(), "1970-01-01T01:00:00+01:00");
+    }
+
+    #[test]
+    fn iso8601_rejects_out_of_range_timestamps() {
+        // chrono stops around year 262000; u64::MAX does not even fit i64
+        for unix in [10_000_000_000_000, u64::MAX] {
+            assert_eq!(iso8601(unix), Err(EngineError::TimestampOutOfRange(unix)));
+            assert_eq!(
+                iso8601_with_offset(unix, 120),
+                Err(EngineError::TimestampOutOfRange(unix))
+            );
+            assert_eq!(TimeTruth::new(0, unix).iso, "");
+        }
+    }
+}
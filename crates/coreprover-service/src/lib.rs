@@ -5,9 +5,11 @@ pub mod settlement;
 pub mod workers;
 pub mod profiles;
 pub mod engine;
+pub mod receipt_sink;
+pub mod state_view;
 pub mod types;
 
-pub use api::routes::create_router;
+pub use api::routes::{create_admin_router, create_router};
 
 /// Service configuration
 #[derive(Debug, Clone, serde::Deserialize)]
@@ -22,6 +24,21 @@ pub struct Config {
 pub struct ServerConfig {
     pub host: String,
     pub port: u16,
+    /// Port of the admin listener, which binds to loopback only
+    #[serde(default = "default_admin_port")]
+    pub admin_port: u16,
+}
+
+fn default_admin_port() -> u16 {
+    3001
+}
+
+impl ServerConfig {
+    /// Admin routes are unauthenticated, so they are only served on
+    /// loopback whatever `host` the public API binds to
+    pub fn admin_addr(&self) -> String {
+        format!("127.0.0.1:{}", self.admin_port)
+    }
 }
 
 #[derive(Debug, Clone, serde::Deserialize)]
@@ -40,6 +57,61 @@ pub struct BlockchainConfig {
     pub rpc_url: String,
     pub contract_address: String,
     pub chain_id: u64,
+    /// Seconds per block of the engine's simulated chain
+    #[serde(default = "default_block_interval_secs")]
+    pub block_interval_secs: u64,
+    /// Engine genesis; None = service start time
+    #[serde(default)]
+    pub genesis_unix: Option<u64>,
+}
+
+fn default_block_interval_secs() -> u64 {
+    12
+}
+
+impl BlockchainConfig {
+    /// Override chain timing from `COREPROVER_CHAIN_ID`,
+    /// `COREPROVER_BLOCK_INTERVAL_SECS` and `COREPROVER_GENESIS_UNIX`
+    pub fn apply_env_overrides(&mut self) -> anyhow::Result<()> {
+        self.apply_overrides(|key| std::env::var(key).ok())
+    }
+
+    /// `apply_env_overrides` with the variables read through `lookup`
+    pub fn apply_overrides(
+        &mut self,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> anyhow::Result<()> {
+        let parse = |key: &str| -> anyhow::Result<Option<u64>> {
+            lookup(key)
+                .map(|v| v.trim().parse::<u64>())
+                .transpose()
+                .map_err(|e| anyhow::anyhow!("{}: {}", key, e))
+        };
+
+        if let Some(chain_id) = parse("COREPROVER_CHAIN_ID")? {
+            self.chain_id = chain_id;
+        }
+        if let Some(secs) = parse("COREPROVER_BLOCK_INTERVAL_SECS")? {
+            self.block_interval_secs = secs;
+        }
+        if let Some(genesis) = parse("COREPROVER_GENESIS_UNIX")? {
+            self.genesis_unix = Some(genesis);
+        }
+
+        if self.block_interval_secs == 0 {
+            anyhow::bail!("block_interval_secs must be > 0");
+        }
+        Ok(())
+    }
+
+    /// Engine on this chain; `now_unix` is the genesis when none is configured
+    pub fn build_engine(&self, now_unix: u64) -> engine::CoreProverEngine {
+        engine::CoreProverEngine::new(
+            self.chain_id,
+            self.block_interval_secs,
+            self.genesis_unix.unwrap_or(now_unix),
+        )
+    }
 }
 
 impl Config {
@@ -49,4 +121,102 @@ impl Config {
         let config: Config = toml::from_str(&contents)?;
         Ok(config)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blockchain() -> BlockchainConfig {
+        BlockchainConfig {
+            rpc_url: "http://localhost:8545".to_string(),
+            contract_address: "0x0000000000000000000000000000000000000000".to_string(),
+            chain_id: 31337,
+            block_interval_secs: 12,
+            genesis_unix: None,
+        }
+    }
+
+    #[test]
+    fn custom_block_interval_sets_block_rate() {
+        let config = BlockchainConfig {
+            block_interval_secs: 2,
+            genesis_unix: Some(1_700_000_000),
+            ..blockchain()
+        };
+        let mut engine = config.build_engine(1_800_000_000);
+        assert_eq!(engine.genesis_unix(), 1_700_000_000);
+        assert_eq!(engine.current_block_height, 1);
+
+        engine.advance_time(1);
+        assert_eq!(engine.current_block_height, 1);
+        engine.advance_time(1);
+        assert_eq!(engine.current_block_height, 2);
+        engine.advance_time(18);
+        assert_eq!(engine.current_block_height, 11);
+        assert!(engine.verify_clock_consistency().is_ok());
+    }
+
+    #[test]
+    fn genesis_defaults_to_start_time() {
+        let engine = blockchain().build_engine(1_800_000_000);
+        assert_eq!(engine.genesis_unix(), 1_800_000_000);
+        assert_eq!(engine.block_interval_secs, 12);
+    }
+
+    #[test]
+    fn env_overrides_chain_timing() {
+        let mut config = blockchain();
+        config
+            .apply_overrides(|key| match key {
+                "COREPROVER_CHAIN_ID" => Some("369".into()),
+                "COREPROVER_BLOCK_INTERVAL_SECS" => Some(" 10 ".into()),
+                "COREPROVER_GENESIS_UNIX" => Some("1700000000".into()),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(config.chain_id, 369);
+        assert_eq!(config.block_interval_secs, 10);
+        assert_eq!(config.genesis_unix, Some(1_700_000_000));
+
+        let err = blockchain()
+            .apply_overrides(|key| (key == "COREPROVER_BLOCK_INTERVAL_SECS").then(|| "0".into()))
+            .unwrap_err();
+        assert!(err.to_string().contains("must be > 0"));
+
+        let err = blockchain()
+            .apply_overrides(|key| (key == "COREPROVER_CHAIN_ID").then(|| "pulse".into()))
+            .unwrap_err();
+        assert!(err.to_string().starts_with("COREPROVER_CHAIN_ID:"));
+    }
+
+    #[test]
+    fn timing_fields_default_when_absent_from_toml() {
+        let config: BlockchainConfig = toml::from_str(
+            r#"
+            rpc_url = "http://localhost:8545"
+            contract_address = "0x0"
+            chain_id = 1
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.block_interval_secs, 12);
+        assert_eq!(config.genesis_unix, None);
+    }
+
+    #[test]
+    fn admin_listener_binds_loopback_only() {
+        let config: ServerConfig = toml::from_str(
+            r#"
+            host = "0.0.0.0"
+            port = 8080
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.admin_port, 3001);
+        assert_eq!(config.admin_addr(), "127.0.0.1:3001");
+
+        let config = ServerConfig { admin_port: 9000, ..config };
+        assert_eq!(config.admin_addr(), "127.0.0.1:9000");
+    }
+}
@@ -0,0 +1,82 @@
+// ============================================================================
+// Receipt Sinks — push feed of finalized receipts
+// ============================================================================
+
+use crate::types::ReceiptMetadata;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Receives each receipt once, when the engine finalizes it.
+pub trait ReceiptSink: Send {
+    fn emit(&mut self, receipt: &ReceiptMetadata);
+}
+
+// ============================================================================
+// In-memory collector
+// ============================================================================
+
+/// Collects receipts into a shared Vec. Clones share the same buffer, so
+/// keep one clone to inspect what the engine emitted.
+#[derive(Debug, Clone, Default)]
+pub struct VecSink {
+    receipts: Arc<Mutex<Vec<ReceiptMetadata>>>,
+}
+
+impl VecSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn receipts(&self) -> Vec<ReceiptMetadata> {
+        self.receipts.lock().unwrap().clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.receipts.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl ReceiptSink for VecSink {
+    fn emit(&mut self, receipt: &ReceiptMetadata) {
+        self.receipts.lock().unwrap().push(receipt.clone());
+    }
+}
+
+// ============================================================================
+// NDJSON file writer
+// ============================================================================
+
+/// Appends one JSON receipt per line. Each line is flushed so tailing
+/// indexers see it immediately.
+pub struct NdjsonFileSink {
+    writer: BufWriter<File>,
+}
+
+impl NdjsonFileSink {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            writer: BufWriter::new(file),
+        })
+    }
+}
+
+impl ReceiptSink for NdjsonFileSink {
+    fn emit(&mut self, receipt: &ReceiptMetadata) {
+        let result = serde_json::to_writer(&mut self.writer, receipt)
+            .map_err(io::Error::from)
+            .and_then(|_| self.writer.write_all(b"\n"))
+            .and_then(|_| self.writer.flush());
+
+        // A failing sink must not fail settlement
+        if let Err(e) = result {
+            tracing::warn!("ndjson receipt sink write failed: {}", e);
+        }
+    }
+}
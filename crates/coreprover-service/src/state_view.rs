@@ -0,0 +1,54 @@
+// ============================================================================
+// StateView — lock-free-for-readers escrow state snapshots
+// ============================================================================
+//
+// The engine keeps an `Arc<HashMap<order_id, EscrowState>>` in step with its
+// escrows (copy-on-write via Arc::make_mut) and publishes each new version to
+// a shared slot. Readers clone the current Arc and then query it without any
+// further synchronisation, so read-heavy endpoints never wait on engine
+// mutations; the slot lock is only held for a pointer clone or swap.
+
+use crate::types::EscrowState;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Immutable point-in-time view of every escrow's state. Cheap to clone.
+#[derive(Debug, Clone, Default)]
+pub struct StateView {
+    states: Arc<HashMap<[u8; 32], EscrowState>>,
+}
+
+impl StateView {
+    pub(crate) fn new(states: Arc<HashMap<[u8; 32], EscrowState>>) -> Self {
+        Self { states }
+    }
+
+    pub fn get(&self, order_id: &[u8; 32]) -> Option<EscrowState> {
+        self.states.get(order_id).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+}
+
+/// Handle for reading the latest published StateView from any thread.
+#[derive(Debug, Clone, Default)]
+pub struct StateViewReader {
+    slot: Arc<RwLock<StateView>>,
+}
+
+impl StateViewReader {
+    /// The most recently published view.
+    pub fn load(&self) -> StateView {
+        self.slot.read().unwrap().clone()
+    }
+
+    pub(crate) fn publish(&self, view: StateView) {
+        *self.slot.write().unwrap() = view;
+    }
+}
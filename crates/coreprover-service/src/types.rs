@@ -34,35 +34,138 @@ pub enum EscrowState {
     SellerAccepted,
     SellerFulfilled,
     FulfillmentExpired,
+    /// Fulfilled after the deadline: withdrawal is locked again and the
+    /// late discount applies
+    LateFulfilled,
+    /// Part of the amount claimed by `seller_claim_partial`; the rest is
+    /// still held
+    PartiallyClaimed,
+    /// Buyer or seller contested the fulfillment; funds are frozen until
+    /// `resolve_dispute`
+    Disputed,
     SellerClaimed,
     SellerRefunded,
     BuyerWithdrawn,
+    /// Fulfilled but never claimed; the buyer took the funds back once the
+    /// dispute window lapsed (`buyer_reclaim`)
+    BuyerReclaimed,
 }
 
 impl EscrowState {
+    pub const ALL: [EscrowState; 11] = [
+        EscrowState::BuyerCommitted,
+        EscrowState::SellerAccepted,
+        EscrowState::SellerFulfilled,
+        EscrowState::FulfillmentExpired,
+        EscrowState::LateFulfilled,
+        EscrowState::PartiallyClaimed,
+        EscrowState::Disputed,
+        EscrowState::SellerClaimed,
+        EscrowState::SellerRefunded,
+        EscrowState::BuyerWithdrawn,
+        EscrowState::BuyerReclaimed,
+    ];
+
     pub fn is_terminal(self) -> bool {
         matches!(
             self,
             EscrowState::SellerClaimed
                 | EscrowState::SellerRefunded
                 | EscrowState::BuyerWithdrawn
+                | EscrowState::BuyerReclaimed
         )
     }
 
     pub fn can_fulfill(self) -> bool {
         matches!(self, EscrowState::SellerAccepted | EscrowState::FulfillmentExpired)
     }
+
+    /// Transitions the engine can perform (self-loops excluded)
+    pub fn can_transition_to(self, target: EscrowState) -> bool {
+        use EscrowState::*;
+
+        matches!(
+            (self, target),
+            (BuyerCommitted, SellerAccepted)
+                | (BuyerCommitted, BuyerWithdrawn)
+                | (SellerAccepted, SellerFulfilled)
+                | (SellerAccepted, FulfillmentExpired)
+                | (SellerAccepted, LateFulfilled)
+                | (SellerAccepted, SellerRefunded)
+                | (SellerFulfilled, SellerClaimed)
+                | (SellerFulfilled, SellerRefunded)
+                | (SellerFulfilled, PartiallyClaimed)
+                | (SellerFulfilled, Disputed)
+                | (SellerFulfilled, BuyerReclaimed)
+                | (FulfillmentExpired, LateFulfilled)
+                | (FulfillmentExpired, BuyerWithdrawn)
+                | (LateFulfilled, SellerClaimed)
+                | (LateFulfilled, SellerRefunded)
+                | (LateFulfilled, PartiallyClaimed)
+                | (LateFulfilled, Disputed)
+                | (LateFulfilled, BuyerReclaimed)
+                | (PartiallyClaimed, SellerClaimed)
+                | (Disputed, SellerClaimed)
+                | (Disputed, SellerRefunded)
+        )
+    }
+}
+
+/// Render the escrow state machine as a Graphviz DOT graph.
+/// Edges come from `EscrowState::can_transition_to`.
+pub fn escrow_state_graph_dot() -> String {
+    let mut dot = String::from("digraph EscrowState {\n    rankdir=LR;\n");
+
+    for state in EscrowState::ALL {
+        let shape = if state.is_terminal() { "doublecircle" } else { "circle" };
+        dot.push_str(&format!("    {:?} [shape={}];\n", state, shape));
+    }
+
+    for from in EscrowState::ALL {
+        for to in EscrowState::ALL {
+            if from.can_transition_to(to) {
+                dot.push_str(&format!("    {:?} -> {:?};\n", from, to));
+            }
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+// ============================================================================
+// Disputes
+// ============================================================================
+
+/// Side of an escrow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Party {
+    Buyer,
+    Seller,
+}
+
+/// Where `resolve_dispute` sends the escrowed funds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DisputeOutcome {
+    /// Settles as a seller claim
+    PaySeller,
+    /// Settles as a refund to the buyer
+    RefundBuyer,
 }
 
 // ============================================================================
 // Timing Windows (pure u64 seconds)
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TimingWindows {
     pub acceptance_window_secs: u64,
     pub fulfillment_window_secs: u64,
     pub claim_window_secs: u64,
+    /// After fulfillment, how long the seller has to claim before the buyer
+    /// may `buyer_reclaim` the funds (0 = reclaim disabled)
+    #[serde(default)]
+    pub buyer_dispute_window_secs: u64,
 }
 
 impl TimingWindows {
@@ -71,6 +174,7 @@ impl TimingWindows {
             acceptance_window_secs: 1800, // 30 minutes
             fulfillment_window_secs: 3600, // 1 hour
             claim_window_secs: 3600,       // 1 hour
+            buyer_dispute_window_secs: 0,
         }
     }
 }
@@ -79,13 +183,22 @@ impl TimingWindows {
 // Payment Profile
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Max difference between two controllers' timing windows for their
+/// profiles to count as compatible (clock skew + block time slack)
+pub const PROFILE_WINDOW_TOLERANCE_SECS: u64 = 60;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PaymentProfile {
     pub timing: TimingWindows,
     pub allows_timed_release: bool,
     pub enables_late_discount: bool,
     pub late_discount_pct: u8,
     pub discount_expiration_days: u64,
+
+    // Penalty (basis points of amount) routed to the seller when the buyer
+    // withdraws after the acceptance window lapses. 0 = full refund.
+    #[serde(default)]
+    pub acceptance_timeout_buyer_penalty_bps: u16,
 }
 
 impl PaymentProfile {
@@ -96,7 +209,77 @@ impl PaymentProfile {
             enables_late_discount: true,
             late_discount_pct: 10,
             discount_expiration_days: 90,
+            acceptance_timeout_buyer_penalty_bps: 0,
+        }
+    }
+
+    /// Check that two controllers' profiles agree closely enough to share a
+    /// deal: windows within `PROFILE_WINDOW_TOLERANCE_SECS`, identical
+    /// discount / timed-release / penalty semantics. Returns the first
+    /// incompatibility found.
+    pub fn is_compatible_with(&self, other: &PaymentProfile) -> Result<(), String> {
+        let windows = [
+            (
+                "acceptance_window_secs",
+                self.timing.acceptance_window_secs,
+                other.timing.acceptance_window_secs,
+            ),
+            (
+                "fulfillment_window_secs",
+                self.timing.fulfillment_window_secs,
+                other.timing.fulfillment_window_secs,
+            ),
+            (
+                "claim_window_secs",
+                self.timing.claim_window_secs,
+                other.timing.claim_window_secs,
+            ),
+            (
+                "buyer_dispute_window_secs",
+                self.timing.buyer_dispute_window_secs,
+                other.timing.buyer_dispute_window_secs,
+            ),
+        ];
+        for (name, a, b) in windows {
+            if a.abs_diff(b) > PROFILE_WINDOW_TOLERANCE_SECS {
+                return Err(format!(
+                    "{} differs by more than {}s ({} vs {})",
+                    name, PROFILE_WINDOW_TOLERANCE_SECS, a, b
+                ));
+            }
+        }
+
+        if self.allows_timed_release != other.allows_timed_release {
+            return Err("allows_timed_release differs".into());
+        }
+
+        if self.enables_late_discount != other.enables_late_discount {
+            return Err("enables_late_discount differs".into());
+        }
+        if self.enables_late_discount {
+            if self.late_discount_pct != other.late_discount_pct {
+                return Err(format!(
+                    "late_discount_pct differs ({} vs {})",
+                    self.late_discount_pct, other.late_discount_pct
+                ));
+            }
+            if self.discount_expiration_days != other.discount_expiration_days {
+                return Err(format!(
+                    "discount_expiration_days differs ({} vs {})",
+                    self.discount_expiration_days, other.discount_expiration_days
+                ));
+            }
+        }
+
+        if self.acceptance_timeout_buyer_penalty_bps != other.acceptance_timeout_buyer_penalty_bps {
+            return Err(format!(
+                "acceptance_timeout_buyer_penalty_bps differs ({} vs {})",
+                self.acceptance_timeout_buyer_penalty_bps,
+                other.acceptance_timeout_buyer_penalty_bps
+            ));
         }
+
+        Ok(())
     }
 }
 
@@ -113,6 +296,8 @@ pub struct ReceiptMetadata {
     pub fulfillment_mono: u64,
     pub fulfillment_unix: u64,
     pub fulfillment_iso: String,
+    #[serde(default)]
+    pub fulfillment_deadline_unix: u64,
 
     pub settlement_mono: u64,
     pub settlement_unix: u64,
@@ -135,18 +320,266 @@ pub struct ReceiptMetadata {
     pub seller_claim_txid: Option<String>,
     pub seller_refund_txid: Option<String>,
 
-    // Optional economic termination before fulfillment
+    // Txids of seller_claim_partial calls, in order
+    #[serde(default)]
+    pub partial_claim_txids: Vec<String>,
+
+    // Buyer-side termination: withdrawal before fulfillment, or
+    // buyer_reclaim after an unclaimed fulfillment
     pub buyer_withdraw_txid: Option<String>,
 
+    // Withdrawal split (acceptance timeout penalty)
+    #[serde(default)]
+    pub buyer_refund_amount: u128,
+    #[serde(default)]
+    pub seller_penalty_amount: u128,
+
+    // Claim split: what the seller received and the controller fee
+    #[serde(default)]
+    pub seller_amount: u128,
+    #[serde(default)]
+    pub fee_amount: u128,
+
+    // Why the seller declined (seller_decline only)
+    #[serde(default)]
+    pub decline_reason: Option<String>,
+
+    // Dispute evidence and who resolved it (resolve_dispute only)
+    #[serde(default)]
+    pub dispute_evidence_hash: Option<[u8; 32]>,
+    #[serde(default)]
+    pub dispute_resolver: Option<String>,
+
     // Settlement ordering anchor
     pub seller_block_height: u64,
 }
 
+impl ReceiptMetadata {
+    /// Settled by a claim, a refund or a buyer withdrawal/reclaim; a stub
+    /// written at fulfillment is not finalized until one of these lands
+    pub fn is_finalized(&self) -> bool {
+        self.seller_claim_txid.is_some()
+            || self.seller_refund_txid.is_some()
+            || self.buyer_withdraw_txid.is_some()
+    }
+}
+
 // ============================================================================
-// Escrow Session Record
+// Escrow Event Log
+// ============================================================================
+
+/// One entry of an escrow's ordered history. The log holds every change
+/// the engine makes, so `Escrow::replay_events` can rebuild the escrow.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EscrowEvent {
+    BuyerCommitted {
+        order_id: [u8; 32],
+        buyer: String,
+        seller: String,
+        amount: u64,
+        profile: PaymentProfile,
+        buyer_chain_id: u64,
+        buyer_commit_txid: String,
+        at_mono: u64,
+    },
+    SellerReassigned {
+        from: String,
+        to: String,
+        at_mono: u64,
+    },
+    SellerAccepted {
+        seller_chain_id: u64,
+        txid: String,
+        #[serde(default)]
+        fee_bps: u32,
+        at_mono: u64,
+    },
+    /// Late when `at_mono` is past the fulfillment deadline
+    SellerFulfilled {
+        txid: String,
+        at_mono: u64,
+    },
+    FulfillmentExpired {
+        at_mono: u64,
+    },
+    /// Also recorded by `timed_release` (auto-claim txid)
+    SellerClaimed {
+        txid: String,
+        block_height: u64,
+        at_mono: u64,
+    },
+    /// `seller_claim_partial`; the claim that reaches the full amount
+    /// settles the escrow
+    SellerClaimedPartial {
+        txid: String,
+        amount: u64,
+        block_height: u64,
+        at_mono: u64,
+    },
+    /// Also recorded by `seller_decline`
+    SellerRefunded {
+        txid: String,
+        block_height: u64,
+        at_mono: u64,
+    },
+    BuyerWithdrew {
+        txid: Option<String>,
+        at_mono: u64,
+    },
+    BuyerReclaimed {
+        txid: String,
+        at_mono: u64,
+    },
+    DisputeOpened {
+        opener: Party,
+        evidence_hash: [u8; 32],
+        at_mono: u64,
+    },
+    /// Settles the escrow as a claim or a refund, per `outcome`
+    DisputeResolved {
+        outcome: DisputeOutcome,
+        resolver: String,
+        txid: String,
+        block_height: u64,
+        at_mono: u64,
+    },
+}
+
+impl EscrowEvent {
+    pub fn at_mono(&self) -> u64 {
+        match self {
+            EscrowEvent::BuyerCommitted { at_mono, .. }
+            | EscrowEvent::SellerReassigned { at_mono, .. }
+            | EscrowEvent::SellerAccepted { at_mono, .. }
+            | EscrowEvent::SellerFulfilled { at_mono, .. }
+            | EscrowEvent::FulfillmentExpired { at_mono }
+            | EscrowEvent::SellerClaimed { at_mono, .. }
+            | EscrowEvent::SellerClaimedPartial { at_mono, .. }
+            | EscrowEvent::SellerRefunded { at_mono, .. }
+            | EscrowEvent::BuyerWithdrew { at_mono, .. }
+            | EscrowEvent::BuyerReclaimed { at_mono, .. }
+            | EscrowEvent::DisputeOpened { at_mono, .. }
+            | EscrowEvent::DisputeResolved { at_mono, .. } => *at_mono,
+        }
+    }
+}
+
+// ============================================================================
+// Engine Event Log
 // ============================================================================
 
+/// Which engine operation an `EngineEvent` records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EngineEventKind {
+    Committed,
+    SellerReassigned,
+    Accepted,
+    /// On time or late; the escrow state tells which
+    Fulfilled,
+    Claimed,
+    PartiallyClaimed,
+    Refunded,
+    Declined,
+    Withdrew,
+    Reclaimed,
+    DisputeOpened,
+    DisputeResolved,
+    TimedRelease,
+    FulfillmentExpired,
+}
+
+/// One entry of the engine-wide log, stamped with all three clocks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EngineEvent {
+    pub order_id: [u8; 32],
+    pub kind: EngineEventKind,
+    pub mono: u64,
+    pub unix: u64,
+    pub block_height: u64,
+}
+
+// ============================================================================
+// Refund Eligibility (buyer-facing)
+// ============================================================================
+
+/// Whether the buyer can withdraw now, and if not, why
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RefundEligibility {
+    /// `buyer_withdraw` would succeed and return `amount`
+    Eligible { amount: u64 },
+    /// Acceptance window still open; withdrawal opens in `seconds_remaining`
+    NotYet { seconds_remaining: u64 },
+    /// Funds are held for the seller's side of the deal
+    Locked { reason: String },
+    /// Already settled
+    TerminalAlready(EscrowState),
+}
+
+// ============================================================================
+// Txid Kinds (reconciliation)
+// ============================================================================
+
+/// Which blockchain-anchored action a txid records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TxidKind {
+    BuyerCommit,
+    SellerAccept,
+    SellerFulfill,
+    SellerClaim,
+    SellerRefund,
+    BuyerWithdraw,
+}
+
+// ============================================================================
+// Commit Spec (bulk commit input)
+// ============================================================================
+
+/// Arguments of one `buyer_commit`, for `CoreProverEngine::bulk_commit`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitSpec {
+    pub buyer: String,
+    pub seller: String,
+    pub amount: u64,
+    pub profile: PaymentProfile,
+    pub buyer_chain_id: u64,
+    pub buyer_commit_txid: String,
+}
+
+// ============================================================================
+// Settlement Split
+// ============================================================================
+
+/// Basis points in 100%
+pub const MAX_FEE_BPS: u32 = 10_000;
+
+/// How a claimed amount is divided between the seller and the controller
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Settlement {
+    pub seller_amount: u64,
+    pub fee_amount: u64,
+}
+
+impl Settlement {
+    /// `fee_amount = floor(amount * fee_bps / 10000)`; the seller gets the rest
+    pub fn split(amount: u64, fee_bps: u32) -> Self {
+        let fee_amount = (amount as u128 * fee_bps.min(MAX_FEE_BPS) as u128
+            / MAX_FEE_BPS as u128) as u64;
+        Self {
+            seller_amount: amount - fee_amount,
+            fee_amount,
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.seller_amount + self.fee_amount
+    }
+}
+
+// ============================================================================
+// Escrow Session Record
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Escrow {
     // Identity
     pub order_id: [u8; 32],
@@ -156,6 +589,12 @@ pub struct Escrow {
     // Value
     pub amount: u64,
     pub profile: PaymentProfile,
+    // Released to the seller so far (partial claims; full on SellerClaimed)
+    #[serde(default)]
+    pub claimed_so_far: u64,
+    // Controller fee taken out of seller_claim, from the negotiated offer
+    #[serde(default)]
+    pub fee_bps: u32,
 
     pub state: EscrowState,
 
@@ -182,8 +621,18 @@ pub struct Escrow {
 
     pub buyer_withdraw_txid: Option<String>,
 
+    // Set by open_dispute
+    #[serde(default)]
+    pub dispute_opener: Option<Party>,
+    #[serde(default)]
+    pub dispute_evidence_hash: Option<[u8; 32]>,
+
     // Final settlement anchor
     pub seller_block_height: Option<u64>,
+
+    // Ordered history; see `replay_events`
+    #[serde(default)]
+    pub events: Vec<EscrowEvent>,
 }
 
 impl Escrow {
@@ -205,6 +654,8 @@ impl Escrow {
             seller,
             amount,
             profile,
+            claimed_so_far: 0,
+            fee_bps: 0,
             state: EscrowState::BuyerCommitted,
 
             buyer_commit_mono: current_mono,
@@ -228,7 +679,291 @@ impl Escrow {
 
             buyer_withdraw_txid: None,
 
+            dispute_opener: None,
+            dispute_evidence_hash: None,
+
             seller_block_height: None,
+
+            events: Vec::new(),
+        }
+    }
+
+    /// Rebuild an escrow by folding its ordered event log. The log must start
+    /// with `BuyerCommitted`, be in time order, and every state change must
+    /// be an edge of the escrow state machine.
+    pub fn replay_events(events: &[EscrowEvent]) -> Result<Escrow, String> {
+        let (first, rest) = events.split_first().ok_or("event log is empty")?;
+
+        let mut escrow = match first {
+            EscrowEvent::BuyerCommitted {
+                order_id,
+                buyer,
+                seller,
+                amount,
+                profile,
+                buyer_chain_id,
+                buyer_commit_txid,
+                at_mono,
+            } => Escrow::new(
+                *order_id,
+                buyer.clone(),
+                seller.clone(),
+                *amount,
+                profile.clone(),
+                *buyer_chain_id,
+                buyer_commit_txid.clone(),
+                *at_mono,
+            ),
+            other => {
+                return Err(format!(
+                    "event 0: log must start with BuyerCommitted, got {:?}",
+                    other
+                ))
+            }
+        };
+        escrow.events.push(first.clone());
+
+        for (i, event) in rest.iter().enumerate() {
+            escrow.apply_event(event).map_err(|e| format!("event {}: {}", i + 1, e))?;
+        }
+        Ok(escrow)
+    }
+
+    fn apply_event(&mut self, event: &EscrowEvent) -> Result<(), String> {
+        let last = self.events.last().map(|e| e.at_mono()).unwrap_or(0);
+        if event.at_mono() < last {
+            return Err(format!("out of order: at_mono {} before {}", event.at_mono(), last));
         }
+
+        let target = match event {
+            EscrowEvent::BuyerCommitted { .. } => return Err("duplicate BuyerCommitted".into()),
+            EscrowEvent::SellerReassigned { .. } => self.state,
+            EscrowEvent::SellerAccepted { .. } => EscrowState::SellerAccepted,
+            EscrowEvent::SellerFulfilled { at_mono, .. } => {
+                if !self.state.can_fulfill() {
+                    return Err(format!("fulfillment invalid in state {:?}", self.state));
+                }
+                match self.fulfillment_deadline_mono {
+                    Some(d) if *at_mono > d => EscrowState::LateFulfilled,
+                    _ => EscrowState::SellerFulfilled,
+                }
+            }
+            EscrowEvent::FulfillmentExpired { at_mono } => {
+                if self.fulfillment_deadline_mono.is_none_or(|d| *at_mono <= d) {
+                    return Err("expiry before the fulfillment deadline".into());
+                }
+                EscrowState::FulfillmentExpired
+            }
+            EscrowEvent::SellerClaimed { .. } => EscrowState::SellerClaimed,
+            EscrowEvent::SellerClaimedPartial { amount, .. } => {
+                let unclaimed = self.amount - self.claimed_so_far;
+                if *amount == 0 || *amount > unclaimed {
+                    return Err(format!(
+                        "partial claim of {} with {} unclaimed",
+                        amount, unclaimed
+                    ));
+                }
+                if *amount == unclaimed {
+                    EscrowState::SellerClaimed
+                } else {
+                    EscrowState::PartiallyClaimed
+                }
+            }
+            EscrowEvent::SellerRefunded { .. } => EscrowState::SellerRefunded,
+            EscrowEvent::BuyerWithdrew { .. } => EscrowState::BuyerWithdrawn,
+            EscrowEvent::BuyerReclaimed { .. } => EscrowState::BuyerReclaimed,
+            EscrowEvent::DisputeOpened { .. } => EscrowState::Disputed,
+            EscrowEvent::DisputeResolved { outcome, .. } => {
+                if self.state != EscrowState::Disputed {
+                    return Err(format!("dispute resolution in state {:?}", self.state));
+                }
+                match outcome {
+                    DisputeOutcome::PaySeller => EscrowState::SellerClaimed,
+                    DisputeOutcome::RefundBuyer => EscrowState::SellerRefunded,
+                }
+            }
+        };
+
+        let reassign = matches!(event, EscrowEvent::SellerReassigned { .. });
+        let next_partial = matches!(event, EscrowEvent::SellerClaimedPartial { .. })
+            && self.state == EscrowState::PartiallyClaimed
+            && target == EscrowState::PartiallyClaimed;
+        if !reassign && !next_partial && !self.state.can_transition_to(target) {
+            return Err(format!("invalid transition {:?} -> {:?}", self.state, target));
+        }
+
+        match event {
+            EscrowEvent::BuyerCommitted { .. } => unreachable!(),
+            EscrowEvent::SellerReassigned { from, to, .. } => {
+                if self.state != EscrowState::BuyerCommitted {
+                    return Err("reassignment only valid from BuyerCommitted".into());
+                }
+                if &self.seller != from {
+                    return Err(format!("reassignment from {} but seller is {}", from, self.seller));
+                }
+                self.seller = to.clone();
+            }
+            EscrowEvent::SellerAccepted { seller_chain_id, txid, fee_bps, at_mono } => {
+                self.seller_chain_id = *seller_chain_id;
+                self.fee_bps = *fee_bps;
+                self.seller_accept_mono = Some(*at_mono);
+                self.seller_accept_txid = Some(txid.clone());
+                self.fulfillment_deadline_mono =
+                    Some(at_mono + self.profile.timing.fulfillment_window_secs);
+            }
+            EscrowEvent::SellerFulfilled { txid, at_mono } => {
+                self.fulfillment_mono = Some(*at_mono);
+                self.seller_fulfill_txid = Some(txid.clone());
+            }
+            EscrowEvent::FulfillmentExpired { .. } => {}
+            EscrowEvent::SellerClaimed { txid, block_height, at_mono } => {
+                self.claimed_so_far = self.amount;
+                self.seller_claim_txid = Some(txid.clone());
+                self.settlement_mono = Some(*at_mono);
+                self.seller_block_height = Some(*block_height);
+            }
+            EscrowEvent::SellerClaimedPartial { txid, amount, block_height, at_mono } => {
+                self.claimed_so_far += amount;
+                if target == EscrowState::SellerClaimed {
+                    self.seller_claim_txid = Some(txid.clone());
+                    self.settlement_mono = Some(*at_mono);
+                    self.seller_block_height = Some(*block_height);
+                }
+            }
+            EscrowEvent::SellerRefunded { txid, block_height, at_mono } => {
+                self.seller_refund_txid = Some(txid.clone());
+                self.settlement_mono = Some(*at_mono);
+                self.seller_block_height = Some(*block_height);
+            }
+            EscrowEvent::BuyerWithdrew { txid, at_mono } => {
+                if txid.is_some() {
+                    self.buyer_withdraw_txid = txid.clone();
+                }
+                self.settlement_mono = Some(*at_mono);
+                self.seller_block_height = None;
+            }
+            EscrowEvent::BuyerReclaimed { txid, at_mono } => {
+                self.buyer_withdraw_txid = Some(txid.clone());
+                self.settlement_mono = Some(*at_mono);
+                self.seller_block_height = None;
+            }
+            EscrowEvent::DisputeOpened { opener, evidence_hash, .. } => {
+                self.dispute_opener = Some(*opener);
+                self.dispute_evidence_hash = Some(*evidence_hash);
+            }
+            EscrowEvent::DisputeResolved { outcome, txid, block_height, at_mono, .. } => {
+                match outcome {
+                    DisputeOutcome::PaySeller => {
+                        self.claimed_so_far = self.amount;
+                        self.seller_claim_txid = Some(txid.clone());
+                    }
+                    DisputeOutcome::RefundBuyer => self.seller_refund_txid = Some(txid.clone()),
+                }
+                self.settlement_mono = Some(*at_mono);
+                self.seller_block_height = Some(*block_height);
+            }
+        }
+
+        self.state = target;
+        self.events.push(event.clone());
+        Ok(())
     }
-}
\ No newline at end of file
+
+    /// The `BuyerCommitted` event that opens this escrow's log.
+    pub fn committed_event(&self) -> EscrowEvent {
+        EscrowEvent::BuyerCommitted {
+            order_id: self.order_id,
+            buyer: self.buyer.clone(),
+            seller: self.seller.clone(),
+            amount: self.amount,
+            profile: self.profile.clone(),
+            buyer_chain_id: self.buyer_chain_id,
+            buyer_commit_txid: self.buyer_commit_txid.clone(),
+            at_mono: self.buyer_commit_mono,
+        }
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escrow_state_graph_dot_edges() {
+        let dot = escrow_state_graph_dot();
+        assert!(dot.starts_with("digraph EscrowState {"));
+        assert!(dot.contains("BuyerCommitted -> SellerAccepted;"));
+        assert!(dot.contains("FulfillmentExpired -> BuyerWithdrawn;"));
+        assert!(dot.contains("FulfillmentExpired -> LateFulfilled;"));
+        assert!(!dot.contains("LateFulfilled -> BuyerWithdrawn;"));
+        assert!(!dot.contains("BuyerCommitted -> SellerClaimed;"));
+        assert!(!dot.contains("SellerClaimed ->"));
+        assert!(dot.contains("SellerFulfilled -> BuyerReclaimed;"));
+        assert!(dot.contains("BuyerReclaimed [shape=doublecircle];"));
+    }
+
+    #[test]
+    fn identical_profiles_are_compatible() {
+        let a = PaymentProfile::pizza_delivery();
+        let b = PaymentProfile::pizza_delivery();
+        assert!(a.is_compatible_with(&b).is_ok());
+    }
+
+    #[test]
+    fn small_window_difference_is_tolerated() {
+        let a = PaymentProfile::pizza_delivery();
+        let mut b = PaymentProfile::pizza_delivery();
+        b.timing.fulfillment_window_secs += PROFILE_WINDOW_TOLERANCE_SECS;
+        assert!(a.is_compatible_with(&b).is_ok());
+
+        b.timing.fulfillment_window_secs += 1;
+        let err = a.is_compatible_with(&b).unwrap_err();
+        assert!(err.contains("fulfillment_window_secs"));
+    }
+
+    #[test]
+    fn discount_mismatch_is_incompatible() {
+        let a = PaymentProfile::pizza_delivery();
+        let mut b = PaymentProfile::pizza_delivery();
+        b.late_discount_pct = 15;
+        let err = a.is_compatible_with(&b).unwrap_err();
+        assert!(err.contains("late_discount_pct"));
+
+        // Discount terms are irrelevant when neither side offers one
+        let mut a = a;
+        a.enables_late_discount = false;
+        b.enables_late_discount = false;
+        assert!(a.is_compatible_with(&b).is_ok());
+    }
+
+    #[test]
+    fn replay_events_rejects_invalid_transition() {
+        let escrow = Escrow::new(
+            [7u8; 32],
+            "buyer".into(),
+            "seller".into(),
+            100,
+            PaymentProfile::pizza_delivery(),
+            1,
+            "0xcommit".into(),
+            0,
+        );
+        let log = vec![
+            escrow.committed_event(),
+            EscrowEvent::SellerClaimed {
+                txid: "0xclaim".into(),
+                block_height: 1,
+                at_mono: 5,
+            },
+        ];
+        let err = Escrow::replay_events(&log).unwrap_err();
+        assert!(err.contains("event 1: invalid transition"), "{}", err);
+
+        assert!(Escrow::replay_events(&[]).is_err());
+        assert!(Escrow::replay_events(&log[1..]).is_err());
+    }
+}
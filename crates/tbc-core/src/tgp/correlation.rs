@@ -0,0 +1,174 @@
+//# TGP Message Correlation
+
+//**Destination Path:** `crates/tbc-core/src/tgp/correlation.rs`
+
+//**Implementation:** M1 - TGP Message Parsing & Basic Routing
+
+//! Cross-message consistency checks for a TGP exchange
+//!
+//! Each message's own `validate()` only checks its fields in isolation.
+//! [`TGPCorrelationValidator`] checks that the messages of one exchange
+//! belong together: the OFFER answers the QUERY with the same asset and
+//! amount, and the SETTLE reports on that OFFER.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use tbc_core::tgp::correlation::TGPCorrelationValidator;
+//! use tbc_core::tgp::messages::{OfferMessage, QueryMessage, SettleMessage};
+//! use tbc_core::tgp::types::{EconomicEnvelope, SettleSource, ZkProfile};
+//!
+//! let zk = ZkProfile::Optional;
+//! let query = QueryMessage::new("q-1", "buyer://alice", "seller://bob", "USDC", 1000, zk);
+//! let envelope = EconomicEnvelope::new(50);
+//! let offer = OfferMessage::new("offer-1", "q-1", "USDC", 1000, false, envelope);
+//! let settle = SettleMessage::new("settle-1", "offer-1", true, SettleSource::BuyerNotify);
+//!
+//! TGPCorrelationValidator::validate_offer_against(&query, &offer)?;
+//! TGPCorrelationValidator::validate_settle_against(&offer, &settle)?;
+//! # Ok::<(), String>(())
+//! ```
+
+use super::messages::{OfferMessage, QueryMessage, SettleMessage};
+
+// ============================================================================
+// TGPCorrelationValidator
+// ============================================================================
+
+/// Checks that QUERY, OFFER, and SETTLE messages form one exchange
+pub struct TGPCorrelationValidator;
+
+impl TGPCorrelationValidator {
+    /// Validate that `offer` answers `query`
+    ///
+    /// # Validation Rules (per TGP-00 §3.2)
+    ///
+    /// - `offer.query_id` must equal `query.id`
+    /// - `offer.asset` must equal `query.asset`
+    /// - `offer.amount` must equal `query.amount`
+    pub fn validate_offer_against(
+        query: &QueryMessage,
+        offer: &OfferMessage,
+    ) -> Result<(), String> {
+        offer.validate_echo(query).map_err(|e| {
+            format!(
+                "OFFER {} does not answer QUERY {}: {}",
+                offer.id, query.id, e
+            )
+        })
+    }
+
+    /// Validate that `settle` reports on `offer`
+    ///
+    /// # Validation Rules (per TGP-00 §3.3)
+    ///
+    /// - `settle.query_or_offer_id` must equal `offer.id` or `offer.query_id`
+    /// - `settle.session_id` must equal `offer.session_id` when both are set
+    pub fn validate_settle_against(
+        offer: &OfferMessage,
+        settle: &SettleMessage,
+    ) -> Result<(), String> {
+        let id = &settle.query_or_offer_id;
+        if id != &offer.id && id != &offer.query_id {
+            return Err(format!(
+                "SETTLE {} references '{}', expected OFFER '{}' or QUERY '{}'",
+                settle.id, id, offer.id, offer.query_id
+            ));
+        }
+
+        if let (Some(settle_session), Some(offer_session)) = (&settle.session_id, &offer.session_id)
+        {
+            if settle_session != offer_session {
+                return Err(format!(
+                    "SETTLE {} session_id '{}' does not match OFFER session_id '{}'",
+                    settle.id, settle_session, offer_session
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tgp::types::{EconomicEnvelope, SettleSource, ZkProfile};
+
+    fn query() -> QueryMessage {
+        QueryMessage::new(
+            "q-1",
+            "buyer://alice",
+            "seller://bob",
+            "USDC",
+            1000,
+            ZkProfile::Optional,
+        )
+    }
+
+    fn offer(asset: &str, amount: u64) -> OfferMessage {
+        OfferMessage::new(
+            "offer-1",
+            "q-1",
+            asset,
+            amount,
+            false,
+            EconomicEnvelope::new(50),
+        )
+        .with_session("sess-1")
+    }
+
+    #[test]
+    fn test_offer_matches_query() {
+        assert!(
+            TGPCorrelationValidator::validate_offer_against(&query(), &offer("USDC", 1000)).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_offer_mismatches_query() {
+        let err = TGPCorrelationValidator::validate_offer_against(&query(), &offer("DAI", 1000))
+            .unwrap_err();
+        assert!(err.contains("asset 'DAI' does not match QUERY asset 'USDC'"));
+
+        let err = TGPCorrelationValidator::validate_offer_against(&query(), &offer("USDC", 999))
+            .unwrap_err();
+        assert!(err.contains("amount 999 does not match QUERY amount 1000"));
+
+        let mut stray = offer("USDC", 1000);
+        stray.query_id = "q-2".into();
+        let err = TGPCorrelationValidator::validate_offer_against(&query(), &stray).unwrap_err();
+        assert!(err.contains("query_id 'q-2'"));
+    }
+
+    #[test]
+    fn test_settle_against_offer() {
+        let offer = offer("USDC", 1000);
+        let settle = |id: &str| SettleMessage::new("settle-1", id, true, SettleSource::BuyerNotify);
+
+        assert!(
+            TGPCorrelationValidator::validate_settle_against(&offer, &settle("offer-1")).is_ok()
+        );
+        assert!(TGPCorrelationValidator::validate_settle_against(&offer, &settle("q-1")).is_ok());
+        assert!(TGPCorrelationValidator::validate_settle_against(
+            &offer,
+            &settle("offer-1").with_session("sess-1")
+        )
+        .is_ok());
+
+        let err = TGPCorrelationValidator::validate_settle_against(&offer, &settle("offer-9"))
+            .unwrap_err();
+        assert!(err.contains("references 'offer-9'"));
+
+        let err = TGPCorrelationValidator::validate_settle_against(
+            &offer,
+            &settle("offer-1").with_session("sess-2"),
+        )
+        .unwrap_err();
+        assert!(err.contains("session_id 'sess-2'"));
+    }
+}
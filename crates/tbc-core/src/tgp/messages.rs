@@ -38,9 +38,12 @@
 
 use serde::{Deserialize, Serialize};
 
-use super::types::{EconomicEnvelope, SettleSource, ZkProfile};
+use super::types::{
+    EconomicEnvelope, RouteOption, SettleOutcome, SettleSource, TimingSummary, ZkProfile,
+};
 use super::validation::{
     validate_address, validate_non_empty, validate_positive_amount, validate_transaction_hash,
+    validate_transaction_hash_for_chain,
 };
 
 // ============================================================================
@@ -94,6 +97,10 @@ pub enum TGPMessage {
     /// ERROR message - signals protocol failure
     #[serde(rename = "ERROR")]
     Error(ErrorMessage),
+
+    /// CANCEL message - buyer aborts the session before settlement
+    #[serde(rename = "CANCEL")]
+    Cancel(CancelMessage),
 }
 
 impl TGPMessage {
@@ -104,6 +111,7 @@ impl TGPMessage {
             TGPMessage::Offer(m) => &m.id,
             TGPMessage::Settle(m) => &m.id,
             TGPMessage::Error(m) => &m.id,
+            TGPMessage::Cancel(m) => &m.id,
         }
     }
 
@@ -114,6 +122,7 @@ impl TGPMessage {
             TGPMessage::Offer(_) => "OFFER",
             TGPMessage::Settle(_) => "SETTLE",
             TGPMessage::Error(_) => "ERROR",
+            TGPMessage::Cancel(_) => "CANCEL",
         }
     }
 
@@ -128,8 +137,185 @@ impl TGPMessage {
             TGPMessage::Offer(m) => m.validate(),
             TGPMessage::Settle(m) => m.validate(),
             TGPMessage::Error(m) => m.validate(),
+            TGPMessage::Cancel(m) => m.validate(),
+        }
+    }
+
+    /// Parse a message and [`validate`](Self::validate) it
+    ///
+    /// Plain `serde_json` decoding accepts messages that parse but are not
+    /// valid TGP (an empty `id`, a zero `amount`); this rejects them too.
+    ///
+    /// # Errors
+    ///
+    /// Returns `invalid JSON: ...` if the input does not decode, or
+    /// `invalid <PHASE> message '<id>': ...` if it fails validation.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use tbc_core::tgp::messages::TGPMessage;
+    /// let json = r#"{"phase":"CANCEL","id":"cancel-1","session_id":"sess-1"}"#;
+    /// assert!(TGPMessage::parse_and_validate(json).is_ok());
+    ///
+    /// let empty_id = r#"{"phase":"CANCEL","id":"","session_id":"sess-1"}"#;
+    /// assert!(TGPMessage::parse_and_validate(empty_id).is_err());
+    /// ```
+    pub fn parse_and_validate(json: &str) -> Result<TGPMessage, String> {
+        Self::parse_and_validate_bytes(json.as_bytes())
+    }
+
+    /// [`parse_and_validate`](Self::parse_and_validate) for raw bytes
+    /// (e.g. a request body)
+    pub fn parse_and_validate_bytes(bytes: &[u8]) -> Result<TGPMessage, String> {
+        let message: TGPMessage =
+            serde_json::from_slice(bytes).map_err(|e| format!("invalid JSON: {}", e))?;
+        message.validate().map_err(|e| {
+            format!("invalid {} message '{}': {}", message.phase(), message.id(), e)
+        })?;
+        Ok(message)
+    }
+}
+
+// ============================================================================
+// Lenient Decoding
+// ============================================================================
+
+/// Result of lenient TGP decoding
+///
+/// Known phases decode to their typed [`TGPMessage`] variant. Any other
+/// `phase` is preserved as raw JSON so a gateway can forward or log it
+/// instead of rejecting the message outright.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LenientMessage {
+    /// A recognized phase (QUERY, OFFER, SETTLE, ERROR, CANCEL)
+    Known(TGPMessage),
+
+    /// An unrecognized phase, kept verbatim
+    Unknown {
+        phase: String,
+        raw: serde_json::Value,
+    },
+}
+
+impl TGPMessage {
+    /// Phases with a typed representation
+    pub const KNOWN_PHASES: [&'static str; 5] = ["QUERY", "OFFER", "SETTLE", "ERROR", "CANCEL"];
+
+    /// Parse a message without failing on unrecognized phases
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is not JSON, has no string `phase`,
+    /// or is a known phase with a malformed body.
+    pub fn parse_lenient(json: &str) -> Result<LenientMessage, serde_json::Error> {
+        let raw: serde_json::Value = serde_json::from_str(json)?;
+
+        let phase = match raw.get("phase").and_then(|p| p.as_str()) {
+            Some(p) if !Self::KNOWN_PHASES.contains(&p) => p.to_string(),
+            // Known or missing phase: let the typed decoder report errors
+            _ => return serde_json::from_value(raw).map(LenientMessage::Known),
+        };
+
+        Ok(LenientMessage::Unknown { phase, raw })
+    }
+
+    /// Parse a message, rejecting any field not defined by TGP-00
+    ///
+    /// The default serde decoding ignores unknown fields, so a typo in an
+    /// optional field (e.g. `escrow_contract_from402`) silently drops it.
+    /// Use this where such typos must be caught.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for invalid JSON, unknown phases, unknown fields
+    /// (including inside `economic_envelope`), or a malformed body.
+    pub fn from_json_strict(json: &str) -> Result<TGPMessage, String> {
+        let raw: serde_json::Value =
+            serde_json::from_str(json).map_err(|e| format!("invalid JSON: {}", e))?;
+
+        let phase = raw
+            .get("phase")
+            .and_then(|p| p.as_str())
+            .ok_or("phase is required")?
+            .to_string();
+
+        let allowed: &[&str] = match phase.as_str() {
+            "QUERY" => &[
+                "phase",
+                "id",
+                "from",
+                "to",
+                "asset",
+                "amount",
+                "escrow_from_402",
+                "escrow_contract_from_402",
+                "zk_profile",
+            ],
+            "OFFER" => &[
+                "phase",
+                "id",
+                "query_id",
+                "asset",
+                "amount",
+                "coreprover_contract",
+                "session_id",
+                "zk_required",
+                "economic_envelope",
+                "timing",
+                "routes",
+            ],
+            "SETTLE" => &[
+                "phase",
+                "id",
+                "query_or_offer_id",
+                "success",
+                "source",
+                "layer8_tx",
+                "session_id",
+                "origin_chain_id",
+                "outcome",
+            ],
+            "ERROR" => &["phase", "id", "code", "message", "correlation_id"],
+            "CANCEL" => &["phase", "id", "session_id", "reason"],
+            other => return Err(format!("unknown phase: {}", other)),
+        };
+
+        reject_unknown_fields(&raw, allowed, &phase)?;
+        if let Some(envelope) = raw.get("economic_envelope") {
+            reject_unknown_fields(envelope, &["max_fees_bps", "expiry"], "economic_envelope")?;
+        }
+        if let Some(timing) = raw.get("timing") {
+            let fields = ["acceptance_window_secs", "fulfillment_window_secs", "claim_window_secs"];
+            reject_unknown_fields(timing, &fields, "timing")?;
+        }
+        if let Some(routes) = raw.get("routes").and_then(|r| r.as_array()) {
+            let fields = [
+                "coreprover_contract",
+                "chain_id",
+                "estimated_fee_bps",
+                "estimated_latency_secs",
+            ];
+            for route in routes {
+                reject_unknown_fields(route, &fields, "routes")?;
+            }
+        }
+
+        serde_json::from_value(raw).map_err(|e| format!("invalid {} message: {}", phase, e))
+    }
+}
+
+fn reject_unknown_fields(
+    value: &serde_json::Value,
+    allowed: &[&str],
+    context: &str,
+) -> Result<(), String> {
+    if let Some(object) = value.as_object() {
+        if let Some(unknown) = object.keys().find(|k| !allowed.contains(&k.as_str())) {
+            return Err(format!("unknown field '{}' in {}", unknown, context));
         }
     }
+    Ok(())
 }
 
 // ============================================================================
@@ -150,12 +336,14 @@ impl TGPMessage {
 /// use tbc_core::tgp::messages::QueryMessage;
 /// use tbc_core::tgp::types::ZkProfile;
 ///
-/// let query = QueryMessage::new(
+/// // REQUIRED is only coherent when the 402 advertised a CoreProver escrow
+/// let query = QueryMessage::with_escrow_from_402(
 ///     "q-abc123",
 ///     "buyer://alice",
 ///     "seller://bob",
 ///     "USDC",
 ///     1_000_000,
+///     "0x742d35Cc6634C0532925a3b844Bc9e7595f0bEbA",
 ///     ZkProfile::Required,
 /// );
 ///
@@ -216,6 +404,8 @@ impl QueryMessage {
     /// - `asset` must not be empty
     /// - `amount` must be greater than zero
     /// - `escrow_contract_from_402` must be valid address if present
+    /// - `zk_profile` must be coherent with the 402 escrow metadata
+    ///   (see [`validate_coherent`](Self::validate_coherent))
     pub fn validate(&self) -> Result<(), String> {
         validate_non_empty(&self.id, "id")?;
         validate_non_empty(&self.from, "from")?;
@@ -227,7 +417,23 @@ impl QueryMessage {
             validate_address(contract, "escrow_contract_from_402")?;
         }
 
-        Ok(())
+        self.validate_coherent()
+    }
+
+    /// Check that `zk_profile` agrees with the 402 escrow metadata
+    ///
+    /// - `NONE` (direct payment) must not carry escrow metadata
+    /// - `REQUIRED` needs the 402 to have advertised CoreProver
+    pub fn validate_coherent(&self) -> Result<(), String> {
+        match self.zk_profile {
+            ZkProfile::None if self.escrow_from_402 || self.escrow_contract_from_402.is_some() => {
+                Err("zk_profile NONE contradicts escrow metadata from 402".to_string())
+            }
+            ZkProfile::Required if !self.escrow_from_402 => {
+                Err("zk_profile REQUIRED but 402 did not advertise escrow".to_string())
+            }
+            _ => Ok(()),
+        }
     }
 
     /// Create a new QUERY message with required fields
@@ -272,6 +478,115 @@ impl QueryMessage {
             zk_profile,
         }
     }
+
+    /// Start a [`QueryMessageBuilder`]
+    pub fn builder() -> QueryMessageBuilder {
+        QueryMessageBuilder::default()
+    }
+}
+
+/// Fluent builder for [`QueryMessage`]
+///
+/// Unset strings are empty and `amount` is zero, so [`build`](Self::build)
+/// fails unless every required field was set. `zk_profile` defaults to
+/// `OPTIONAL` and no escrow metadata is attached.
+///
+/// # Examples
+///
+/// ```rust
+/// use tbc_core::tgp::messages::QueryMessage;
+/// use tbc_core::tgp::types::ZkProfile;
+///
+/// let query = QueryMessage::builder()
+///     .id("q-abc123")
+///     .from("buyer://alice")
+///     .to("seller://bob")
+///     .asset("USDC")
+///     .amount(1_000_000)
+///     .zk_profile(ZkProfile::Required)
+///     .escrow_from_402(true)
+///     .escrow_contract("0x1234567890abcdef1234567890abcdef12345678")
+///     .build()
+///     .unwrap();
+///
+/// assert!(query.escrow_contract_from_402.is_some());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct QueryMessageBuilder {
+    id: String,
+    from: String,
+    to: String,
+    asset: String,
+    amount: u64,
+    zk_profile: ZkProfile,
+    escrow_from_402: bool,
+    escrow_contract: Option<String>,
+}
+
+impl QueryMessageBuilder {
+    /// Set the query ID
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = id.into();
+        self
+    }
+
+    /// Set the buyer identifier
+    pub fn from(mut self, from: impl Into<String>) -> Self {
+        self.from = from.into();
+        self
+    }
+
+    /// Set the seller identifier
+    pub fn to(mut self, to: impl Into<String>) -> Self {
+        self.to = to.into();
+        self
+    }
+
+    /// Set the asset denomination
+    pub fn asset(mut self, asset: impl Into<String>) -> Self {
+        self.asset = asset.into();
+        self
+    }
+
+    /// Set the amount in smallest units
+    pub fn amount(mut self, amount: u64) -> Self {
+        self.amount = amount;
+        self
+    }
+
+    /// Set the buyer's ZK preference
+    pub fn zk_profile(mut self, zk_profile: ZkProfile) -> Self {
+        self.zk_profile = zk_profile;
+        self
+    }
+
+    /// Set whether the 402 response advertised CoreProver
+    pub fn escrow_from_402(mut self, escrow_from_402: bool) -> Self {
+        self.escrow_from_402 = escrow_from_402;
+        self
+    }
+
+    /// Set the CoreProver contract address from the 402 header
+    pub fn escrow_contract(mut self, contract: impl Into<String>) -> Self {
+        self.escrow_contract = Some(contract.into());
+        self
+    }
+
+    /// Assemble the QUERY and run [`QueryMessage::validate`] on it
+    pub fn build(self) -> Result<QueryMessage, String> {
+        let query = QueryMessage {
+            id: self.id,
+            from: self.from,
+            to: self.to,
+            asset: self.asset,
+            amount: self.amount,
+            escrow_from_402: self.escrow_from_402,
+            escrow_contract_from_402: self.escrow_contract,
+            zk_profile: self.zk_profile,
+        };
+        query.validate()?;
+        Ok(query)
+    }
 }
 
 // ============================================================================
@@ -345,6 +660,18 @@ pub struct OfferMessage {
     ///
     /// **Spec:** TGP-00 §3.2 - Required field (see §3.6)
     pub economic_envelope: EconomicEnvelope,
+
+    /// Escrow acceptance/fulfillment/claim windows
+    ///
+    /// **Spec:** Extension to TGP-00 §3.2 - Optional field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timing: Option<TimingSummary>,
+
+    /// Alternative settlement paths, in any order
+    ///
+    /// **Spec:** Extension to TGP-00 §3.2 - Optional field, omitted when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub routes: Vec<RouteOption>,
 }
 
 impl OfferMessage {
@@ -361,6 +688,61 @@ impl OfferMessage {
 
         self.economic_envelope.validate()?;
 
+        if let Some(ref timing) = self.timing {
+            timing.validate()?;
+        }
+
+        let max_fees_bps = self.economic_envelope.max_fees_bps;
+        for (i, route) in self.routes.iter().enumerate() {
+            if let Some(ref contract) = route.coreprover_contract {
+                validate_address(contract, &format!("routes[{}].coreprover_contract", i))?;
+            }
+            if route.estimated_fee_bps > max_fees_bps {
+                return Err(format!(
+                    "routes[{}].estimated_fee_bps {} exceeds economic_envelope.max_fees_bps {}",
+                    i, route.estimated_fee_bps, max_fees_bps
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Cheapest route, with lower latency breaking fee ties
+    ///
+    /// Returns `None` when the OFFER lists no routes.
+    pub fn best_route(&self) -> Option<&RouteOption> {
+        self.routes
+            .iter()
+            .min_by_key(|r| (r.estimated_fee_bps, r.estimated_latency_secs))
+    }
+
+    /// Validate that echoed fields match the originating QUERY
+    ///
+    /// # Validation Rules (per TGP-00 §3.2)
+    ///
+    /// - `query_id` must equal the QUERY `id`
+    /// - `asset` must equal the QUERY `asset`
+    /// - `amount` must equal the QUERY `amount`
+    pub fn validate_echo(&self, query: &QueryMessage) -> Result<(), String> {
+        if self.query_id != query.id {
+            return Err(format!(
+                "query_id '{}' does not match QUERY id '{}'",
+                self.query_id, query.id
+            ));
+        }
+        if self.asset != query.asset {
+            return Err(format!(
+                "asset '{}' does not match QUERY asset '{}'",
+                self.asset, query.asset
+            ));
+        }
+        if self.amount != query.amount {
+            return Err(format!(
+                "amount {} does not match QUERY amount {}",
+                self.amount, query.amount
+            ));
+        }
         Ok(())
     }
 
@@ -382,6 +764,8 @@ impl OfferMessage {
             session_id: None,
             zk_required,
             economic_envelope,
+            timing: None,
+            routes: Vec::new(),
         }
     }
 
@@ -396,6 +780,54 @@ impl OfferMessage {
         self.session_id = Some(session_id.into());
         self
     }
+
+    /// Builder method to set the escrow timeline
+    pub fn with_timing(mut self, timing: TimingSummary) -> Self {
+        self.timing = Some(timing);
+        self
+    }
+
+    /// Builder method to append a settlement route
+    pub fn with_route(mut self, route: RouteOption) -> Self {
+        self.routes.push(route);
+        self
+    }
+
+    /// Derive a deterministic CoreProver session ID for a QUERY
+    ///
+    /// `sess-` followed by the hex keccak256 of the controller and query IDs,
+    /// so a retried QUERY maps to the same session on the same controller.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use tbc_core::tgp::messages::OfferMessage;
+    /// let a = OfferMessage::derive_session_id("q-1", "tbc-01");
+    /// assert_eq!(a, OfferMessage::derive_session_id("q-1", "tbc-01"));
+    /// assert!(a.starts_with("sess-"));
+    /// ```
+    pub fn derive_session_id(query_id: &str, controller_id: &str) -> String {
+        use tiny_keccak::{Hasher, Keccak};
+
+        let mut hasher = Keccak::v256();
+        hasher.update(b"tgp-session-v1");
+        // Length prefixes keep ("ab","c") and ("a","bc") distinct
+        for part in [controller_id, query_id] {
+            hasher.update(&(part.len() as u64).to_be_bytes());
+            hasher.update(part.as_bytes());
+        }
+        let mut digest = [0u8; 32];
+        hasher.finalize(&mut digest);
+
+        let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+        format!("sess-{}", hex)
+    }
+
+    /// Builder method to set a session ID derived from this OFFER's query
+    pub fn with_derived_session(self, controller_id: &str) -> Self {
+        let session_id = Self::derive_session_id(&self.query_id, controller_id);
+        self.with_session(session_id)
+    }
 }
 
 // ============================================================================
@@ -457,6 +889,19 @@ pub struct SettleMessage {
     /// **Spec:** TGP-00 §3.3 - Optional field
     #[serde(skip_serializing_if = "Option::is_none")]
     pub session_id: Option<String>,
+
+    /// Chain where `layer8_tx` was executed
+    ///
+    /// Extension: when set, `layer8_tx` is validated with that chain's
+    /// hash length instead of the EVM default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin_chain_id: Option<u64>,
+
+    /// How the escrow terminated
+    ///
+    /// Extension: when set, `success` must equal `outcome.is_success()`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<SettleOutcome>,
 }
 
 impl SettleMessage {
@@ -466,7 +911,19 @@ impl SettleMessage {
         validate_non_empty(&self.query_or_offer_id, "query_or_offer_id")?;
 
         if let Some(ref tx) = self.layer8_tx {
-            validate_transaction_hash(tx, "layer8_tx")?;
+            match self.origin_chain_id {
+                Some(chain_id) => validate_transaction_hash_for_chain(tx, chain_id, "layer8_tx")?,
+                None => validate_transaction_hash(tx, "layer8_tx")?,
+            }
+        }
+
+        if let Some(outcome) = self.outcome {
+            if outcome.is_success() != self.success {
+                return Err(format!(
+                    "success={} contradicts outcome {}",
+                    self.success, outcome
+                ));
+            }
         }
 
         Ok(())
@@ -486,6 +943,8 @@ impl SettleMessage {
             source,
             layer8_tx: None,
             session_id: None,
+            origin_chain_id: None,
+            outcome: None,
         }
     }
 
@@ -500,6 +959,18 @@ impl SettleMessage {
         self.session_id = Some(session_id.into());
         self
     }
+
+    /// Builder method to set the chain `layer8_tx` was executed on
+    pub fn with_origin_chain(mut self, chain_id: u64) -> Self {
+        self.origin_chain_id = Some(chain_id);
+        self
+    }
+
+    /// Builder method to set the escrow outcome
+    pub fn with_outcome(mut self, outcome: SettleOutcome) -> Self {
+        self.outcome = Some(outcome);
+        self
+    }
 }
 
 // ============================================================================
@@ -600,6 +1071,63 @@ impl ErrorMessage {
     }
 }
 
+// ============================================================================
+// CANCEL Message (extension)
+// ============================================================================
+
+/// CANCEL message - buyer aborts a session before settlement
+///
+/// Extension to TGP-00: lets a Buyer end a session after QUERY without
+/// waiting for the state timeout. Valid from `QuerySent` and
+/// `OfferReceived`; the session moves to the terminal `Cancelled` state.
+///
+/// # Examples
+///
+/// ```rust
+/// use tbc_core::tgp::messages::CancelMessage;
+///
+/// let cancel = CancelMessage::new("cancel-1", "sess-abc123")
+///     .with_reason("found a cheaper route");
+///
+/// assert!(cancel.validate().is_ok());
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CancelMessage {
+    /// Unique identifier for this cancellation
+    pub id: String,
+
+    /// Session being cancelled
+    pub session_id: String,
+
+    /// Optional human-readable reason
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl CancelMessage {
+    /// Validate the CANCEL message structure
+    pub fn validate(&self) -> Result<(), String> {
+        validate_non_empty(&self.id, "id")?;
+        validate_non_empty(&self.session_id, "session_id")?;
+        Ok(())
+    }
+
+    /// Create a new CANCEL message
+    pub fn new(id: impl Into<String>, session_id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            session_id: session_id.into(),
+            reason: None,
+        }
+    }
+
+    /// Builder method to set the cancellation reason
+    pub fn with_reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+}
+
 // ============================================================================
 // Standard Error Codes
 // ============================================================================
@@ -637,6 +1165,111 @@ mod tests {
         assert!(invalid.validate().is_err());
     }
 
+    #[test]
+    fn test_query_zk_profile_coherence() {
+        let contract = "0x1234567890abcdef1234567890abcdef12345678";
+        let query = |zk| QueryMessage::new("q-1", "buyer://a", "seller://b", "USDC", 1000, zk);
+        let escrow = |zk| {
+            QueryMessage::with_escrow_from_402(
+                "q-1",
+                "buyer://a",
+                "seller://b",
+                "USDC",
+                1000,
+                contract,
+                zk,
+            )
+        };
+
+        // Coherent combinations
+        assert!(query(ZkProfile::None).validate().is_ok());
+        assert!(query(ZkProfile::Optional).validate().is_ok());
+        assert!(escrow(ZkProfile::Optional).validate().is_ok());
+        assert!(escrow(ZkProfile::Required).validate().is_ok());
+
+        // NONE with escrow metadata
+        assert!(escrow(ZkProfile::None).validate().is_err());
+        let mut flag_only = query(ZkProfile::None);
+        flag_only.escrow_from_402 = true;
+        assert!(flag_only.validate_coherent().is_err());
+        let mut contract_only = query(ZkProfile::None);
+        contract_only.escrow_contract_from_402 = Some(contract.to_string());
+        assert!(contract_only.validate_coherent().is_err());
+
+        // REQUIRED without escrow advertised
+        let err = query(ZkProfile::Required).validate().unwrap_err();
+        assert!(err.contains("REQUIRED"));
+    }
+
+    #[test]
+    fn test_query_builder_minimal() {
+        let query = QueryMessage::builder()
+            .id("q-123")
+            .from("buyer://alice")
+            .to("seller://bob")
+            .asset("USDC")
+            .amount(1000)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            query,
+            QueryMessage::new(
+                "q-123",
+                "buyer://alice",
+                "seller://bob",
+                "USDC",
+                1000,
+                ZkProfile::Optional
+            )
+        );
+    }
+
+    #[test]
+    fn test_query_builder_with_escrow() {
+        let contract = "0x1234567890abcdef1234567890abcdef12345678";
+        let builder = QueryMessage::builder()
+            .id("q-123")
+            .from("buyer://alice")
+            .to("seller://bob")
+            .asset("USDC")
+            .amount(1000)
+            .zk_profile(ZkProfile::Required)
+            .escrow_from_402(true)
+            .escrow_contract(contract);
+
+        let query = builder.clone().build().unwrap();
+        assert_eq!(
+            query,
+            QueryMessage::with_escrow_from_402(
+                "q-123",
+                "buyer://alice",
+                "seller://bob",
+                "USDC",
+                1000,
+                contract,
+                ZkProfile::Required
+            )
+        );
+
+        // Coherence is part of validation
+        assert!(builder.zk_profile(ZkProfile::None).build().is_err());
+    }
+
+    #[test]
+    fn test_query_builder_rejects_zero_amount() {
+        let result = QueryMessage::builder()
+            .id("q-123")
+            .from("buyer://alice")
+            .to("seller://bob")
+            .asset("USDC")
+            .amount(0)
+            .build();
+        assert!(result.unwrap_err().contains("amount"));
+
+        assert!(QueryMessage::builder().build().is_err());
+    }
+
     #[test]
     fn test_offer_message_validation() {
         let valid = OfferMessage::new(
@@ -701,4 +1334,307 @@ mod tests {
         assert!(offer.coreprover_contract.is_some());
         assert!(offer.session_id.is_some());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_offer_validate_echo() {
+        let query = QueryMessage::new(
+            "q-123",
+            "buyer://alice",
+            "seller://bob",
+            "USDC",
+            1000,
+            ZkProfile::Optional,
+        );
+        let offer = OfferMessage::new(
+            "offer-123",
+            "q-123",
+            "USDC",
+            1000,
+            false,
+            EconomicEnvelope::new(50),
+        );
+        assert!(offer.validate_echo(&query).is_ok());
+
+        let mut wrong_amount = offer.clone();
+        wrong_amount.amount = 999;
+        assert!(wrong_amount.validate_echo(&query).unwrap_err().contains("amount"));
+
+        let mut wrong_asset = offer.clone();
+        wrong_asset.asset = "ETH".to_string();
+        assert!(wrong_asset.validate_echo(&query).unwrap_err().contains("asset"));
+
+        let mut wrong_query = offer;
+        wrong_query.query_id = "q-other".to_string();
+        assert!(wrong_query.validate_echo(&query).unwrap_err().contains("query_id"));
+    }
+
+    #[test]
+    fn test_from_json_strict_rejects_misspelled_field() {
+        let json = r#"{
+            "phase": "QUERY",
+            "id": "q-1",
+            "from": "buyer://alice",
+            "to": "seller://bob",
+            "asset": "USDC",
+            "amount": 1000,
+            "escrow_from_402": true,
+            "escrow_contract_from402": "0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb0",
+            "zk_profile": "OPTIONAL"
+        }"#;
+
+        // Lenient default silently drops the typo
+        match serde_json::from_str::<TGPMessage>(json).unwrap() {
+            TGPMessage::Query(q) => assert_eq!(q.escrow_contract_from_402, None),
+            other => panic!("expected Query, got {:?}", other),
+        }
+
+        let err = TGPMessage::from_json_strict(json).unwrap_err();
+        assert!(err.contains("escrow_contract_from402"));
+
+        // Correctly spelled message passes strict parsing
+        let fixed = json.replace("escrow_contract_from402", "escrow_contract_from_402");
+        assert!(TGPMessage::from_json_strict(&fixed).is_ok());
+    }
+
+    #[test]
+    fn test_from_json_strict_checks_envelope() {
+        let offer = OfferMessage::new(
+            "offer-1",
+            "q-1",
+            "USDC",
+            1000,
+            false,
+            EconomicEnvelope::new(50),
+        );
+        let mut value = serde_json::to_value(TGPMessage::Offer(offer)).unwrap();
+        assert!(TGPMessage::from_json_strict(&value.to_string()).is_ok());
+
+        value["economic_envelope"]["max_fee_bps"] = serde_json::json!(10);
+        assert!(TGPMessage::from_json_strict(&value.to_string()).is_err());
+    }
+
+    #[test]
+    fn test_parse_and_validate() {
+        let query = QueryMessage::new(
+            "q-1",
+            "buyer://alice",
+            "seller://bob",
+            "USDC",
+            1000,
+            ZkProfile::Optional,
+        );
+        let json = serde_json::to_string(&TGPMessage::Query(query.clone())).unwrap();
+        assert_eq!(
+            TGPMessage::parse_and_validate(&json).unwrap(),
+            TGPMessage::Query(query.clone())
+        );
+        assert!(TGPMessage::parse_and_validate_bytes(json.as_bytes()).is_ok());
+
+        // Parses fine, but a zero amount is not a valid QUERY
+        let zero = json.replace(r#""amount":1000"#, r#""amount":0"#);
+        assert!(serde_json::from_str::<TGPMessage>(&zero).is_ok());
+        let err = TGPMessage::parse_and_validate(&zero).unwrap_err();
+        assert!(err.starts_with("invalid QUERY message 'q-1': "), "{}", err);
+        assert!(err.ends_with("amount must be greater than 0"), "{}", err);
+
+        let err = TGPMessage::parse_and_validate_bytes(b"{\"phase\":\"QUERY\",").unwrap_err();
+        assert!(err.starts_with("invalid JSON: "), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_lenient_unknown_phase() {
+        let json = r#"{"phase":"PING","id":"ping-1","nonce":7}"#;
+
+        match TGPMessage::parse_lenient(json).unwrap() {
+            LenientMessage::Unknown { phase, raw } => {
+                assert_eq!(phase, "PING");
+                assert_eq!(raw["nonce"], 7);
+            }
+            other => panic!("expected Unknown, got {:?}", other),
+        }
+
+        // Strict decoding still rejects it
+        assert!(serde_json::from_str::<TGPMessage>(json).is_err());
+    }
+
+    #[test]
+    fn test_parse_lenient_known_phase() {
+        let message = TGPMessage::Error(ErrorMessage::new("err-1", "TIMEOUT", "timed out"));
+        let json = serde_json::to_string(&message).unwrap();
+
+        assert_eq!(
+            TGPMessage::parse_lenient(&json).unwrap(),
+            LenientMessage::Known(message)
+        );
+
+        // Malformed known phase is still an error
+        assert!(TGPMessage::parse_lenient(r#"{"phase":"QUERY","id":"q-1"}"#).is_err());
+    }
+
+    #[test]
+    fn test_settle_validates_hash_for_origin_chain() {
+        let tx = "0x9f2d8e7c3b1a5f4e2d1c0b9a8f7e6d5c4b3a2f1e0d9c8b7a6f5e4d3c2b1a0f9e";
+        let settle = SettleMessage::new("settle-1", "offer-1", true, SettleSource::BuyerNotify)
+            .with_tx(tx)
+            .with_origin_chain(8453);
+        assert!(settle.validate().is_ok());
+
+        let short = settle.clone().with_tx("0x1234");
+        assert!(short.validate().unwrap_err().contains("layer8_tx"));
+
+        // Round-trips through JSON, including strict parsing
+        let json = serde_json::to_string(&TGPMessage::Settle(settle)).unwrap();
+        assert!(TGPMessage::from_json_strict(&json).is_ok());
+    }
+
+    #[test]
+    fn test_settle_outcome_must_match_success() {
+        let refunded =
+            SettleMessage::new("settle-1", "offer-1", false, SettleSource::ControllerWatcher)
+                .with_outcome(SettleOutcome::Refunded);
+        assert!(refunded.validate().is_ok());
+
+        let contradictory =
+            SettleMessage::new("settle-1", "offer-1", true, SettleSource::ControllerWatcher)
+                .with_outcome(SettleOutcome::Refunded);
+        assert!(contradictory.validate().is_err());
+    }
+
+    #[test]
+    fn test_derive_session_id() {
+        let a = OfferMessage::derive_session_id("q-1", "tbc-01");
+        assert_eq!(a, OfferMessage::derive_session_id("q-1", "tbc-01"));
+        assert!(a.starts_with("sess-"));
+        assert_eq!(a.len(), "sess-".len() + 64);
+
+        assert_ne!(a, OfferMessage::derive_session_id("q-2", "tbc-01"));
+        assert_ne!(a, OfferMessage::derive_session_id("q-1", "tbc-02"));
+        assert_ne!(
+            OfferMessage::derive_session_id("ab", "c"),
+            OfferMessage::derive_session_id("b", "ca")
+        );
+
+        let offer = OfferMessage::new(
+            "offer-1",
+            "q-1",
+            "USDC",
+            1000,
+            false,
+            EconomicEnvelope::new(50),
+        )
+        .with_derived_session("tbc-01");
+        assert_eq!(offer.session_id.as_deref(), Some(a.as_str()));
+    }
+
+    #[test]
+    fn test_offer_timing_round_trip() {
+        // pizza_delivery windows: 30 min accept, 1 h fulfill, 1 h claim
+        let offer = OfferMessage::new(
+            "offer-1",
+            "q-1",
+            "USDC",
+            1000,
+            true,
+            EconomicEnvelope::new(50),
+        )
+        .with_timing(TimingSummary::new(1800, 3600, 3600));
+        assert!(offer.validate().is_ok());
+
+        let json = serde_json::to_string(&offer).unwrap();
+        assert!(json.contains(r#""fulfillment_window_secs":3600"#));
+        let parsed: OfferMessage = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, offer);
+        assert_eq!(parsed.timing.unwrap().max_lock_secs(), 9000);
+
+        // Absent timing is omitted and still parses
+        let bare = OfferMessage { timing: None, ..offer.clone() };
+        let json = serde_json::to_string(&bare).unwrap();
+        assert!(!json.contains("timing"));
+        assert_eq!(serde_json::from_str::<OfferMessage>(&json).unwrap(), bare);
+
+        let strict = TGPMessage::from_json_strict(
+            &serde_json::to_string(&TGPMessage::Offer(offer.clone())).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(strict, TGPMessage::Offer(offer.clone()));
+
+        let zero = offer.with_timing(TimingSummary::new(1800, 3600, 0));
+        assert!(zero.validate().unwrap_err().contains("claim_window_secs"));
+    }
+
+    fn routed_offer(max_fees_bps: u32) -> OfferMessage {
+        OfferMessage::new(
+            "offer-1",
+            "q-1",
+            "USDC",
+            1000,
+            true,
+            EconomicEnvelope::new(max_fees_bps),
+        )
+        .with_route(RouteOption::new(1, 40, 180))
+        .with_route(
+            RouteOption::new(369, 25, 60)
+                .with_coreprover("0x742d35Cc6634C0532925a3b844Bc9e7595f0bEbA"),
+        )
+        .with_route(RouteOption::new(8453, 25, 20))
+    }
+
+    #[test]
+    fn test_offer_best_route_ranking() {
+        let offer = routed_offer(50);
+        assert!(offer.validate().is_ok());
+
+        // Equal fees fall back to latency
+        let best = offer.best_route().unwrap();
+        assert_eq!(best.chain_id, 8453);
+
+        let json = serde_json::to_string(&TGPMessage::Offer(offer.clone())).unwrap();
+        assert_eq!(
+            TGPMessage::from_json_strict(&json).unwrap(),
+            TGPMessage::Offer(offer.clone())
+        );
+
+        // No routes: field omitted, no best route
+        let bare = OfferMessage { routes: Vec::new(), ..offer };
+        assert!(bare.best_route().is_none());
+        assert!(!serde_json::to_string(&bare).unwrap().contains("routes"));
+    }
+
+    #[test]
+    fn test_offer_route_exceeding_envelope() {
+        let err = routed_offer(30).validate().unwrap_err();
+        assert!(err.contains("routes[0].estimated_fee_bps 40"));
+        assert!(err.contains("max_fees_bps 30"));
+
+        let mut offer = routed_offer(50);
+        offer.routes[1].coreprover_contract = Some("not-an-address".into());
+        assert!(offer.validate().unwrap_err().contains("routes[1].coreprover_contract"));
+    }
+
+    #[test]
+    fn test_cancel_message_round_trip() {
+        let cancel = CancelMessage::new("cancel-1", "sess-1").with_reason("changed my mind");
+        assert!(cancel.validate().is_ok());
+
+        let message = TGPMessage::Cancel(cancel);
+        assert_eq!(message.id(), "cancel-1");
+        assert_eq!(message.phase(), "CANCEL");
+        assert!(message.validate().is_ok());
+
+        let json = serde_json::to_string(&message).unwrap();
+        assert!(json.starts_with(r#"{"phase":"CANCEL","id":"cancel-1""#), "{}", json);
+        assert_eq!(serde_json::from_str::<TGPMessage>(&json).unwrap(), message);
+        assert_eq!(TGPMessage::from_json_strict(&json).unwrap(), message);
+        assert_eq!(
+            TGPMessage::parse_lenient(&json).unwrap(),
+            LenientMessage::Known(message)
+        );
+
+        // reason is optional and omitted when absent
+        let bare = r#"{"phase":"CANCEL","id":"cancel-2","session_id":"sess-1"}"#;
+        let parsed: TGPMessage = serde_json::from_str(bare).unwrap();
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), bare);
+
+        assert!(CancelMessage::new("cancel-3", "").validate().is_err());
+    }
+}
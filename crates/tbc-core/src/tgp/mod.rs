@@ -1,8 +1,20 @@
 pub mod state;
+pub mod state_store;
 pub mod messages;
 pub mod validation;
+pub mod correlation;
+pub mod verification;
 pub mod types;
 
 // Optional: Re-export commonly used items
-pub use state::{TGPState, TGPSession, TGPStateError};
-pub use messages::{TGPMessage, QueryMessage, OfferMessage, SettleMessage, ErrorMessage};
\ No newline at end of file
+pub use state::{
+    Clock, MockClock, RecordingObserver, RetryClock, SessionObserver, SessionSummary,
+    SystemClock, TGPSession, TGPState, TGPStateError, Tdr, TimeoutPolicy, TokioClock,
+};
+pub use state_store::{RestoredSessions, SessionStore};
+pub use correlation::TGPCorrelationValidator;
+pub use verification::{ChainVerifier, SettlementVerifier};
+pub use messages::{
+    TGPMessage, LenientMessage, QueryMessage, OfferMessage, SettleMessage, ErrorMessage,
+    CancelMessage, QueryMessageBuilder,
+};
\ No newline at end of file
@@ -43,6 +43,7 @@
 //!  └──────────┘
 //!
 //!    Any state ──ERROR──> Errored
+//!    QuerySent / OfferReceived ──CANCEL──> Cancelled
 //! ```
 //!
 //! # Examples
@@ -62,8 +63,14 @@
 //! session.transition(TGPState::Settled).unwrap();
 //! ```
 
+use super::messages::SettleMessage;
+use super::types::SettleSource;
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use thiserror::Error;
 
 // ============================================================================
@@ -95,7 +102,7 @@ pub enum TGPStateError {
     /// Session is in a terminal state and cannot transition
     ///
     /// Occurs when trying to transition from a terminal state
-    /// (Settled or Errored) to any other state.
+    /// (Settled, Errored or Cancelled) to any other state.
     #[error("Session is in terminal state {0:?} and cannot transition")]
     TerminalState(TGPState),
 
@@ -104,6 +111,36 @@ pub enum TGPStateError {
     /// Not necessarily an error, but indicates a redundant transition attempt.
     #[error("Session already in state {0:?}")]
     AlreadyInState(TGPState),
+
+    /// Action guarding a transition failed on every attempt
+    ///
+    /// Returned by [`TGPSession::transition_with_retry`]; the session has
+    /// been force-errored.
+    #[error("Transition to {target:?} failed after {attempts} attempts: {last_error}")]
+    RetriesExhausted {
+        target: TGPState,
+        attempts: u32,
+        last_error: String,
+    },
+
+    /// Session snapshot could not be restored
+    ///
+    /// Returned by [`TGPSession::from_snapshot`] when the JSON does not
+    /// parse or describes a session the state machine could not produce.
+    #[error("Invalid session snapshot: {0}")]
+    InvalidSnapshot(String),
+
+    /// Session store file could not be read or written
+    #[error("Session store I/O failed: {0}")]
+    StoreIo(String),
+
+    /// A TDR was requested before the session settled
+    #[error("Session is in state {0:?}; a TDR is only emitted from Settled")]
+    NotSettled(TGPState),
+
+    /// The SETTLE message does not belong to this session
+    #[error("SETTLE does not match session: {0}")]
+    SettleMismatch(String),
 }
 
 // ============================================================================
@@ -127,6 +164,7 @@ pub enum TGPStateError {
 /// - **Finalizing**: Layer-8 transaction submitted, waiting for confirmation
 /// - **Settled**: Settlement confirmed, TDR emitted (terminal state)
 /// - **Errored**: Terminal error state, may retry from Idle (terminal state)
+/// - **Cancelled**: Buyer sent CANCEL before accepting (terminal state)
 ///
 /// # Examples
 ///
@@ -194,9 +232,29 @@ pub enum TGPState {
     /// **Entry:** ERROR message OR critical failure from any state
     /// **Exit:** None (terminal, but new session may be initiated)
     Errored,
+
+    /// Buyer aborted the session with a CANCEL message
+    ///
+    /// **Terminal State:** Cannot transition to any other state
+    ///
+    /// **Entry:** CANCEL sent from QuerySent or OfferReceived
+    /// **Exit:** None (terminal)
+    Cancelled,
 }
 
 impl TGPState {
+    /// All states, in lifecycle order
+    pub const ALL: [TGPState; 8] = [
+        TGPState::Idle,
+        TGPState::QuerySent,
+        TGPState::OfferReceived,
+        TGPState::AcceptSent,
+        TGPState::Finalizing,
+        TGPState::Settled,
+        TGPState::Errored,
+        TGPState::Cancelled,
+    ];
+
     /// Check if this is a terminal state
     ///
     /// Terminal states cannot transition to any other state.
@@ -207,10 +265,11 @@ impl TGPState {
     /// # use tbc_core::tgp::state::TGPState;
     /// assert!(TGPState::Settled.is_terminal());
     /// assert!(TGPState::Errored.is_terminal());
+    /// assert!(TGPState::Cancelled.is_terminal());
     /// assert!(!TGPState::Idle.is_terminal());
     /// ```
     pub fn is_terminal(&self) -> bool {
-        matches!(self, TGPState::Settled | TGPState::Errored)
+        matches!(self, TGPState::Settled | TGPState::Errored | TGPState::Cancelled)
     }
 
     /// Check if this state can transition to the target state
@@ -240,10 +299,12 @@ impl TGPState {
             // From QuerySent
             (QuerySent, OfferReceived) => true,
             (QuerySent, Errored) => true,
+            (QuerySent, Cancelled) => true,
 
             // From OfferReceived
             (OfferReceived, AcceptSent) => true,
             (OfferReceived, Errored) => true,
+            (OfferReceived, Cancelled) => true,
 
             // From AcceptSent
             (AcceptSent, Finalizing) => true,
@@ -261,9 +322,65 @@ impl TGPState {
         }
     }
 
+    /// Render the state machine as a Graphviz DOT graph with labeled edges
+    ///
+    /// Every `(from, to)` pair allowed by [`can_transition_to`](Self::can_transition_to)
+    /// becomes an edge labeled with the message or event that drives it.
+    /// Terminal states are drawn with a double border.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use tbc_core::tgp::state::TGPState;
+    /// let dot = TGPState::transition_graph_dot();
+    /// assert!(dot.contains("Idle -> QuerySent [label=\"QUERY\"];"));
+    /// ```
+    pub fn transition_graph_dot() -> String {
+        let mut dot = String::from("digraph TGPState {\n    rankdir=LR;\n");
+
+        for state in TGPState::ALL {
+            let shape = if state.is_terminal() { "doublecircle" } else { "circle" };
+            dot.push_str(&format!("    {:?} [shape={}];\n", state, shape));
+        }
+
+        for from in TGPState::ALL {
+            for to in TGPState::ALL {
+                if from.can_transition_to(to) {
+                    dot.push_str(&format!(
+                        "    {:?} -> {:?} [label=\"{}\"];\n",
+                        from,
+                        to,
+                        from.transition_label(to)
+                    ));
+                }
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// What drives the transition to `target`, for graph labels
+    fn transition_label(&self, target: TGPState) -> &'static str {
+        use TGPState::*;
+
+        match (self, target) {
+            (Idle, QuerySent) => "QUERY",
+            (QuerySent, OfferReceived) => "OFFER",
+            (OfferReceived, AcceptSent) => "ACCEPT",
+            (AcceptSent, Finalizing) => "submit",
+            (Finalizing, Settled) => "SETTLE",
+            (_, Cancelled) => "CANCEL",
+            (_, Errored) => "ERROR / timeout",
+            _ => "",
+        }
+    }
+
     /// Get the typical timeout for this state (in seconds)
     ///
-    /// Returns the recommended timeout duration per TGP-00 §4.
+    /// Returns the recommended timeout duration per TGP-00 §4, i.e. the
+    /// [`TimeoutPolicy::default`] value. Sessions apply their own
+    /// [`TGPSession::timeout_policy`].
     ///
     /// # Examples
     ///
@@ -274,15 +391,7 @@ impl TGPState {
     /// assert_eq!(TGPState::Settled.timeout_seconds(), None);
     /// ```
     pub fn timeout_seconds(&self) -> Option<u64> {
-        match self {
-            TGPState::QuerySent => Some(30),      // 30 seconds
-            TGPState::OfferReceived => Some(300), // 5 minutes
-            TGPState::Finalizing => Some(600),    // 10 minutes
-            TGPState::Idle => None,               // No timeout in idle
-            TGPState::AcceptSent => Some(60),     // 1 minute (implementation-specific)
-            TGPState::Settled => None,            // Terminal state
-            TGPState::Errored => None,            // Terminal state
-        }
+        TimeoutPolicy::default().timeout_for(*self)
     }
 
     /// Get a human-readable description of this state
@@ -295,6 +404,7 @@ impl TGPState {
             TGPState::Finalizing => "Waiting for settlement confirmation",
             TGPState::Settled => "Settlement completed successfully",
             TGPState::Errored => "Session terminated with error",
+            TGPState::Cancelled => "Session cancelled by buyer",
         }
     }
 }
@@ -305,6 +415,127 @@ impl Default for TGPState {
     }
 }
 
+// ============================================================================
+// Timeout Policy
+// ============================================================================
+
+/// Per-state timeouts (in seconds) applied by [`TGPSession::transition`]
+///
+/// `None` disables the timeout for that state. `Idle` and the terminal
+/// states never time out.
+///
+/// # Examples
+///
+/// ```rust
+/// # use tbc_core::tgp::state::{TGPState, TimeoutPolicy};
+/// let policy = TimeoutPolicy {
+///     query_sent: Some(5),
+///     ..TimeoutPolicy::default()
+/// };
+/// assert_eq!(policy.timeout_for(TGPState::QuerySent), Some(5));
+/// assert_eq!(policy.timeout_for(TGPState::OfferReceived), Some(300));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeoutPolicy {
+    /// Waiting for OFFER (default 30 seconds)
+    pub query_sent: Option<u64>,
+    /// Reviewing OFFER (default 5 minutes)
+    pub offer_received: Option<u64>,
+    /// Initiating settlement (default 1 minute, implementation-specific)
+    pub accept_sent: Option<u64>,
+    /// Waiting for settlement confirmation (default 10 minutes)
+    pub finalizing: Option<u64>,
+}
+
+impl TimeoutPolicy {
+    /// Timeout for `state`, if it has one
+    pub fn timeout_for(&self, state: TGPState) -> Option<u64> {
+        match state {
+            TGPState::QuerySent => self.query_sent,
+            TGPState::OfferReceived => self.offer_received,
+            TGPState::AcceptSent => self.accept_sent,
+            TGPState::Finalizing => self.finalizing,
+            TGPState::Idle | TGPState::Settled | TGPState::Errored | TGPState::Cancelled => None,
+        }
+    }
+}
+
+impl Default for TimeoutPolicy {
+    /// Recommended values per TGP-00 §4
+    fn default() -> Self {
+        Self {
+            query_sent: Some(30),
+            offer_received: Some(300),
+            accept_sent: Some(60),
+            finalizing: Some(600),
+        }
+    }
+}
+
+// ============================================================================
+// Clocks
+// ============================================================================
+
+/// Source of the current Unix time for session timestamps and timeouts
+///
+/// [`TGPSession::new`] and [`TGPSession::transition`] read [`SystemClock`];
+/// [`TGPSession::new_with_clock`] and [`TGPSession::transition_at`] take the
+/// time explicitly so timeout behavior can be tested deterministically.
+pub trait Clock: Send + Sync {
+    /// Seconds since the Unix epoch
+    fn now_unix(&self) -> u64;
+}
+
+/// [`Clock`] backed by `SystemTime::now()`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_unix(&self) -> u64 {
+        current_timestamp()
+    }
+}
+
+/// Manually advanced [`Clock`] (for tests)
+///
+/// # Examples
+///
+/// ```rust
+/// # use tbc_core::tgp::state::{Clock, MockClock};
+/// let clock = MockClock::new(1_000);
+/// clock.advance(30);
+/// assert_eq!(clock.now_unix(), 1_030);
+/// ```
+#[derive(Debug, Default)]
+pub struct MockClock {
+    now: AtomicU64,
+}
+
+impl MockClock {
+    /// Clock stopped at `now_unix`
+    pub fn new(now_unix: u64) -> Self {
+        Self {
+            now: AtomicU64::new(now_unix),
+        }
+    }
+
+    /// Move the clock forward by `secs`
+    pub fn advance(&self, secs: u64) {
+        self.now.fetch_add(secs, Ordering::SeqCst);
+    }
+
+    /// Set the clock to `now_unix`
+    pub fn set(&self, now_unix: u64) {
+        self.now.store(now_unix, Ordering::SeqCst);
+    }
+}
+
+impl Clock for MockClock {
+    fn now_unix(&self) -> u64 {
+        self.now.load(Ordering::SeqCst)
+    }
+}
+
 // ============================================================================
 // TGPSession Struct
 // ============================================================================
@@ -329,6 +560,7 @@ impl Default for TGPState {
 /// | `created_at` | Unix timestamp of session creation |
 /// | `updated_at` | Unix timestamp of last state change |
 /// | `timeout_at` | Unix timestamp when session expires |
+/// | `timeout_policy` | Per-state timeouts used to compute `timeout_at` |
 ///
 /// # Examples
 ///
@@ -376,6 +608,18 @@ pub struct TGPSession {
     /// **Present:** From OfferReceived onwards
     pub offer_id: Option<String>,
 
+    /// Asset denomination of the originating QUERY
+    ///
+    /// **Present:** From QuerySent onwards, when recorded by the caller
+    #[serde(default)]
+    pub asset: Option<String>,
+
+    /// Amount in smallest unit of the originating QUERY
+    ///
+    /// **Present:** From QuerySent onwards, when recorded by the caller
+    #[serde(default)]
+    pub amount: Option<u64>,
+
     /// Unix timestamp of session creation (seconds since epoch)
     ///
     /// **Spec:** Used for audit trail and timeout calculation
@@ -392,6 +636,12 @@ pub struct TGPSession {
     ///
     /// **None:** For states without timeouts (Idle, terminal states)
     pub timeout_at: Option<u64>,
+
+    /// Per-state timeouts applied on each transition
+    ///
+    /// **Default:** [`TimeoutPolicy::default`] (TGP-00 §4 values)
+    #[serde(default)]
+    pub timeout_policy: TimeoutPolicy,
 }
 
 impl TGPSession {
@@ -409,18 +659,51 @@ impl TGPSession {
     /// assert_eq!(session.session_id, "sess-abc123");
     /// ```
     pub fn new(session_id: impl Into<String>) -> Self {
-        let now = current_timestamp();
+        Self::new_with_clock(session_id, &SystemClock)
+    }
+
+    /// Create a new session in Idle state, timestamped by `clock`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use tbc_core::tgp::state::{MockClock, TGPSession};
+    /// let clock = MockClock::new(1_700_000_000);
+    /// let session = TGPSession::new_with_clock("sess-abc123", &clock);
+    /// assert_eq!(session.created_at, 1_700_000_000);
+    /// ```
+    pub fn new_with_clock(session_id: impl Into<String>, clock: &dyn Clock) -> Self {
+        let now = clock.now_unix();
         Self {
             session_id: session_id.into(),
             state: TGPState::Idle,
             query_id: None,
             offer_id: None,
+            asset: None,
+            amount: None,
             created_at: now,
             updated_at: now,
             timeout_at: None,
+            timeout_policy: TimeoutPolicy::default(),
         }
     }
 
+    /// Create a new session in Idle state with deployment-specific timeouts
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use tbc_core::tgp::state::{TGPSession, TGPState, TimeoutPolicy};
+    /// let policy = TimeoutPolicy { finalizing: Some(3_600), ..TimeoutPolicy::default() };
+    /// let session = TGPSession::with_timeout_policy("sess-abc123", policy);
+    /// assert_eq!(session.timeout_policy.timeout_for(TGPState::Finalizing), Some(3_600));
+    /// ```
+    pub fn with_timeout_policy(session_id: impl Into<String>, policy: TimeoutPolicy) -> Self {
+        let mut session = Self::new(session_id);
+        session.timeout_policy = policy;
+        session
+    }
+
     /// Transition to a new state with validation
     ///
     /// This method validates the transition, updates timestamps, sets timeouts,
@@ -450,8 +733,32 @@ impl TGPSession {
     /// assert!(session.transition(TGPState::Settled).is_err());
     /// ```
     pub fn transition(&mut self, new_state: TGPState) -> Result<(), TGPStateError> {
+        self.transition_at(new_state, current_timestamp())
+    }
+
+    /// [`transition`](Self::transition) at an explicit Unix time `now`
+    ///
+    /// The timeout check and the new `updated_at` / `timeout_at` all use
+    /// `now` instead of the system clock.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use tbc_core::tgp::state::{Clock, MockClock, TGPSession, TGPState, TGPStateError};
+    /// let clock = MockClock::new(1_000);
+    /// let mut session = TGPSession::new_with_clock("sess-123", &clock);
+    /// session.transition_at(TGPState::QuerySent, clock.now_unix()).unwrap();
+    /// assert_eq!(session.timeout_at, Some(1_030));
+    ///
+    /// clock.advance(31);
+    /// assert_eq!(
+    ///     session.transition_at(TGPState::OfferReceived, clock.now_unix()),
+    ///     Err(TGPStateError::SessionTimeout(1_030))
+    /// );
+    /// ```
+    pub fn transition_at(&mut self, new_state: TGPState, now: u64) -> Result<(), TGPStateError> {
         // Check if session has timed out
-        if self.is_timed_out() {
+        if self.is_timed_out_at(now) {
             let timeout = self.timeout_at.unwrap_or(0);
             return Err(TGPStateError::SessionTimeout(timeout));
         }
@@ -474,10 +781,10 @@ impl TGPSession {
         // Perform transition
         let old_state = self.state;
         self.state = new_state;
-        self.updated_at = current_timestamp();
+        self.updated_at = now;
 
         // Set timeout for new state
-        if let Some(timeout_seconds) = new_state.timeout_seconds() {
+        if let Some(timeout_seconds) = self.timeout_policy.timeout_for(new_state) {
             self.timeout_at = Some(self.updated_at + timeout_seconds);
         } else {
             self.timeout_at = None;
@@ -504,8 +811,13 @@ impl TGPSession {
     /// assert!(!session.is_timed_out());
     /// ```
     pub fn is_timed_out(&self) -> bool {
+        self.is_timed_out_at(current_timestamp())
+    }
+
+    /// Check if the session has timed out at Unix time `now`
+    pub fn is_timed_out_at(&self, now: u64) -> bool {
         if let Some(timeout) = self.timeout_at {
-            current_timestamp() > timeout
+            now > timeout
         } else {
             false
         }
@@ -603,10 +915,361 @@ impl TGPSession {
     }
 }
 
+// ============================================================================
+// Snapshots (crash recovery)
+// ============================================================================
+
+impl TGPSession {
+    /// Serialize the session to JSON for crash recovery
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use tbc_core::tgp::state::{TGPSession, TGPState};
+    /// let mut session = TGPSession::new("sess-123");
+    /// session.transition(TGPState::QuerySent).unwrap();
+    ///
+    /// let restored = TGPSession::from_snapshot(&session.to_snapshot()).unwrap();
+    /// assert_eq!(restored, session);
+    /// ```
+    pub fn to_snapshot(&self) -> String {
+        serde_json::to_string(self).expect("TGPSession always serializes")
+    }
+
+    /// Restore a session written by [`to_snapshot`](Self::to_snapshot)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TGPStateError::InvalidSnapshot`] if the JSON does not parse
+    /// or the session is not internally consistent:
+    /// - `session_id` is empty
+    /// - `updated_at` or `timeout_at` is before `created_at`
+    /// - a terminal state carries a timeout
+    pub fn from_snapshot(json: &str) -> Result<Self, TGPStateError> {
+        let session: TGPSession =
+            serde_json::from_str(json).map_err(|e| TGPStateError::InvalidSnapshot(e.to_string()))?;
+        session.check_consistency()?;
+        Ok(session)
+    }
+
+    fn check_consistency(&self) -> Result<(), TGPStateError> {
+        let invalid = |msg: String| {
+            Err(TGPStateError::InvalidSnapshot(format!("session {}: {}", self.session_id, msg)))
+        };
+
+        if self.session_id.is_empty() {
+            return invalid("empty session_id".into());
+        }
+        if self.updated_at < self.created_at {
+            return invalid(format!(
+                "updated_at {} before created_at {}",
+                self.updated_at, self.created_at
+            ));
+        }
+        match self.timeout_at {
+            Some(t) if t < self.created_at => invalid(format!(
+                "timeout_at {} before created_at {}",
+                t, self.created_at
+            )),
+            Some(_) if self.state.is_terminal() => {
+                invalid(format!("terminal state {:?} has a timeout", self.state))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+// ============================================================================
+// Terminal-State Observers
+// ============================================================================
+
+/// Final summary of a session, handed to observers when it terminates
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionSummary {
+    /// Session that terminated
+    pub session_id: String,
+    /// Terminal state entered (`Settled` or `Errored`)
+    pub final_state: TGPState,
+    /// ID of the originating QUERY, if any
+    pub query_id: Option<String>,
+    /// ID of the accepted OFFER, if any
+    pub offer_id: Option<String>,
+    /// Unix timestamp of session creation
+    pub created_at: u64,
+    /// Unix timestamp the terminal state was entered
+    pub ended_at: u64,
+}
+
+/// Callback fired when a session enters a terminal state
+///
+/// Lets the controller free per-session resources or emit a SETTLE without
+/// polling session state. Fired at most once per session, because terminal
+/// states cannot be left.
+pub trait SessionObserver {
+    /// Called once, after the session has entered `summary.final_state`
+    fn on_terminal(&mut self, summary: &SessionSummary);
+}
+
+/// Observer that records every summary it receives (for tests)
+#[derive(Debug, Clone, Default)]
+pub struct RecordingObserver {
+    /// Summaries in the order they were received
+    pub summaries: Vec<SessionSummary>,
+}
+
+impl SessionObserver for RecordingObserver {
+    fn on_terminal(&mut self, summary: &SessionSummary) {
+        self.summaries.push(summary.clone());
+    }
+}
+
+impl TGPSession {
+    /// Summary of the session as it stands now
+    pub fn summary(&self) -> SessionSummary {
+        SessionSummary {
+            session_id: self.session_id.clone(),
+            final_state: self.state,
+            query_id: self.query_id.clone(),
+            offer_id: self.offer_id.clone(),
+            created_at: self.created_at,
+            ended_at: self.updated_at,
+        }
+    }
+
+    /// [`transition`](Self::transition), notifying `observer` if the
+    /// session enters a terminal state
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use tbc_core::tgp::state::{RecordingObserver, TGPSession, TGPState};
+    /// let mut observer = RecordingObserver::default();
+    /// let mut session = TGPSession::new("sess-123");
+    /// session.transition_observed(TGPState::Errored, &mut observer).unwrap();
+    /// assert_eq!(observer.summaries[0].final_state, TGPState::Errored);
+    /// ```
+    pub fn transition_observed(
+        &mut self,
+        new_state: TGPState,
+        observer: &mut dyn SessionObserver,
+    ) -> Result<(), TGPStateError> {
+        self.transition(new_state)?;
+        if self.state.is_terminal() {
+            observer.on_terminal(&self.summary());
+        }
+        Ok(())
+    }
+
+    /// [`force_error`](Self::force_error), notifying `observer` unless the
+    /// session had already terminated
+    pub fn force_error_observed(&mut self, observer: &mut dyn SessionObserver) {
+        if self.state.is_terminal() {
+            return;
+        }
+        self.force_error();
+        observer.on_terminal(&self.summary());
+    }
+}
+
+// ============================================================================
+// Transaction Detail Records (TDR)
+// ============================================================================
+
+/// Transaction Detail Record emitted when a session settles
+///
+/// The TGP counterpart of a telecom CDR: one immutable record per settled
+/// session, for billing, audit, and reconciliation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Tdr {
+    /// Session that settled
+    pub session_id: String,
+    /// ID of the originating QUERY
+    pub query_id: Option<String>,
+    /// ID of the accepted OFFER
+    pub offer_id: Option<String>,
+    /// State the session ended in (always `Settled`)
+    pub final_state: TGPState,
+    /// Asset denomination of the trade
+    pub asset: Option<String>,
+    /// Amount in smallest unit
+    pub amount: Option<u64>,
+    /// Who reported the settlement
+    pub settle_source: SettleSource,
+    /// Layer-8 transaction hash from the SETTLE
+    pub layer8_tx: Option<String>,
+    /// Unix timestamp of session creation
+    pub created_at: u64,
+    /// Unix timestamp the session entered `Settled`
+    pub settled_at: u64,
+}
+
+impl TGPSession {
+    /// Build the TDR for a settled session from its SETTLE message
+    ///
+    /// # Errors
+    ///
+    /// - [`TGPStateError::NotSettled`] unless the session is `Settled`
+    /// - [`TGPStateError::SettleMismatch`] if `settle` names another session,
+    ///   does not reference this session's QUERY or OFFER, or reports failure
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use tbc_core::tgp::messages::SettleMessage;
+    /// # use tbc_core::tgp::state::{TGPSession, TGPStateError};
+    /// # use tbc_core::tgp::types::SettleSource;
+    /// let session = TGPSession::new("sess-123");
+    /// let settle = SettleMessage::new("settle-1", "offer-1", true, SettleSource::BuyerNotify);
+    /// assert!(matches!(session.emit_tdr(&settle), Err(TGPStateError::NotSettled(_))));
+    /// ```
+    pub fn emit_tdr(&self, settle: &SettleMessage) -> Result<Tdr, TGPStateError> {
+        if self.state != TGPState::Settled {
+            return Err(TGPStateError::NotSettled(self.state));
+        }
+
+        if let Some(ref session_id) = settle.session_id {
+            if session_id != &self.session_id {
+                return Err(TGPStateError::SettleMismatch(format!(
+                    "session_id {} is not {}",
+                    session_id, self.session_id
+                )));
+            }
+        }
+        let correlated = [&self.query_id, &self.offer_id]
+            .into_iter()
+            .any(|id| id.as_deref() == Some(settle.query_or_offer_id.as_str()));
+        if !correlated {
+            return Err(TGPStateError::SettleMismatch(format!(
+                "query_or_offer_id {} is neither the QUERY nor the OFFER",
+                settle.query_or_offer_id
+            )));
+        }
+        if !settle.success {
+            return Err(TGPStateError::SettleMismatch("SETTLE reports failure".into()));
+        }
+
+        Ok(Tdr {
+            session_id: self.session_id.clone(),
+            query_id: self.query_id.clone(),
+            offer_id: self.offer_id.clone(),
+            final_state: self.state,
+            asset: self.asset.clone(),
+            amount: self.amount,
+            settle_source: settle.source,
+            layer8_tx: settle.layer8_tx.clone(),
+            created_at: self.created_at,
+            settled_at: self.updated_at,
+        })
+    }
+}
+
+// ============================================================================
+// Retried Transitions
+// ============================================================================
+
+/// Sleep source for [`TGPSession::transition_with_retry`], injectable so
+/// tests do not wait on real time
+#[async_trait]
+pub trait RetryClock: Send + Sync {
+    /// Wait for `duration`
+    async fn sleep(&self, duration: Duration);
+}
+
+/// [`RetryClock`] backed by `tokio::time::sleep`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioClock;
+
+#[async_trait]
+impl RetryClock for TokioClock {
+    async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+}
+
+impl TGPSession {
+    /// Run `action` up to `attempts` times, then transition to `target`
+    ///
+    /// Meant for transitions that depend on a chain submission which may
+    /// fail transiently (e.g. `AcceptSent → Finalizing`). The wait between
+    /// attempts starts at `backoff` and doubles after each failure.
+    ///
+    /// The session state is not changed until the action succeeds. If the
+    /// transition itself is invalid the action is never run; if every
+    /// attempt fails the session is force-errored and
+    /// [`TGPStateError::RetriesExhausted`] is returned.
+    pub async fn transition_with_retry<F, Fut, E>(
+        &mut self,
+        target: TGPState,
+        attempts: u32,
+        backoff: Duration,
+        clock: &dyn RetryClock,
+        mut action: F,
+    ) -> Result<(), TGPStateError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+        E: Display,
+    {
+        if self.state.is_terminal() {
+            return Err(TGPStateError::TerminalState(self.state));
+        }
+        if !self.state.can_transition_to(target) {
+            return Err(TGPStateError::InvalidTransition(self.state, target));
+        }
+
+        let attempts = attempts.max(1);
+        let mut delay = backoff;
+        let mut last_error = String::new();
+
+        for attempt in 1..=attempts {
+            match action().await {
+                Ok(()) => return self.transition(target),
+                Err(e) => {
+                    last_error = e.to_string();
+                    log::warn!(
+                        "TGP session {} attempt {}/{} toward {:?} failed: {}",
+                        self.session_id,
+                        attempt,
+                        attempts,
+                        target,
+                        last_error
+                    );
+                }
+            }
+            if attempt < attempts {
+                clock.sleep(delay).await;
+                delay = delay.saturating_mul(2);
+            }
+        }
+
+        self.force_error();
+        Err(TGPStateError::RetriesExhausted {
+            target,
+            attempts,
+            last_error,
+        })
+    }
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
 
+/// Render the TGP state machine as a Graphviz DOT graph
+///
+/// Shorthand for [`TGPState::transition_graph_dot`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use tbc_core::tgp::state::tgp_state_graph_dot;
+/// let dot = tgp_state_graph_dot();
+/// assert!(dot.contains("Idle -> QuerySent [label=\"QUERY\"];"));
+/// ```
+pub fn tgp_state_graph_dot() -> String {
+    TGPState::transition_graph_dot()
+}
+
 /// Get current Unix timestamp in seconds
 ///
 /// Returns seconds since Unix epoch (January 1, 1970).
@@ -728,22 +1391,68 @@ mod tests {
 
     #[test]
     fn test_timeout_detection() {
-        let mut session = TGPSession::new("sess-test");
-        session.transition(TGPState::QuerySent).unwrap();
+        let clock = MockClock::new(1_700_000_000);
+        let mut session = TGPSession::new_with_clock("sess-test", &clock);
+        session.transition_at(TGPState::QuerySent, clock.now_unix()).unwrap();
+        assert_eq!(session.timeout_at, Some(1_700_000_030));
 
-        // Manually set timeout to past
-        session.timeout_at = Some(1); // Unix epoch + 1 second (definitely past)
+        // The deadline itself is still in time
+        clock.advance(30);
+        assert!(!session.is_timed_out_at(clock.now_unix()));
 
-        // Should be timed out
-        assert!(session.is_timed_out());
+        clock.advance(1);
+        assert!(session.is_timed_out_at(clock.now_unix()));
 
         // Cannot transition when timed out
-        let result = session.transition(TGPState::OfferReceived);
-        assert!(result.is_err());
-        assert!(matches!(
-            result.unwrap_err(),
-            TGPStateError::SessionTimeout(_)
-        ));
+        let result = session.transition_at(TGPState::OfferReceived, clock.now_unix());
+        assert_eq!(result, Err(TGPStateError::SessionTimeout(1_700_000_030)));
+        assert_eq!(session.state, TGPState::QuerySent);
+    }
+
+    #[test]
+    fn test_transition_at_resets_timeout_per_state() {
+        let clock = MockClock::new(1_000);
+        let mut session = TGPSession::new_with_clock("sess-test", &clock);
+        assert_eq!(session.created_at, 1_000);
+
+        session.transition_at(TGPState::QuerySent, clock.now_unix()).unwrap();
+        clock.advance(20);
+        session.transition_at(TGPState::OfferReceived, clock.now_unix()).unwrap();
+        assert_eq!(session.updated_at, 1_020);
+        assert_eq!(session.timeout_at, Some(1_320));
+
+        // Well past the QUERY timeout, still inside the OFFER one
+        clock.set(1_300);
+        session.transition_at(TGPState::AcceptSent, clock.now_unix()).unwrap();
+        assert_eq!(session.timeout_at, Some(1_360));
+
+        clock.advance(61);
+        assert!(session.transition_at(TGPState::Finalizing, clock.now_unix()).is_err());
+    }
+
+    #[test]
+    fn test_custom_timeout_policy() {
+        let policy = TimeoutPolicy {
+            query_sent: Some(5),
+            accept_sent: None,
+            ..TimeoutPolicy::default()
+        };
+
+        let mut slow = TGPSession::with_timeout_policy("sess-slow", policy);
+        slow.transition_at(TGPState::QuerySent, 1_000).unwrap();
+        assert_eq!(slow.timeout_at, Some(1_005));
+        assert_eq!(
+            slow.transition_at(TGPState::OfferReceived, 1_006),
+            Err(TGPStateError::SessionTimeout(1_005))
+        );
+
+        // Untouched states keep the defaults; a None entry clears the deadline
+        let mut session = TGPSession::with_timeout_policy("sess-test", policy);
+        session.transition_at(TGPState::QuerySent, 1_000).unwrap();
+        session.transition_at(TGPState::OfferReceived, 1_004).unwrap();
+        assert_eq!(session.timeout_at, Some(1_304));
+        session.transition_at(TGPState::AcceptSent, 1_010).unwrap();
+        assert!(session.timeout_at.is_none());
     }
 
     #[test]
@@ -792,6 +1501,7 @@ mod tests {
         assert!(!TGPState::QuerySent.is_terminal());
         assert!(TGPState::Settled.is_terminal());
         assert!(TGPState::Errored.is_terminal());
+        assert!(TGPState::Cancelled.is_terminal());
     }
 
     #[test]
@@ -808,6 +1518,45 @@ mod tests {
         // Terminal states cannot transition
         assert!(!TGPState::Settled.can_transition_to(TGPState::Idle));
         assert!(!TGPState::Errored.can_transition_to(TGPState::QuerySent));
+
+        // CANCEL only before the buyer accepts
+        assert!(TGPState::QuerySent.can_transition_to(TGPState::Cancelled));
+        assert!(TGPState::OfferReceived.can_transition_to(TGPState::Cancelled));
+        assert!(!TGPState::Idle.can_transition_to(TGPState::Cancelled));
+        assert!(!TGPState::AcceptSent.can_transition_to(TGPState::Cancelled));
+        assert!(!TGPState::Cancelled.can_transition_to(TGPState::Errored));
+    }
+
+    #[test]
+    fn test_state_graph_dot() {
+        let dot = tgp_state_graph_dot();
+        assert!(dot.starts_with("digraph TGPState {"));
+        assert_eq!(dot, TGPState::transition_graph_dot());
+        assert!(dot.contains("Idle -> QuerySent [label=\"QUERY\"];"));
+        assert!(dot.contains("Finalizing -> Settled [label=\"SETTLE\"];"));
+        assert!(!dot.contains("Idle -> Settled"));
+        assert!(!dot.contains("Settled ->"));
+    }
+
+    #[test]
+    fn test_transition_graph_dot() {
+        let dot = TGPState::transition_graph_dot();
+        assert!(dot.starts_with("digraph TGPState {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert_eq!(dot.matches('{').count(), 1);
+        assert_eq!(dot.matches('}').count(), 1);
+
+        // Every statement between the braces is a node or an edge
+        let body: Vec<&str> = dot.lines().skip(2).take_while(|l| *l != "}").collect();
+        assert_eq!(body.len(), TGPState::ALL.len() + 12);
+        assert!(body.iter().all(|l| l.starts_with("    ") && l.ends_with("];")));
+
+        assert!(dot.contains("Idle -> QuerySent [label=\"QUERY\"];"));
+        assert!(dot.contains("Finalizing -> Settled [label=\"SETTLE\"];"));
+        assert!(dot.contains("Settled [shape=doublecircle];"));
+        assert!(dot.contains("Idle [shape=circle];"));
+        assert!(!dot.contains("Idle -> Settled"));
+        assert!(!dot.contains("Settled ->"));
     }
 
     #[test]
@@ -852,4 +1601,160 @@ mod tests {
         assert!(session.updated_at >= updated); // Should be equal or greater
         assert_eq!(session.created_at, created); // Created should not change
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_observer_fires_once_on_settled() {
+        let mut observer = RecordingObserver::default();
+        let mut session = TGPSession::new("sess-test");
+        session.query_id = Some("q-123".to_string());
+
+        for state in [
+            TGPState::QuerySent,
+            TGPState::OfferReceived,
+            TGPState::AcceptSent,
+            TGPState::Finalizing,
+        ] {
+            session.transition_observed(state, &mut observer).unwrap();
+        }
+        assert!(observer.summaries.is_empty());
+
+        session
+            .transition_observed(TGPState::Settled, &mut observer)
+            .unwrap();
+
+        // Terminal states cannot be left, so nothing fires again
+        assert!(session
+            .transition_observed(TGPState::Errored, &mut observer)
+            .is_err());
+        session.force_error_observed(&mut observer);
+
+        assert_eq!(observer.summaries.len(), 1);
+        let summary = &observer.summaries[0];
+        assert_eq!(summary.session_id, "sess-test");
+        assert_eq!(summary.final_state, TGPState::Settled);
+        assert_eq!(summary.query_id.as_deref(), Some("q-123"));
+        assert_eq!(session.state, TGPState::Settled);
+    }
+
+    /// Records requested sleeps instead of waiting
+    #[derive(Default)]
+    struct FakeClock {
+        slept: std::sync::Mutex<Vec<Duration>>,
+    }
+
+    #[async_trait]
+    impl RetryClock for FakeClock {
+        async fn sleep(&self, duration: Duration) {
+            self.slept.lock().unwrap().push(duration);
+        }
+    }
+
+    fn accept_sent_session() -> TGPSession {
+        let mut session = TGPSession::new("sess-retry");
+        session.transition(TGPState::QuerySent).unwrap();
+        session.transition(TGPState::OfferReceived).unwrap();
+        session.transition(TGPState::AcceptSent).unwrap();
+        session
+    }
+
+    #[tokio::test]
+    async fn test_transition_with_retry_recovers() {
+        let mut session = accept_sent_session();
+        let clock = FakeClock::default();
+        let mut calls = 0;
+
+        let result = session
+            .transition_with_retry(
+                TGPState::Finalizing,
+                3,
+                Duration::from_millis(100),
+                &clock,
+                || {
+                    calls += 1;
+                    let outcome = if calls < 3 { Err("rpc timeout") } else { Ok(()) };
+                    async move { outcome }
+                },
+            )
+            .await;
+
+        assert!(result.is_ok());
+        assert_eq!(session.state, TGPState::Finalizing);
+        assert_eq!(calls, 3);
+        assert_eq!(
+            *clock.slept.lock().unwrap(),
+            vec![Duration::from_millis(100), Duration::from_millis(200)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_transition_with_retry_exhausted_errors_session() {
+        let mut session = accept_sent_session();
+        let clock = FakeClock::default();
+
+        let result = session
+            .transition_with_retry(TGPState::Finalizing, 2, Duration::ZERO, &clock, || async {
+                Err::<(), _>("nonce too low")
+            })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(TGPStateError::RetriesExhausted { attempts: 2, .. })
+        ));
+        assert_eq!(session.state, TGPState::Errored);
+    }
+
+    #[test]
+    fn test_emit_tdr_from_settled_session() {
+        let clock = MockClock::new(1_700_000_000);
+        let mut session = TGPSession::new_with_clock("sess-tdr", &clock);
+        session.query_id = Some("q-1".into());
+        session.asset = Some("USDC".into());
+        session.amount = Some(1_000_000);
+        session.transition_at(TGPState::QuerySent, 1_700_000_001).unwrap();
+        session.offer_id = Some("offer-1".into());
+        for (state, at) in [
+            (TGPState::OfferReceived, 1_700_000_005),
+            (TGPState::AcceptSent, 1_700_000_010),
+            (TGPState::Finalizing, 1_700_000_020),
+            (TGPState::Settled, 1_700_000_042),
+        ] {
+            session.transition_at(state, at).unwrap();
+        }
+
+        let source = SettleSource::ControllerWatcher;
+        let settle = SettleMessage::new("settle-1", "offer-1", true, source)
+            .with_tx("0xabc")
+            .with_session("sess-tdr");
+        let tdr = session.emit_tdr(&settle).unwrap();
+        assert_eq!(tdr.final_state, TGPState::Settled);
+        assert_eq!(tdr.query_id.as_deref(), Some("q-1"));
+        assert_eq!(tdr.asset.as_deref(), Some("USDC"));
+        assert_eq!(tdr.amount, Some(1_000_000));
+        assert_eq!(tdr.settle_source, SettleSource::ControllerWatcher);
+        assert_eq!(tdr.layer8_tx.as_deref(), Some("0xabc"));
+        assert_eq!((tdr.created_at, tdr.settled_at), (1_700_000_000, 1_700_000_042));
+
+        let json = serde_json::to_value(&tdr).unwrap();
+        assert_eq!(json["settle_source"], "controller-watcher");
+
+        let stranger = settle.clone().with_session("sess-other");
+        assert!(matches!(
+            session.emit_tdr(&stranger),
+            Err(TGPStateError::SettleMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_emit_tdr_requires_settled() {
+        let mut session = TGPSession::new("sess-tdr");
+        session.transition(TGPState::QuerySent).unwrap();
+        session.transition(TGPState::OfferReceived).unwrap();
+
+        let settle = SettleMessage::new("settle-1", "q-1", true, SettleSource::BuyerNotify);
+        assert_eq!(
+            session.emit_tdr(&settle),
+            Err(TGPStateError::NotSettled(TGPState::OfferReceived))
+        );
+    }
+}
@@ -0,0 +1,252 @@
+//# TGP Session Store
+
+//**Destination Path:** `crates/tbc-core/src/tgp/state_store.rs`
+
+//**Implementation:** M1 - TGP Message Parsing & Basic Routing
+
+//! Persistence of TGP sessions for crash recovery
+//!
+//! A [`SessionStore`] writes a whole `HashMap<String, TGPSession>` to one
+//! JSON file and reads it back. Every session is checked with the same
+//! rules as [`TGPSession::from_snapshot`], and sessions whose timeout passed
+//! while the controller was down are reported on load.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//! use tbc_core::tgp::state::{TGPSession, TGPState};
+//! use tbc_core::tgp::state_store::SessionStore;
+//!
+//! let path = std::env::temp_dir().join(format!("tgp-doc-{}.json", std::process::id()));
+//! let store = SessionStore::new(&path);
+//!
+//! let mut session = TGPSession::new("sess-abc123");
+//! session.transition(TGPState::QuerySent).unwrap();
+//! let sessions = HashMap::from([(session.session_id.clone(), session)]);
+//!
+//! store.save(&sessions).unwrap();
+//! let restored = store.load().unwrap();
+//! assert_eq!(restored.sessions, sessions);
+//! assert!(restored.timed_out.is_empty());
+//! # std::fs::remove_file(&path).unwrap();
+//! ```
+
+use super::state::{TGPSession, TGPStateError};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// RestoredSessions
+// ============================================================================
+
+/// Result of [`SessionStore::load`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RestoredSessions {
+    /// Sessions keyed by `session_id`
+    pub sessions: HashMap<String, TGPSession>,
+
+    /// IDs of sessions that are timed out against the current clock,
+    /// sorted. They are restored unchanged; the caller decides whether to
+    /// force-error them.
+    pub timed_out: Vec<String>,
+}
+
+// ============================================================================
+// SessionStore
+// ============================================================================
+
+/// JSON file holding a snapshot of every live TGP session
+#[derive(Debug, Clone)]
+pub struct SessionStore {
+    path: PathBuf,
+}
+
+impl SessionStore {
+    /// Store backed by the file at `path`
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// File the store reads and writes
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write `sessions` to the store file
+    ///
+    /// The file is written to a temporary sibling and renamed into place,
+    /// so a crash mid-write leaves the previous snapshot intact.
+    ///
+    /// # Errors
+    ///
+    /// - [`TGPStateError::InvalidSnapshot`] if a key differs from its
+    ///   session's `session_id`
+    /// - [`TGPStateError::StoreIo`] if the file cannot be written
+    pub fn save(&self, sessions: &HashMap<String, TGPSession>) -> Result<(), TGPStateError> {
+        for (id, session) in sessions {
+            if id != &session.session_id {
+                return Err(TGPStateError::InvalidSnapshot(format!(
+                    "key {} holds session {}",
+                    id, session.session_id
+                )));
+            }
+        }
+
+        // Sorted so identical stores produce identical files
+        let ordered: BTreeMap<_, _> = sessions.iter().collect();
+        let json = serde_json::to_string_pretty(&ordered)
+            .map_err(|e| TGPStateError::InvalidSnapshot(e.to_string()))?;
+
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, json).map_err(|e| self.io_error("write", e))?;
+        fs::rename(&tmp, &self.path).map_err(|e| self.io_error("rename", e))?;
+
+        log::debug!("Saved {} TGP sessions to {}", sessions.len(), self.path.display());
+        Ok(())
+    }
+
+    /// Read the store file back
+    ///
+    /// A missing file restores no sessions. Every session must pass the
+    /// [`TGPSession::from_snapshot`] consistency checks, and its key must
+    /// match its `session_id`. `is_timed_out` is evaluated against the
+    /// current clock, not the clock at save time.
+    ///
+    /// # Errors
+    ///
+    /// - [`TGPStateError::InvalidSnapshot`] for malformed or inconsistent
+    ///   sessions
+    /// - [`TGPStateError::StoreIo`] if the file exists but cannot be read
+    pub fn load(&self) -> Result<RestoredSessions, TGPStateError> {
+        let json = match fs::read_to_string(&self.path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(RestoredSessions::default()),
+            Err(e) => return Err(self.io_error("read", e)),
+        };
+
+        let raw: HashMap<String, serde_json::Value> = serde_json::from_str(&json)
+            .map_err(|e| TGPStateError::InvalidSnapshot(e.to_string()))?;
+
+        let mut restored = RestoredSessions::default();
+        for (id, value) in raw {
+            let session = TGPSession::from_snapshot(&value.to_string())?;
+            if session.session_id != id {
+                return Err(TGPStateError::InvalidSnapshot(format!(
+                    "key {} holds session {}",
+                    id, session.session_id
+                )));
+            }
+            if session.is_timed_out() {
+                restored.timed_out.push(id.clone());
+            }
+            restored.sessions.insert(id, session);
+        }
+        restored.timed_out.sort();
+
+        log::info!(
+            "Restored {} TGP sessions from {} ({} timed out)",
+            restored.sessions.len(),
+            self.path.display(),
+            restored.timed_out.len()
+        );
+        Ok(restored)
+    }
+
+    fn io_error(&self, action: &str, e: std::io::Error) -> TGPStateError {
+        TGPStateError::StoreIo(format!("{} {}: {}", action, self.path.display(), e))
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tgp::state::TGPState;
+
+    fn temp_store(name: &str) -> SessionStore {
+        let path = std::env::temp_dir().join(format!(
+            "tgp-store-{}-{}.json",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+        SessionStore::new(path)
+    }
+
+    fn session(id: &str, states: &[TGPState]) -> TGPSession {
+        let mut session = TGPSession::new(id);
+        for state in states {
+            session.transition(*state).unwrap();
+        }
+        session
+    }
+
+    #[test]
+    fn test_snapshot_rejects_inconsistent_sessions() {
+        let mut querying = session("sess-snap", &[TGPState::QuerySent]);
+        let restored = TGPSession::from_snapshot(&querying.to_snapshot()).unwrap();
+        assert_eq!(restored, querying);
+
+        querying.timeout_at = Some(querying.created_at - 1);
+        assert!(matches!(
+            TGPSession::from_snapshot(&querying.to_snapshot()),
+            Err(TGPStateError::InvalidSnapshot(_))
+        ));
+
+        let mut errored = session("sess-done", &[TGPState::Errored]);
+        errored.timeout_at = Some(errored.created_at + 30);
+        let err = TGPSession::from_snapshot(&errored.to_snapshot()).unwrap_err();
+        assert!(err.to_string().contains("terminal state Errored"));
+
+        assert!(TGPSession::from_snapshot(r#"{"session_id":"x"}"#).is_err());
+    }
+
+    #[test]
+    fn test_store_round_trip_and_timeouts() {
+        let store = temp_store("round-trip");
+        assert!(store.load().unwrap().sessions.is_empty());
+
+        let live = session("sess-live", &[TGPState::QuerySent, TGPState::OfferReceived]);
+        let mut stale = session("sess-stale", &[TGPState::QuerySent]);
+        // Saved two minutes ago with a 30 second timeout
+        stale.created_at -= 120;
+        stale.updated_at = stale.created_at;
+        stale.timeout_at = Some(stale.created_at + 30);
+        let done = session("sess-done", &[TGPState::QuerySent, TGPState::Cancelled]);
+
+        let sessions: HashMap<_, _> = [live, stale, done]
+            .into_iter()
+            .map(|s| (s.session_id.clone(), s))
+            .collect();
+        store.save(&sessions).unwrap();
+
+        let restored = store.load().unwrap();
+        assert_eq!(restored.sessions, sessions);
+        assert_eq!(restored.timed_out, vec!["sess-stale".to_string()]);
+
+        fs::remove_file(store.path()).unwrap();
+    }
+
+    #[test]
+    fn test_store_rejects_mismatched_keys() {
+        let store = temp_store("mismatch");
+        let sessions = HashMap::from([("sess-a".to_string(), session("sess-b", &[]))]);
+        assert!(store.save(&sessions).is_err());
+
+        fs::write(store.path(), r#"{"sess-a": {"session_id": "sess-b", "state": "Idle",
+            "query_id": null, "offer_id": null, "created_at": 1, "updated_at": 1,
+            "timeout_at": null}}"#)
+            .unwrap();
+        assert!(matches!(store.load(), Err(TGPStateError::InvalidSnapshot(_))));
+
+        fs::remove_file(store.path()).unwrap();
+    }
+}
@@ -12,7 +12,10 @@
 //!
 //! - [`ZkProfile`] - §3.5: Buyer's ZK proof preference
 //! - [`EconomicEnvelope`] - §3.6: Economic constraints for offers
+//! - [`RoundingMode`] - How fractional fees are rounded
 //! - [`SettleSource`] - §3.7: Settlement reporter identity
+//! - [`SettleOutcome`] - How the escrow behind a SETTLE terminated
+//! - [`RouteOption`] - One alternative settlement path in an OFFER
 //!
 //! # Examples
 //!
@@ -24,6 +27,7 @@
 //! let source = SettleSource::BuyerNotify;
 //! ```
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 // ============================================================================
@@ -118,6 +122,42 @@ impl ZkProfile {
         matches!(self, ZkProfile::Required)
     }
 
+    /// Resolve whether escrow is used, given the buyer's profile and the
+    /// controller's policy
+    ///
+    /// A buyer who requires escrow always gets it; a buyer who defers gets
+    /// whatever the controller's policy says. Only a buyer refusing escrow
+    /// against a controller requiring it cannot be reconciled.
+    ///
+    /// | Buyer      | `controller_requires` | Result           |
+    /// |------------|-----------------------|------------------|
+    /// | `None`     | `false`               | `Ok(false)`      |
+    /// | `None`     | `true`                | `Err` (conflict) |
+    /// | `Optional` | `false`               | `Ok(false)`      |
+    /// | `Optional` | `true`                | `Ok(true)`       |
+    /// | `Required` | `false`               | `Ok(true)`       |
+    /// | `Required` | `true`                | `Ok(true)`       |
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use tbc_core::tgp::types::ZkProfile;
+    /// assert_eq!(ZkProfile::resolve(ZkProfile::Required, false), Ok(true));
+    /// assert_eq!(ZkProfile::resolve(ZkProfile::Optional, false), Ok(false));
+    /// assert!(ZkProfile::resolve(ZkProfile::None, true).is_err());
+    /// ```
+    pub fn resolve(buyer: ZkProfile, controller_requires: bool) -> Result<bool, String> {
+        match (buyer, controller_requires) {
+            (ZkProfile::None, true) => Err(
+                "zk_profile conflict: buyer refuses escrow but controller policy requires it"
+                    .to_string(),
+            ),
+            (ZkProfile::None, false) => Ok(false),
+            (ZkProfile::Optional, requires) => Ok(requires),
+            (ZkProfile::Required, _) => Ok(true),
+        }
+    }
+
     /// Get a human-readable description of this profile
     pub fn description(&self) -> &'static str {
         match self {
@@ -144,6 +184,26 @@ impl std::fmt::Display for ZkProfile {
     }
 }
 
+// ============================================================================
+// RoundingMode Enumeration
+// ============================================================================
+
+/// How a fee that is not a whole number of base units is rounded
+///
+/// Used by [`EconomicEnvelope::calculate_fee_with_rounding`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RoundingMode {
+    /// Round down (truncate); never over-collects
+    #[default]
+    Floor,
+
+    /// Round up; never under-collects
+    Ceil,
+
+    /// Round to the nearest unit, halves rounding up
+    Nearest,
+}
+
 // ============================================================================
 // EconomicEnvelope Structure (§3.6)
 // ============================================================================
@@ -299,20 +359,190 @@ impl EconomicEnvelope {
     /// assert_eq!(max_fee, 5_000); // 0.005 USDC = 5000 base units
     /// ```
     pub fn calculate_max_fee(&self, amount: u64) -> u64 {
-        ((amount as u128 * self.max_fees_bps as u128) / 10000) as u64
+        self.calculate_fee_with_rounding(amount, RoundingMode::Floor)
     }
 
-    /// Check if the envelope has expired (requires current time)
+    /// Calculate the maximum fee for a given amount, rounding the
+    /// fractional base unit with `mode`
     ///
-    /// Note: This is a simple string comparison. For production use,
-    /// parse with chrono and compare timestamps.
-    pub fn is_expired(&self, current_time_rfc3339: &str) -> bool {
-        if let Some(ref expiry) = self.expiry {
-            current_time_rfc3339 > expiry
-        } else {
-            false
+    /// The product is computed in `u128`, so it cannot overflow; a fee above
+    /// `u64::MAX` (only possible with `max_fees_bps` over 10000) saturates.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use tbc_core::tgp::types::{EconomicEnvelope, RoundingMode};
+    /// let envelope = EconomicEnvelope::new(333); // 3.33% of 1000 = 33.3
+    /// assert_eq!(envelope.calculate_fee_with_rounding(1000, RoundingMode::Floor), 33);
+    /// assert_eq!(envelope.calculate_fee_with_rounding(1000, RoundingMode::Ceil), 34);
+    /// assert_eq!(envelope.calculate_fee_with_rounding(1000, RoundingMode::Nearest), 33);
+    /// ```
+    pub fn calculate_fee_with_rounding(&self, amount: u64, mode: RoundingMode) -> u64 {
+        const BPS_DENOMINATOR: u128 = 10_000;
+
+        let product = amount as u128 * self.max_fees_bps as u128;
+        let fee = match mode {
+            RoundingMode::Floor => product / BPS_DENOMINATOR,
+            RoundingMode::Ceil => product.div_ceil(BPS_DENOMINATOR),
+            RoundingMode::Nearest => (product + BPS_DENOMINATOR / 2) / BPS_DENOMINATOR,
+        };
+        u64::try_from(fee).unwrap_or(u64::MAX)
+    }
+
+    /// Check if the envelope has expired at `current_time_rfc3339`
+    ///
+    /// Both timestamps are parsed as RFC3339 and compared as UTC instants,
+    /// so offsets and fractional seconds are honoured. An envelope without
+    /// an expiry never expires. Unparseable timestamps are an error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use tbc_core::tgp::types::EconomicEnvelope;
+    /// let envelope = EconomicEnvelope::with_expiry(50, "2025-01-01T00:00:00Z");
+    /// // 00:30 in +01:00 is still 23:30 UTC the day before
+    /// assert_eq!(envelope.is_expired("2025-01-01T00:30:00+01:00"), Ok(false));
+    /// assert_eq!(envelope.is_expired("2025-01-01T00:00:01Z"), Ok(true));
+    /// assert!(envelope.is_expired("tomorrow").is_err());
+    /// ```
+    pub fn is_expired(&self, current_time_rfc3339: &str) -> Result<bool, String> {
+        let Some(ref expiry) = self.expiry else {
+            return Ok(false);
+        };
+        let expiry = parse_rfc3339("expiry", expiry)?;
+        let now = parse_rfc3339("current time", current_time_rfc3339)?;
+        Ok(now > expiry)
+    }
+}
+
+/// Parse an RFC3339 timestamp into a UTC instant
+fn parse_rfc3339(field: &str, value: &str) -> Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|e| format!("{} is not a valid RFC3339 timestamp ({}): {}", field, e, value))
+}
+
+// ============================================================================
+// TimingSummary Structure (OFFER extension)
+// ============================================================================
+
+/// Escrow timeline carried in an OFFER
+///
+/// Copied from the CoreProver `PaymentProfile` the controller will use, so a
+/// buyer agent can tell how long its funds may be locked before committing.
+///
+/// # Examples
+///
+/// ```rust
+/// use tbc_core::tgp::types::TimingSummary;
+///
+/// let timing = TimingSummary::new(1800, 3600, 3600);
+/// assert!(timing.validate().is_ok());
+/// assert_eq!(timing.max_lock_secs(), 9000);
+///
+/// assert!(TimingSummary::new(1800, 0, 3600).validate().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TimingSummary {
+    /// Seconds the seller has to accept after the buyer commits
+    pub acceptance_window_secs: u64,
+
+    /// Seconds the seller has to fulfill after accepting
+    pub fulfillment_window_secs: u64,
+
+    /// Seconds the seller has to claim after fulfilling
+    pub claim_window_secs: u64,
+}
+
+impl TimingSummary {
+    /// Create a TimingSummary from the three escrow windows
+    pub fn new(
+        acceptance_window_secs: u64,
+        fulfillment_window_secs: u64,
+        claim_window_secs: u64,
+    ) -> Self {
+        Self {
+            acceptance_window_secs,
+            fulfillment_window_secs,
+            claim_window_secs,
+        }
+    }
+
+    /// Validate the timing summary
+    ///
+    /// Every window must be non-zero.
+    pub fn validate(&self) -> Result<(), String> {
+        for (secs, field) in [
+            (self.acceptance_window_secs, "acceptance_window_secs"),
+            (self.fulfillment_window_secs, "fulfillment_window_secs"),
+            (self.claim_window_secs, "claim_window_secs"),
+        ] {
+            if secs == 0 {
+                return Err(format!("timing.{} must be greater than zero", field));
+            }
+        }
+        Ok(())
+    }
+
+    /// Longest time funds can stay in escrow if every window runs out
+    pub fn max_lock_secs(&self) -> u64 {
+        self.acceptance_window_secs
+            .saturating_add(self.fulfillment_window_secs)
+            .saturating_add(self.claim_window_secs)
+    }
+}
+
+// ============================================================================
+// RouteOption Structure (OFFER extension)
+// ============================================================================
+
+/// One settlement path a controller can offer
+///
+/// An OFFER may list several routes so a buyer agent can pick between
+/// cheaper and faster settlement. Fees are quoted in basis points, the same
+/// unit as [`EconomicEnvelope::max_fees_bps`].
+///
+/// # Examples
+///
+/// ```rust
+/// use tbc_core::tgp::types::RouteOption;
+///
+/// let route = RouteOption::new(369, 30, 12)
+///     .with_coreprover("0x742d35Cc6634C0532925a3b844Bc9e7595f0bEbA");
+/// assert_eq!(route.estimated_fee_bps, 30);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RouteOption {
+    /// CoreProver escrow contract used on this route, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coreprover_contract: Option<String>,
+
+    /// Chain the settlement happens on
+    pub chain_id: u64,
+
+    /// Expected total fee in basis points
+    pub estimated_fee_bps: u32,
+
+    /// Expected seconds until settlement is final
+    pub estimated_latency_secs: u64,
+}
+
+impl RouteOption {
+    /// Create a route without a CoreProver contract
+    pub fn new(chain_id: u64, estimated_fee_bps: u32, estimated_latency_secs: u64) -> Self {
+        Self {
+            coreprover_contract: None,
+            chain_id,
+            estimated_fee_bps,
+            estimated_latency_secs,
         }
     }
+
+    /// Builder method to set the CoreProver contract
+    pub fn with_coreprover(mut self, contract: impl Into<String>) -> Self {
+        self.coreprover_contract = Some(contract.into());
+        self
+    }
 }
 
 // ============================================================================
@@ -419,6 +649,56 @@ impl std::fmt::Display for SettleSource {
     }
 }
 
+// ============================================================================
+// SettleOutcome Enumeration (extension)
+// ============================================================================
+
+/// How the escrow behind a SETTLE terminated
+///
+/// Extension to TGP-00: lets the buyer agent finalize its session for
+/// escrows that ended without payment to the seller.
+///
+/// # Examples
+///
+/// ```rust
+/// use tbc_core::tgp::types::SettleOutcome;
+///
+/// assert!(SettleOutcome::Claimed.is_success());
+/// assert!(!SettleOutcome::Refunded.is_success());
+///
+/// let json = serde_json::to_string(&SettleOutcome::Withdrawn).unwrap();
+/// assert_eq!(json, r#""withdrawn""#);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum SettleOutcome {
+    /// Seller claimed the escrowed payment
+    Claimed,
+
+    /// Seller refunded the buyer after fulfillment
+    Refunded,
+
+    /// Buyer withdrew before fulfillment
+    Withdrawn,
+}
+
+impl SettleOutcome {
+    /// Only a claim completes the purchase
+    pub fn is_success(&self) -> bool {
+        matches!(self, SettleOutcome::Claimed)
+    }
+}
+
+impl std::fmt::Display for SettleOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SettleOutcome::Claimed => write!(f, "claimed"),
+            SettleOutcome::Refunded => write!(f, "refunded"),
+            SettleOutcome::Withdrawn => write!(f, "withdrawn"),
+        }
+    }
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -454,6 +734,18 @@ mod tests {
         assert!(ZkProfile::Required.requires_escrow());
     }
 
+    #[test]
+    fn test_zk_profile_resolve_truth_table() {
+        assert_eq!(ZkProfile::resolve(ZkProfile::None, false), Ok(false));
+        assert_eq!(ZkProfile::resolve(ZkProfile::Optional, false), Ok(false));
+        assert_eq!(ZkProfile::resolve(ZkProfile::Optional, true), Ok(true));
+        assert_eq!(ZkProfile::resolve(ZkProfile::Required, false), Ok(true));
+        assert_eq!(ZkProfile::resolve(ZkProfile::Required, true), Ok(true));
+
+        let err = ZkProfile::resolve(ZkProfile::None, true).unwrap_err();
+        assert!(err.contains("conflict"));
+    }
+
     #[test]
     fn test_economic_envelope_validation() {
         let valid = EconomicEnvelope::new(50);
@@ -478,6 +770,61 @@ mod tests {
         assert_eq!(envelope.calculate_max_fee(100_000_000), 500_000);
     }
 
+    #[test]
+    fn test_fee_rounding_modes() {
+        use RoundingMode::*;
+
+        // (bps, amount, floor, ceil, nearest)
+        let cases = [
+            (333, 1000, 33, 34, 33), // 33.3
+            (555, 1000, 55, 56, 56), // 55.5: half rounds up
+            (50, 1999, 9, 10, 10),   // 9.995
+            (50, 2000, 10, 10, 10),  // exact
+            (1, 1, 0, 1, 0),         // 0.0001
+        ];
+        for (bps, amount, floor, ceil, nearest) in cases {
+            let envelope = EconomicEnvelope::new(bps);
+            assert_eq!(envelope.calculate_fee_with_rounding(amount, Floor), floor);
+            assert_eq!(envelope.calculate_fee_with_rounding(amount, Ceil), ceil);
+            assert_eq!(envelope.calculate_fee_with_rounding(amount, Nearest), nearest);
+            assert_eq!(envelope.calculate_max_fee(amount), floor);
+        }
+
+        // No overflow at the top of the u64 range
+        let full = EconomicEnvelope::new(10_000);
+        assert_eq!(full.calculate_fee_with_rounding(u64::MAX, Ceil), u64::MAX);
+        let over = EconomicEnvelope::new(20_000);
+        assert_eq!(over.calculate_fee_with_rounding(u64::MAX, Nearest), u64::MAX);
+    }
+
+    #[test]
+    fn test_economic_envelope_expiry_across_offsets() {
+        let envelope = EconomicEnvelope::with_expiry(50, "2025-01-01T00:00:00+01:00");
+
+        // Same instant as the expiry, written in UTC: not yet expired
+        assert_eq!(envelope.is_expired("2024-12-31T23:00:00Z"), Ok(false));
+        assert_eq!(envelope.is_expired("2024-12-31T18:00:00-05:00"), Ok(false));
+
+        // Sorts before the expiry as a string, but is half an hour later
+        assert_eq!(envelope.is_expired("2024-12-31T23:30:00Z"), Ok(true));
+
+        assert_eq!(EconomicEnvelope::new(50).is_expired("not-a-date"), Ok(false));
+        assert!(envelope.is_expired("2025-01-01 00:00:00").is_err());
+        assert!(EconomicEnvelope::with_expiry(50, "soon")
+            .is_expired("2025-01-01T00:00:00Z")
+            .is_err());
+    }
+
+    #[test]
+    fn test_economic_envelope_expiry_fractional_seconds() {
+        let envelope = EconomicEnvelope::with_expiry(50, "2025-01-01T00:00:00.500Z");
+
+        assert_eq!(envelope.is_expired("2025-01-01T00:00:00.499Z"), Ok(false));
+        assert_eq!(envelope.is_expired("2025-01-01T00:00:00.5Z"), Ok(false));
+        assert_eq!(envelope.is_expired("2025-01-01T00:00:00.501Z"), Ok(true));
+        assert_eq!(envelope.is_expired("2025-01-01T01:00:00.500000001+01:00"), Ok(true));
+    }
+
     #[test]
     fn test_settle_source_serialization() {
         assert_eq!(
@@ -15,6 +15,7 @@
 //! - [`validate_positive_amount`] - Check that amounts are greater than zero
 //! - [`validate_address`] - Check Ethereum address format
 //! - [`validate_transaction_hash`] - Check transaction hash format
+//! - [`validate_transaction_hash_for_chain`] - Check hash length for a chain
 //! - [`validate_id_format`] - Check message ID format (optional)
 //!
 //! # Examples
@@ -201,6 +202,88 @@ pub fn validate_transaction_hash(hash: &str, field_name: &str) -> Result<(), Str
     Ok(())
 }
 
+/// Chain classification used for chain-specific format rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainFamily {
+    /// EVM chains: 0x-prefixed 32-byte transaction hashes
+    Evm,
+    /// Other chains, with their transaction hash length in bytes
+    Other { tx_hash_bytes: usize },
+}
+
+impl ChainFamily {
+    /// Classify a chain ID
+    ///
+    /// All chain IDs are currently EVM. Non-EVM chains are added here
+    /// with their transaction hash length.
+    pub fn classify(_chain_id: u64) -> Self {
+        ChainFamily::Evm
+    }
+
+    /// Expected transaction hash length in bytes
+    pub fn tx_hash_bytes(&self) -> usize {
+        match self {
+            ChainFamily::Evm => 32,
+            ChainFamily::Other { tx_hash_bytes } => *tx_hash_bytes,
+        }
+    }
+}
+
+/// Validate a transaction hash against the length expected for a chain
+///
+/// Same rules as [`validate_transaction_hash`] (0x prefix, hex only), but
+/// the length comes from [`ChainFamily::classify`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use tbc_core::tgp::validation::validate_transaction_hash_for_chain;
+/// let valid = "0x9f2d8e7c3b1a5f4e2d1c0b9a8f7e6d5c4b3a2f1e0d9c8b7a6f5e4d3c2b1a0f9e";
+/// assert!(validate_transaction_hash_for_chain(valid, 8453, "layer8_tx").is_ok());
+/// ```
+pub fn validate_transaction_hash_for_chain(
+    hash: &str,
+    chain_id: u64,
+    field_name: &str,
+) -> Result<(), String> {
+    validate_transaction_hash_for_family(hash, ChainFamily::classify(chain_id), field_name)
+}
+
+/// Validate a transaction hash against a chain family's expected length
+pub fn validate_transaction_hash_for_family(
+    hash: &str,
+    family: ChainFamily,
+    field_name: &str,
+) -> Result<(), String> {
+    let hex_part = hash.strip_prefix("0x").ok_or_else(|| {
+        format!(
+            "{} must be a valid transaction hash starting with 0x: {}",
+            field_name, hash
+        )
+    })?;
+
+    let bytes = family.tx_hash_bytes();
+    if hex_part.len() != bytes * 2 {
+        return Err(format!(
+            "{} must be {} characters long (0x + {} hex chars) for {:?}: {}",
+            field_name,
+            bytes * 2 + 2,
+            bytes * 2,
+            family,
+            hash
+        ));
+    }
+
+    if !hex_part.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!(
+            "{} must contain only hexadecimal characters after 0x: {}",
+            field_name, hash
+        ));
+    }
+
+    Ok(())
+}
+
 // ============================================================================
 // Optional Advanced Validation
 // ============================================================================
@@ -435,6 +518,22 @@ mod tests {
         assert!(validate_transaction_hash("9f2d8e7c3b1a5f4e2d1c0b9a8f7e6d5c4b3a2f1e0d9c8b7a6f5e4d3c2b1a0f9e", "tx").is_err()); // No 0x
     }
 
+    #[test]
+    fn test_validate_transaction_hash_for_chain() {
+        let evm = "0x9f2d8e7c3b1a5f4e2d1c0b9a8f7e6d5c4b3a2f1e0d9c8b7a6f5e4d3c2b1a0f9e";
+        assert_eq!(ChainFamily::classify(8453), ChainFamily::Evm);
+        assert!(validate_transaction_hash_for_chain(evm, 8453, "tx").is_ok());
+        assert!(validate_transaction_hash_for_chain("0x123", 8453, "tx").is_err());
+        assert!(validate_transaction_hash_for_chain(&evm[2..], 8453, "tx").is_err());
+
+        // Hypothetical chain with 64-byte transaction hashes
+        let wide = ChainFamily::Other { tx_hash_bytes: 64 };
+        let wide_hash = format!("0x{}", "ab".repeat(64));
+        assert!(validate_transaction_hash_for_family(&wide_hash, wide, "tx").is_ok());
+        assert!(validate_transaction_hash_for_family(evm, wide, "tx").is_err());
+        assert!(validate_transaction_hash_for_family(&wide_hash, ChainFamily::Evm, "tx").is_err());
+    }
+
     #[test]
     fn test_validate_id_format() {
         // Valid IDs with prefix
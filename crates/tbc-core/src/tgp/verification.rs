@@ -0,0 +1,131 @@
+//# TGP Settlement Verification
+
+//**Destination Path:** `crates/tbc-core/src/tgp/verification.rs`
+
+//**Implementation:** M1 - TGP Message Parsing & Basic Routing
+
+//! Gating SETTLE messages on their source's trust
+//!
+//! A SETTLE from any source other than the controller's own watcher is a
+//! claim that still has to be checked (TGP-00 §3.7). A [`SettlementVerifier`]
+//! decides whether the controller may act on a SETTLE; [`ChainVerifier`] is
+//! the default, which requires sources that
+//! [`requires_verification`](super::types::SettleSource::requires_verification)
+//! to name a well-formed `layer8_tx` the controller can look up.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use tbc_core::tgp::messages::SettleMessage;
+//! use tbc_core::tgp::types::SettleSource;
+//! use tbc_core::tgp::verification::{ChainVerifier, SettlementVerifier};
+//!
+//! let tx = "0x9f2d8e7c3b1a5f4e2d1c0b9a8f7e6d5c4b3a2f1e0d9c8b7a6f5e4d3c2b1a0f9e";
+//! let reported = SettleMessage::new("settle-1", "offer-1", true, SettleSource::BuyerNotify);
+//!
+//! assert!(ChainVerifier.verify(&reported).is_err());
+//! assert!(ChainVerifier.verify(&reported.with_tx(tx)).is_ok());
+//! ```
+
+use super::messages::SettleMessage;
+use super::validation::{validate_transaction_hash, validate_transaction_hash_for_chain};
+
+// ============================================================================
+// SettlementVerifier
+// ============================================================================
+
+/// Decides whether the controller may act on a SETTLE
+pub trait SettlementVerifier: Send + Sync {
+    /// `Ok` when `settle` is verified (or its source needs no verification)
+    fn verify(&self, settle: &SettleMessage) -> Result<(), String>;
+}
+
+// ============================================================================
+// ChainVerifier
+// ============================================================================
+
+/// Default [`SettlementVerifier`]
+///
+/// # Verification Rules (per TGP-00 §3.7)
+///
+/// - Sources that require verification (`BuyerNotify`, `CoreproverIndexer`)
+///   must carry `layer8_tx`
+/// - `ControllerWatcher` may omit `layer8_tx`
+/// - A present `layer8_tx` must be a well-formed hash for `origin_chain_id`
+///   (EVM format when unset)
+///
+/// Only the format is checked; confirming the transaction on chain is left
+/// to the controller's watcher.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChainVerifier;
+
+impl SettlementVerifier for ChainVerifier {
+    fn verify(&self, settle: &SettleMessage) -> Result<(), String> {
+        let Some(tx) = &settle.layer8_tx else {
+            if settle.source.requires_verification() {
+                return Err(format!(
+                    "SETTLE {} from {} requires layer8_tx for verification",
+                    settle.id, settle.source
+                ));
+            }
+            return Ok(());
+        };
+
+        match settle.origin_chain_id {
+            Some(chain_id) => validate_transaction_hash_for_chain(tx, chain_id, "layer8_tx"),
+            None => validate_transaction_hash(tx, "layer8_tx"),
+        }
+        .map_err(|e| format!("SETTLE {} from {}: {}", settle.id, settle.source, e))
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tgp::types::SettleSource;
+
+    const TX: &str = "0x9f2d8e7c3b1a5f4e2d1c0b9a8f7e6d5c4b3a2f1e0d9c8b7a6f5e4d3c2b1a0f9e";
+
+    fn settle(source: SettleSource) -> SettleMessage {
+        SettleMessage::new("settle-1", "offer-1", true, source)
+    }
+
+    #[test]
+    fn test_buyer_notify_requires_tx() {
+        let err = ChainVerifier.verify(&settle(SettleSource::BuyerNotify)).unwrap_err();
+        assert!(err.contains("buyer-notify requires layer8_tx"));
+
+        assert!(ChainVerifier.verify(&settle(SettleSource::BuyerNotify).with_tx(TX)).is_ok());
+    }
+
+    #[test]
+    fn test_indexer_requires_tx() {
+        let err = ChainVerifier.verify(&settle(SettleSource::CoreproverIndexer)).unwrap_err();
+        assert!(err.contains("coreprover-indexer requires layer8_tx"));
+
+        let with_tx = settle(SettleSource::CoreproverIndexer).with_tx(TX).with_origin_chain(8453);
+        assert!(ChainVerifier.verify(&with_tx).is_ok());
+    }
+
+    #[test]
+    fn test_controller_watcher_passes_without_tx() {
+        assert!(ChainVerifier.verify(&settle(SettleSource::ControllerWatcher)).is_ok());
+        assert!(ChainVerifier.verify(&settle(SettleSource::ControllerWatcher).with_tx(TX)).is_ok());
+    }
+
+    #[test]
+    fn test_malformed_tx_is_rejected_for_every_source() {
+        for source in [
+            SettleSource::BuyerNotify,
+            SettleSource::CoreproverIndexer,
+            SettleSource::ControllerWatcher,
+        ] {
+            let err = ChainVerifier.verify(&settle(source).with_tx("0x123")).unwrap_err();
+            assert!(err.contains("layer8_tx"), "{}", err);
+        }
+    }
+}
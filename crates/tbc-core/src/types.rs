@@ -19,6 +19,9 @@ pub struct Order {
     pub seller: Address,
     pub amount: u128,
     pub created_at: u64,
+    /// What `amount` is denominated in; `None` for legacy single-asset orders
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset: Option<Asset>,
 }
 
 /// Route information
@@ -27,4 +30,204 @@ pub struct Route {
     pub order_id: OrderId,
     pub seller_address: Address,
     pub agent_id: String,
-}
\ No newline at end of file
+}
+
+/// Decimals assumed for a bare symbol (EVM native convention)
+pub const DEFAULT_ASSET_DECIMALS: u8 = 18;
+
+/// An asset amounts are denominated in
+///
+/// Serializes as the bare symbol string (`"ETH"`) when `chain_id`,
+/// `contract` and `decimals` are all default, so payloads from before
+/// this type existed still parse, and as an object otherwise.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "AssetRepr", into = "AssetRepr")]
+pub struct Asset {
+    pub symbol: String,
+    /// 0 = chain not specified
+    pub chain_id: u64,
+    /// Token contract; `None` for the chain's native asset
+    pub contract: Option<String>,
+    pub decimals: u8,
+}
+
+impl Asset {
+    pub fn new(symbol: impl Into<String>, chain_id: u64, decimals: u8) -> Self {
+        Self {
+            symbol: symbol.into(),
+            chain_id,
+            contract: None,
+            decimals,
+        }
+    }
+
+    pub fn with_contract(mut self, contract: impl Into<String>) -> Self {
+        self.contract = Some(contract.into());
+        self
+    }
+
+    pub fn is_native(&self) -> bool {
+        self.contract.is_none()
+    }
+
+    /// Whether this asset carries nothing beyond its symbol
+    pub fn is_bare(&self) -> bool {
+        self.chain_id == 0 && self.contract.is_none() && self.decimals == DEFAULT_ASSET_DECIMALS
+    }
+
+    /// Format a smallest-unit amount, e.g. 1_500_000 with 6 decimals is
+    /// "1.5". Trailing fractional zeros are dropped.
+    pub fn human_amount(&self, amount: u64) -> String {
+        let decimals = self.decimals as usize;
+        if decimals == 0 {
+            return amount.to_string();
+        }
+
+        let digits = format!("{:0>width$}", amount, width = decimals + 1);
+        let (whole, frac) = digits.split_at(digits.len() - decimals);
+        let frac = frac.trim_end_matches('0');
+        if frac.is_empty() {
+            whole.to_string()
+        } else {
+            format!("{}.{}", whole, frac)
+        }
+    }
+}
+
+impl From<&str> for Asset {
+    /// A bare symbol: no chain, no contract, default decimals
+    fn from(symbol: &str) -> Self {
+        Self::new(symbol, 0, DEFAULT_ASSET_DECIMALS)
+    }
+}
+
+impl std::fmt::Display for Asset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.symbol)
+    }
+}
+
+/// Wire form of [`Asset`]: a bare symbol or the full object
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum AssetRepr {
+    Symbol(String),
+    Full {
+        symbol: String,
+        #[serde(default)]
+        chain_id: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        contract: Option<String>,
+        #[serde(default = "default_asset_decimals")]
+        decimals: u8,
+    },
+}
+
+fn default_asset_decimals() -> u8 {
+    DEFAULT_ASSET_DECIMALS
+}
+
+impl From<AssetRepr> for Asset {
+    fn from(repr: AssetRepr) -> Self {
+        match repr {
+            AssetRepr::Symbol(symbol) => Asset::from(symbol.as_str()),
+            AssetRepr::Full {
+                symbol,
+                chain_id,
+                contract,
+                decimals,
+            } => Asset {
+                symbol,
+                chain_id,
+                contract,
+                decimals,
+            },
+        }
+    }
+}
+
+impl From<Asset> for AssetRepr {
+    fn from(asset: Asset) -> Self {
+        if asset.is_bare() {
+            return AssetRepr::Symbol(asset.symbol);
+        }
+        AssetRepr::Full {
+            symbol: asset.symbol,
+            chain_id: asset.chain_id,
+            contract: asset.contract,
+            decimals: asset.decimals,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USDC_BASE: &str = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913";
+
+    #[test]
+    fn test_bare_symbol_round_trip() {
+        let asset = Asset::from("ETH");
+        assert!(asset.is_bare());
+        assert_eq!(asset.decimals, DEFAULT_ASSET_DECIMALS);
+
+        let json = serde_json::to_string(&asset).unwrap();
+        assert_eq!(json, r#""ETH""#);
+        assert_eq!(serde_json::from_str::<Asset>(&json).unwrap(), asset);
+    }
+
+    #[test]
+    fn test_token_round_trip() {
+        let usdc = Asset::new("USDC", 8453, 6).with_contract(USDC_BASE);
+        assert!(!usdc.is_native());
+
+        let json = serde_json::to_string(&usdc).unwrap();
+        assert!(
+            json.starts_with(r#"{"symbol":"USDC","chain_id":8453"#),
+            "{}",
+            json
+        );
+        assert_eq!(serde_json::from_str::<Asset>(&json).unwrap(), usdc);
+
+        // Native asset on a known chain keeps its chain id
+        let eth = Asset::new("ETH", 1, DEFAULT_ASSET_DECIMALS);
+        let json = serde_json::to_string(&eth).unwrap();
+        assert_eq!(json, r#"{"symbol":"ETH","chain_id":1,"decimals":18}"#);
+        assert_eq!(serde_json::from_str::<Asset>(&json).unwrap(), eth);
+    }
+
+    #[test]
+    fn test_partial_object_uses_defaults() {
+        let asset: Asset = serde_json::from_str(r#"{"symbol":"DAI"}"#).unwrap();
+        assert_eq!(asset, Asset::from("DAI"));
+    }
+
+    #[test]
+    fn test_order_asset_is_optional() {
+        let legacy = r#"{"id":"o-1","buyer":"0xb","seller":"0xs","amount":5,"created_at":0}"#;
+        let order: Order = serde_json::from_str(legacy).unwrap();
+        assert!(order.asset.is_none());
+        assert_eq!(serde_json::to_string(&order).unwrap(), legacy);
+
+        let with_asset = legacy.replace("}", r#","asset":"USDC"}"#);
+        let order: Order = serde_json::from_str(&with_asset).unwrap();
+        assert_eq!(order.asset, Some(Asset::from("USDC")));
+        assert_eq!(serde_json::to_string(&order).unwrap(), with_asset);
+    }
+
+    #[test]
+    fn test_human_amount() {
+        let usdc = Asset::new("USDC", 8453, 6);
+        assert_eq!(usdc.human_amount(1_500_000), "1.5");
+        assert_eq!(usdc.human_amount(2_000_000), "2");
+        assert_eq!(usdc.human_amount(1), "0.000001");
+        assert_eq!(usdc.human_amount(0), "0");
+
+        assert_eq!(
+            Asset::from("ETH").human_amount(u64::MAX),
+            "18.446744073709551615"
+        );
+        assert_eq!(Asset::new("SAT", 0, 0).human_amount(42), "42");
+    }
+}
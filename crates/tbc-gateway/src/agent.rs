@@ -1,6 +1,17 @@
 //! Agent coordination
+//!
+//! An [`Agent`] acts for a buyer: it sends a QUERY to its controller over a
+//! [`ControllerTransport`], checks the OFFER against its own
+//! [`EconomicEnvelope`] policy, and accepts or cancels, moving its
+//! [`TGPSession`] through the TGP state machine as it goes.
 
 use anyhow::Result;
+use async_trait::async_trait;
+use thiserror::Error;
+
+use tbc_core::tgp::messages::{CancelMessage, OfferMessage, QueryMessage, TGPMessage};
+use tbc_core::tgp::state::{TGPSession, TGPState, TGPStateError};
+use tbc_core::tgp::types::EconomicEnvelope;
 
 /// Agent identifier
 pub type AgentId = String;
@@ -13,28 +24,287 @@ pub enum AgentStatus {
     Busy,
 }
 
+/// Link between an agent and its controller
+#[async_trait]
+pub trait ControllerTransport: Send + Sync {
+    /// Deliver `message`; a QUERY is answered with an OFFER or ERROR,
+    /// a CANCEL with nothing
+    async fn send(&self, message: TGPMessage) -> std::result::Result<Option<TGPMessage>, String>;
+}
+
+/// Why [`Agent::run_once`] could not finish a session
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum AgentError {
+    #[error("transport failed: {0}")]
+    Transport(String),
+
+    #[error("controller returned ERROR {code}: {message}")]
+    Controller { code: String, message: String },
+
+    #[error("unexpected reply to QUERY {0}")]
+    UnexpectedReply(String),
+
+    #[error("invalid OFFER: {0}")]
+    InvalidOffer(String),
+
+    #[error(transparent)]
+    State(#[from] TGPStateError),
+}
+
 /// Agent coordinator
 pub struct Agent {
     pub id: AgentId,
     pub status: AgentStatus,
+    /// Most fees the buyer accepts in an OFFER
+    pub policy: EconomicEnvelope,
+    transport: Box<dyn ControllerTransport>,
+    session: Option<TGPSession>,
 }
 
 impl Agent {
-    pub fn new(id: AgentId) -> Self {
+    pub fn new(
+        id: AgentId,
+        transport: impl ControllerTransport + 'static,
+        policy: EconomicEnvelope,
+    ) -> Self {
         Self {
             id,
             status: AgentStatus::Active,
+            policy,
+            transport: Box::new(transport),
+            session: None,
         }
     }
-    
+
     /// Check if agent is available
     pub fn is_available(&self) -> bool {
         matches!(self.status, AgentStatus::Active)
     }
-    
+
     /// Assign order to agent
     pub async fn assign_order(&mut self, _order_id: &str) -> Result<()> {
         self.status = AgentStatus::Busy;
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Session of the latest `run_once`, if any
+    pub fn session(&self) -> Option<&TGPSession> {
+        self.session.as_ref()
+    }
+
+    /// Whether `offer` fits the agent's fee policy
+    pub fn accepts(&self, offer: &OfferMessage) -> bool {
+        offer.economic_envelope.max_fees_bps <= self.policy.max_fees_bps
+    }
+
+    /// Run one buyer session for `query`: QUERY → OFFER → accept or cancel
+    ///
+    /// Returns `AcceptSent` when the OFFER fits the policy and `Cancelled`
+    /// (after sending a CANCEL) when it does not. Transport failures,
+    /// controller ERRORs and malformed OFFERs leave the session `Errored`.
+    pub async fn run_once(
+        &mut self,
+        query: QueryMessage,
+    ) -> std::result::Result<TGPState, AgentError> {
+        let mut session = TGPSession::new(format!("sess-{}", query.id));
+        session.query_id = Some(query.id.clone());
+        session.asset = Some(query.asset.clone());
+        session.amount = Some(query.amount);
+        session.transition(TGPState::QuerySent)?;
+
+        self.status = AgentStatus::Busy;
+        let outcome = self.negotiate(&mut session, query).await;
+        self.status = AgentStatus::Active;
+
+        if outcome.is_err() && !session.state.is_terminal() {
+            session.force_error();
+        }
+        self.session = Some(session);
+        outcome
+    }
+
+    async fn negotiate(
+        &self,
+        session: &mut TGPSession,
+        query: QueryMessage,
+    ) -> std::result::Result<TGPState, AgentError> {
+        let query_id = query.id.clone();
+        let reply = self
+            .transport
+            .send(TGPMessage::Query(query.clone()))
+            .await
+            .map_err(AgentError::Transport)?;
+
+        let offer = match reply {
+            Some(TGPMessage::Offer(offer)) => offer,
+            Some(TGPMessage::Error(err)) => {
+                return Err(AgentError::Controller {
+                    code: err.code,
+                    message: err.message,
+                })
+            }
+            _ => return Err(AgentError::UnexpectedReply(query_id)),
+        };
+
+        offer.validate().map_err(AgentError::InvalidOffer)?;
+        offer
+            .validate_echo(&query)
+            .map_err(AgentError::InvalidOffer)?;
+        session.offer_id = Some(offer.id.clone());
+        session.transition(TGPState::OfferReceived)?;
+
+        if self.accepts(&offer) {
+            session.transition(TGPState::AcceptSent)?;
+            return Ok(TGPState::AcceptSent);
+        }
+
+        let reason = format!(
+            "OFFER {} fees {} bps exceed policy {} bps",
+            offer.id, offer.economic_envelope.max_fees_bps, self.policy.max_fees_bps
+        );
+        tracing::info!(agent = %self.id, "rejecting {}", reason);
+        // The controller correlates by the session it put on the OFFER
+        let session_id = offer.session_id.as_deref().unwrap_or(&session.session_id);
+        let cancel =
+            CancelMessage::new(format!("cancel-{}", query_id), session_id).with_reason(reason);
+        self.transport
+            .send(TGPMessage::Cancel(cancel))
+            .await
+            .map_err(AgentError::Transport)?;
+        session.transition(TGPState::Cancelled)?;
+        Ok(TGPState::Cancelled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tbc_core::tgp::messages::{error_codes, ErrorMessage};
+    use tbc_core::tgp::types::ZkProfile;
+
+    /// Controller that answers every QUERY with a canned reply and records
+    /// what it was sent
+    #[derive(Clone)]
+    struct MockController {
+        reply: TGPMessage,
+        sent: Arc<Mutex<Vec<TGPMessage>>>,
+    }
+
+    impl MockController {
+        fn new(reply: TGPMessage) -> Self {
+            Self {
+                reply,
+                sent: Arc::new(Mutex::new(Vec::new())),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ControllerTransport for MockController {
+        async fn send(
+            &self,
+            message: TGPMessage,
+        ) -> std::result::Result<Option<TGPMessage>, String> {
+            let is_query = matches!(message, TGPMessage::Query(_));
+            self.sent.lock().unwrap().push(message);
+            Ok(is_query.then(|| self.reply.clone()))
+        }
+    }
+
+    fn query() -> QueryMessage {
+        QueryMessage::new(
+            "q-1",
+            "buyer://alice",
+            "seller://bob",
+            "USDC",
+            1_000_000,
+            ZkProfile::Optional,
+        )
+    }
+
+    fn offer(max_fees_bps: u32) -> TGPMessage {
+        TGPMessage::Offer(OfferMessage::new(
+            "offer-q-1",
+            "q-1",
+            "USDC",
+            1_000_000,
+            false,
+            EconomicEnvelope::new(max_fees_bps),
+        ))
+    }
+
+    fn agent(controller: &MockController) -> Agent {
+        Agent::new(
+            "agent-001".into(),
+            controller.clone(),
+            EconomicEnvelope::new(50),
+        )
+    }
+
+    #[tokio::test]
+    async fn accepts_offer_within_envelope() {
+        let controller = MockController::new(offer(30));
+        let mut agent = agent(&controller);
+
+        assert_eq!(agent.run_once(query()).await, Ok(TGPState::AcceptSent));
+
+        let session = agent.session().unwrap();
+        assert_eq!(session.state, TGPState::AcceptSent);
+        assert_eq!(session.query_id.as_deref(), Some("q-1"));
+        assert_eq!(session.offer_id.as_deref(), Some("offer-q-1"));
+        assert!(agent.is_available());
+        assert_eq!(controller.sent.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn rejects_offer_exceeding_max_fees() {
+        let controller = MockController::new(offer(75));
+        let mut agent = agent(&controller);
+
+        assert_eq!(agent.run_once(query()).await, Ok(TGPState::Cancelled));
+        assert_eq!(agent.session().unwrap().state, TGPState::Cancelled);
+
+        let sent = controller.sent.lock().unwrap();
+        let TGPMessage::Cancel(cancel) = &sent[1] else {
+            panic!("expected CANCEL, got {:?}", sent[1]);
+        };
+        assert_eq!(cancel.session_id, "sess-q-1");
+        assert!(cancel
+            .reason
+            .as_deref()
+            .unwrap()
+            .contains("75 bps exceed policy 50 bps"));
+    }
+
+    #[tokio::test]
+    async fn cancel_echoes_controller_session() {
+        let TGPMessage::Offer(rejected) = offer(75) else {
+            unreachable!()
+        };
+        let controller =
+            MockController::new(TGPMessage::Offer(rejected.with_session("sess-ctrl-7")));
+        let mut agent = agent(&controller);
+
+        assert_eq!(agent.run_once(query()).await, Ok(TGPState::Cancelled));
+
+        let sent = controller.sent.lock().unwrap();
+        let TGPMessage::Cancel(cancel) = &sent[1] else {
+            panic!("expected CANCEL, got {:?}", sent[1]);
+        };
+        assert_eq!(cancel.session_id, "sess-ctrl-7");
+    }
+
+    #[tokio::test]
+    async fn controller_error_errors_the_session() {
+        let error = ErrorMessage::new("err-q-1", error_codes::POLICY_VIOLATION, "amount too small");
+        let controller = MockController::new(TGPMessage::Error(error));
+        let mut agent = agent(&controller);
+
+        let err = agent.run_once(query()).await.unwrap_err();
+        assert!(
+            matches!(err, AgentError::Controller { ref code, .. } if code == "POLICY_VIOLATION")
+        );
+        assert_eq!(agent.session().unwrap().state, TGPState::Errored);
+    }
+}
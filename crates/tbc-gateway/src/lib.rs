@@ -1,7 +1,9 @@
 //! TBC Gateway - TGP Implementation
 
 pub mod router;
+pub mod routing;
 pub mod agent;
+pub mod x402;
 
 pub use router::Router;
 pub use agent::Agent;
@@ -1,16 +1,194 @@
 //! Order routing logic
 
+use std::collections::HashMap;
+
+use tbc_core::tgp::messages::{
+    error_codes, ErrorMessage, OfferMessage, QueryMessage, SettleMessage, TGPMessage,
+};
+use tbc_core::tgp::types::{EconomicEnvelope, SettleOutcome, SettleSource};
+use tbc_core::tgp::verification::{ChainVerifier, SettlementVerifier};
 use tbc_core::{Order, Route};
-use anyhow::Result;
+
+use crate::routing::{LowestFeeStrategy, RouteCandidate, RouteProvider, RoutingStrategy};
+use anyhow::{anyhow, Result};
+
+/// Default fee ceiling advertised in OFFER envelopes (0.5%)
+pub const DEFAULT_MAX_FEES_BPS: u32 = 50;
+
+/// Fee policy applied when answering QUERYs
+#[derive(Debug, Clone)]
+pub struct FeeSchedule {
+    /// Fee ceiling advertised in OFFER envelopes
+    pub max_fees_bps: u32,
+    /// Fee the controller actually charges; must not exceed `max_fees_bps`
+    pub fee_bps: u32,
+    /// Smallest fee (in asset base units) worth settling, per asset.
+    /// Assets without an entry have no floor.
+    pub min_absolute_fee: HashMap<String, u64>,
+}
+
+impl FeeSchedule {
+    pub fn new(max_fees_bps: u32) -> Self {
+        Self {
+            max_fees_bps,
+            fee_bps: max_fees_bps,
+            min_absolute_fee: HashMap::new(),
+        }
+    }
+
+    pub fn with_fee_bps(mut self, fee_bps: u32) -> Self {
+        self.fee_bps = fee_bps;
+        self
+    }
+
+    /// Fee actually charged on `amount`
+    pub fn fee_for(&self, amount: u64) -> u64 {
+        ((amount as u128 * self.fee_bps as u128) / 10_000) as u64
+    }
+
+    /// Check that the fee charged on `amount` fits the offered envelope
+    pub fn check_envelope(
+        &self,
+        amount: u64,
+        envelope: &EconomicEnvelope,
+    ) -> std::result::Result<(), String> {
+        let fee = self.fee_for(amount);
+        let max_fee = envelope.calculate_max_fee(amount);
+        if fee > max_fee {
+            return Err(format!(
+                "fee {} ({} bps) exceeds envelope max {} ({} bps)",
+                fee, self.fee_bps, max_fee, envelope.max_fees_bps
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn with_min_absolute_fee(mut self, asset: impl Into<String>, fee: u64) -> Self {
+        self.min_absolute_fee.insert(asset.into(), fee);
+        self
+    }
+
+    /// Smallest amount whose fee covers the asset's floor, if one is set
+    pub fn min_viable_amount(&self, asset: &str) -> Option<u64> {
+        let floor = *self.min_absolute_fee.get(asset)? as u128;
+        let bps = self.fee_bps.max(1) as u128;
+        Some(((floor * 10_000).div_ceil(bps)).min(u64::MAX as u128) as u64)
+    }
+
+    /// Reject amounts whose fee would not cover the asset's floor
+    pub fn check(&self, asset: &str, amount: u64) -> std::result::Result<(), String> {
+        let Some(&floor) = self.min_absolute_fee.get(asset) else {
+            return Ok(());
+        };
+        let fee = self.fee_for(amount);
+        if fee < floor {
+            return Err(format!(
+                "amount {} {} too small: fee {} below floor {}",
+                amount, asset, fee, floor
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for FeeSchedule {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_FEES_BPS)
+    }
+}
 
 /// Order router
 pub struct Router {
-    // Router state
+    fee_schedule: FeeSchedule,
+    /// SETTLEs from sources with a lower `trust_level()` are refused
+    /// (0 = accept every source)
+    min_settle_trust_level: u8,
+    /// Gate applied by `verify_settle` after the trust check
+    settlement_verifier: Box<dyn SettlementVerifier>,
+    /// Route sources consulted by `route_query`, in registration order
+    route_providers: Vec<Box<dyn RouteProvider>>,
+    /// Picks the OFFER's route among the providers' candidates
+    routing_strategy: Box<dyn RoutingStrategy>,
 }
 
 impl Router {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            fee_schedule: FeeSchedule::default(),
+            min_settle_trust_level: 0,
+            settlement_verifier: Box::new(ChainVerifier),
+            route_providers: Vec::new(),
+            routing_strategy: Box::new(LowestFeeStrategy),
+        }
+    }
+
+    pub fn with_fee_schedule(mut self, fee_schedule: FeeSchedule) -> Self {
+        self.fee_schedule = fee_schedule;
+        self
+    }
+
+    /// High-value flows can set this above `BuyerNotify` (30) to act only
+    /// on indexer- or watcher-reported settlements
+    pub fn with_min_settle_trust_level(mut self, level: u8) -> Self {
+        self.min_settle_trust_level = level;
+        self
+    }
+
+    /// Replace the default `ChainVerifier`
+    pub fn with_settlement_verifier(mut self, verifier: impl SettlementVerifier + 'static) -> Self {
+        self.settlement_verifier = Box::new(verifier);
+        self
+    }
+
+    pub fn with_route_provider(mut self, provider: impl RouteProvider + 'static) -> Self {
+        self.route_providers.push(Box::new(provider));
+        self
+    }
+
+    /// Replace the default `LowestFeeStrategy`
+    pub fn with_routing_strategy(mut self, strategy: impl RoutingStrategy + 'static) -> Self {
+        self.routing_strategy = Box::new(strategy);
+        self
+    }
+
+    /// Routes the registered providers offer for `query`, minus those the
+    /// QUERY's policy rules out: non-escrow routes when escrow is
+    /// `Required`, and routes whose fee exceeds the advertised ceiling
+    pub fn route_candidates(&self, query: &QueryMessage) -> Vec<RouteCandidate> {
+        let escrow_only = query.zk_profile.requires_escrow();
+        self.route_providers
+            .iter()
+            .flat_map(|p| {
+                p.routes(query)
+                    .into_iter()
+                    .map(|route| RouteCandidate::new(p.id(), route))
+            })
+            .filter(|c| !escrow_only || c.is_escrow())
+            .filter(|c| c.route.estimated_fee_bps <= self.fee_schedule.max_fees_bps)
+            .collect()
+    }
+
+    /// Settlement route for `query` chosen by the routing strategy
+    ///
+    /// `Ok(None)` when no providers are registered; an error when providers
+    /// are registered but none offers an acceptable route.
+    pub fn select_route(&self, query: &QueryMessage) -> Result<Option<RouteCandidate>> {
+        if self.route_providers.is_empty() {
+            return Ok(None);
+        }
+        let candidates = self.route_candidates(query);
+        self.routing_strategy
+            .select(query, &candidates)
+            .map(Some)
+            .ok_or_else(|| {
+                anyhow!(
+                    "no route for QUERY {} ({} {}, zk_profile {:?})",
+                    query.id,
+                    query.amount,
+                    query.asset,
+                    query.zk_profile
+                )
+            })
     }
     
     /// Route an order to an appropriate seller
@@ -22,10 +200,443 @@ impl Router {
             agent_id: "agent-001".to_string(),
         })
     }
+
+    /// Answer a QUERY with an OFFER
+    ///
+    /// With route providers registered, the OFFER carries the route the
+    /// routing strategy selected. The OFFER is checked against the QUERY
+    /// before it is returned, so a routing bug can never offer a different
+    /// asset or amount.
+    pub fn route_query(&self, query: &QueryMessage) -> Result<OfferMessage> {
+        query.validate().map_err(|e| anyhow!("invalid QUERY: {}", e))?;
+        self.fee_schedule
+            .check(&query.asset, query.amount)
+            .map_err(|e| anyhow!("{}: {}", error_codes::POLICY_VIOLATION, e))?;
+
+        let mut offer = OfferMessage::new(
+            format!("offer-{}", query.id),
+            query.id.clone(),
+            query.asset.clone(),
+            query.amount,
+            query.zk_profile.requires_escrow(),
+            EconomicEnvelope::new(self.fee_schedule.max_fees_bps),
+        );
+
+        if let Some(ref contract) = query.escrow_contract_from_402 {
+            offer = offer.with_coreprover(contract.clone());
+        }
+
+        // The chosen route's escrow takes precedence over the 402 hint
+        if let Some(candidate) = self.select_route(query)? {
+            if let Some(ref contract) = candidate.route.coreprover_contract {
+                offer = offer.with_coreprover(contract.clone());
+            }
+            offer = offer.with_route(candidate.route);
+        }
+
+        if let Err(e) = self
+            .fee_schedule
+            .check_envelope(query.amount, &offer.economic_envelope)
+        {
+            tracing::warn!(query_id = %query.id, "refusing OFFER: {}", e);
+            return Err(anyhow!("{}: {}", error_codes::POLICY_VIOLATION, e));
+        }
+
+        offer.validate().map_err(|e| anyhow!("invalid OFFER: {}", e))?;
+        offer
+            .validate_echo(query)
+            .map_err(|e| anyhow!("OFFER does not echo QUERY: {}", e))?;
+
+        Ok(offer)
+    }
+
+    /// Answer a QUERY with an OFFER, or an ERROR correlated to the QUERY
+    ///
+    /// Amounts below the asset's fee floor, or a fee the envelope would not
+    /// cover, get `POLICY_VIOLATION`; any other failure gets `INVALID_QUERY`.
+    pub fn respond_to_query(&self, query: &QueryMessage) -> TGPMessage {
+        match self.route_query(query) {
+            Ok(offer) => TGPMessage::Offer(offer),
+            Err(e) => {
+                // route_query prefixes its fee policy failures with the code
+                let message = e.to_string();
+                let policy = format!("{}: ", error_codes::POLICY_VIOLATION);
+                let (code, details) = match message.strip_prefix(&policy) {
+                    Some(details) => (error_codes::POLICY_VIOLATION, details.to_string()),
+                    None => (error_codes::INVALID_QUERY, message),
+                };
+                TGPMessage::Error(ErrorMessage::with_correlation(
+                    format!("err-{}", query.id),
+                    code,
+                    details,
+                    query.id.clone(),
+                ))
+            }
+        }
+    }
+
+    /// Refuse a SETTLE whose source is trusted less than the configured
+    /// minimum, with a `POLICY_VIOLATION` ERROR correlated to the SETTLE
+    pub fn check_settle_trust(
+        &self,
+        settle: &SettleMessage,
+    ) -> std::result::Result<(), ErrorMessage> {
+        let level = settle.source.trust_level();
+        if level < self.min_settle_trust_level {
+            return Err(ErrorMessage::with_correlation(
+                format!("err-{}", settle.id),
+                error_codes::POLICY_VIOLATION,
+                format!(
+                    "SETTLE source {:?} (trust {}) below required trust {}",
+                    settle.source, level, self.min_settle_trust_level
+                ),
+                settle.id.clone(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Decide whether the controller may act on a SETTLE: the source must
+    /// meet the trust minimum and pass the settlement verifier, so with the
+    /// default `ChainVerifier` a buyer-notify SETTLE without a `layer8_tx`
+    /// is refused. Failures are `POLICY_VIOLATION` ERRORs correlated to the
+    /// SETTLE.
+    pub fn verify_settle(
+        &self,
+        settle: &SettleMessage,
+    ) -> std::result::Result<(), ErrorMessage> {
+        self.check_settle_trust(settle)?;
+
+        if let Err(e) = self.settlement_verifier.verify(settle) {
+            tracing::warn!(settle_id = %settle.id, "refusing unverified SETTLE: {}", e);
+            return Err(ErrorMessage::with_correlation(
+                format!("err-{}", settle.id),
+                error_codes::POLICY_VIOLATION,
+                e,
+                settle.id.clone(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Claim-time check that the fee actually taken stayed within the
+    /// envelope the buyer accepted. A breach is logged and returned as a
+    /// `POLICY_VIOLATION` ERROR correlated to the OFFER.
+    pub fn check_claimed_fee(
+        &self,
+        offer: &OfferMessage,
+        actual_fee: u64,
+    ) -> std::result::Result<(), ErrorMessage> {
+        let max_fee = offer.economic_envelope.calculate_max_fee(offer.amount);
+        if actual_fee > max_fee {
+            tracing::warn!(
+                offer_id = %offer.id,
+                "claimed fee {} exceeds envelope max {} ({} bps)",
+                actual_fee,
+                max_fee,
+                offer.economic_envelope.max_fees_bps
+            );
+            return Err(ErrorMessage::with_correlation(
+                format!("err-{}", offer.id),
+                error_codes::POLICY_VIOLATION,
+                format!("claimed fee {} exceeds envelope max {}", actual_fee, max_fee),
+                offer.id.clone(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Build the SETTLE the controller emits when an OFFER's escrow terminates
+    ///
+    /// Sent for every outcome, including refunds and withdrawals, so the
+    /// buyer agent can always close its session. `success` follows the
+    /// outcome and the source is the controller's own watcher.
+    pub fn settle_for_outcome(
+        &self,
+        offer: &OfferMessage,
+        outcome: SettleOutcome,
+        tx: Option<String>,
+    ) -> SettleMessage {
+        let mut settle = SettleMessage::new(
+            format!("settle-{}", offer.id),
+            offer.id.clone(),
+            outcome.is_success(),
+            SettleSource::ControllerWatcher,
+        )
+        .with_outcome(outcome);
+
+        if let Some(session_id) = &offer.session_id {
+            settle = settle.with_session(session_id.clone());
+        }
+        if let Some(tx) = tx {
+            settle = settle.with_tx(tx);
+        }
+        settle
+    }
 }
 
 impl Default for Router {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routing::FastestStrategy;
+    use tbc_core::tgp::types::{RouteOption, ZkProfile};
+
+    fn query(amount: u64) -> QueryMessage {
+        QueryMessage::new(
+            "q-1",
+            "buyer://alice",
+            "seller://bob",
+            "USDC",
+            amount,
+            ZkProfile::Optional,
+        )
+    }
+
+    fn router() -> Router {
+        // 0.5% fee, floor of 0.01 USDC (10_000 base units)
+        Router::new()
+            .with_fee_schedule(FeeSchedule::default().with_min_absolute_fee("USDC", 10_000))
+    }
+
+    #[test]
+    fn fee_floor_rejects_dust_query() {
+        let router = router();
+        assert_eq!(router.fee_schedule.min_viable_amount("USDC"), Some(2_000_000));
+
+        match router.respond_to_query(&query(1_999_999)) {
+            TGPMessage::Error(err) => {
+                assert_eq!(err.code, error_codes::POLICY_VIOLATION);
+                assert_eq!(err.correlation_id.as_deref(), Some("q-1"));
+            }
+            other => panic!("expected ERROR, got {:?}", other),
+        }
+        assert!(router.route_query(&query(1_999_999)).is_err());
+    }
+
+    #[test]
+    fn fee_floor_accepts_viable_query() {
+        let router = router();
+
+        match router.respond_to_query(&query(2_000_000)) {
+            TGPMessage::Offer(offer) => assert_eq!(offer.amount, 2_000_000),
+            other => panic!("expected OFFER, got {:?}", other),
+        }
+
+        // Assets without a floor are unrestricted
+        let mut eth = query(1);
+        eth.asset = "ETH".into();
+        assert!(router.route_query(&eth).is_ok());
+    }
+
+    #[test]
+    fn fee_floor_uses_charged_fee_not_ceiling() {
+        // Charges 0.25% under a 0.5% ceiling: the floor needs twice the amount
+        let router = Router::new().with_fee_schedule(
+            FeeSchedule::new(50)
+                .with_fee_bps(25)
+                .with_min_absolute_fee("USDC", 10_000),
+        );
+        assert_eq!(router.fee_schedule.min_viable_amount("USDC"), Some(4_000_000));
+
+        // The ceiling would yield a 19_999 fee here, but only 9_999 is charged
+        assert!(router.fee_schedule.check("USDC", 3_999_999).is_err());
+        match router.respond_to_query(&query(3_999_999)) {
+            TGPMessage::Error(err) => assert_eq!(err.code, error_codes::POLICY_VIOLATION),
+            other => panic!("expected ERROR, got {:?}", other),
+        }
+        assert!(router.route_query(&query(4_000_000)).is_ok());
+    }
+
+    #[test]
+    fn refunded_escrow_settles_unsuccessfully() {
+        let router = Router::new();
+        let offer = router.route_query(&query(5_000_000)).unwrap().with_session("sess-1");
+        let tx = "0x9f2d8e7c3b1a5f4e2d1c0b9a8f7e6d5c4b3a2f1e0d9c8b7a6f5e4d3c2b1a0f9e";
+
+        let settle = router.settle_for_outcome(&offer, SettleOutcome::Refunded, Some(tx.into()));
+        assert!(!settle.success);
+        assert_eq!(settle.outcome, Some(SettleOutcome::Refunded));
+        assert_eq!(settle.source, SettleSource::ControllerWatcher);
+        assert_eq!(settle.query_or_offer_id, offer.id);
+        assert_eq!(settle.session_id.as_deref(), Some("sess-1"));
+        assert!(settle.validate().is_ok());
+
+        let withdrawn = router.settle_for_outcome(&offer, SettleOutcome::Withdrawn, None);
+        assert!(!withdrawn.success);
+        assert!(withdrawn.validate().is_ok());
+
+        let claimed = router.settle_for_outcome(&offer, SettleOutcome::Claimed, None);
+        assert!(claimed.success);
+    }
+
+    #[test]
+    fn settle_trust_threshold_rejects_buyer_notify() {
+        let router = Router::new().with_min_settle_trust_level(50);
+        let settle = |source| SettleMessage::new("settle-1", "offer-q-1", true, source);
+
+        let err = router
+            .check_settle_trust(&settle(SettleSource::BuyerNotify))
+            .unwrap_err();
+        assert_eq!(err.code, error_codes::POLICY_VIOLATION);
+        assert_eq!(err.correlation_id.as_deref(), Some("settle-1"));
+
+        assert!(router.check_settle_trust(&settle(SettleSource::CoreproverIndexer)).is_ok());
+        assert!(router.check_settle_trust(&settle(SettleSource::ControllerWatcher)).is_ok());
+
+        // Default policy accepts every source
+        assert!(Router::new().check_settle_trust(&settle(SettleSource::BuyerNotify)).is_ok());
+    }
+
+    #[test]
+    fn fee_within_envelope_is_offered_and_claimed() {
+        // Charges 0.3% under a 0.5% envelope
+        let router = Router::new().with_fee_schedule(FeeSchedule::new(50).with_fee_bps(30));
+        let offer = router.route_query(&query(1_000_000)).unwrap();
+        assert_eq!(offer.economic_envelope.max_fees_bps, 50);
+
+        let fee = router.fee_schedule.fee_for(offer.amount);
+        assert_eq!(fee, 3_000);
+        assert!(router.check_claimed_fee(&offer, fee).is_ok());
+        assert!(router.check_claimed_fee(&offer, 5_000).is_ok());
+    }
+
+    #[test]
+    fn fee_exceeding_envelope_is_policy_violation() {
+        // Charges 0.8% but would only advertise 0.5%
+        let router = Router::new().with_fee_schedule(FeeSchedule::new(50).with_fee_bps(80));
+        match router.respond_to_query(&query(1_000_000)) {
+            TGPMessage::Error(err) => assert_eq!(err.code, error_codes::POLICY_VIOLATION),
+            other => panic!("expected ERROR, got {:?}", other),
+        }
+
+        let offer = Router::new().route_query(&query(1_000_000)).unwrap();
+        let err = router.check_claimed_fee(&offer, 5_001).unwrap_err();
+        assert_eq!(err.code, error_codes::POLICY_VIOLATION);
+        assert_eq!(err.correlation_id.as_deref(), Some(offer.id.as_str()));
+    }
+
+    #[test]
+    fn unverified_buyer_notify_settle_is_refused() {
+        let router = Router::new();
+        let tx = "0x9f2d8e7c3b1a5f4e2d1c0b9a8f7e6d5c4b3a2f1e0d9c8b7a6f5e4d3c2b1a0f9e";
+        let settle = |source| SettleMessage::new("settle-1", "offer-q-1", true, source);
+
+        let err = router.verify_settle(&settle(SettleSource::BuyerNotify)).unwrap_err();
+        assert_eq!(err.code, error_codes::POLICY_VIOLATION);
+        assert_eq!(err.correlation_id.as_deref(), Some("settle-1"));
+        assert!(router.verify_settle(&settle(SettleSource::BuyerNotify).with_tx(tx)).is_ok());
+
+        assert!(router.verify_settle(&settle(SettleSource::CoreproverIndexer)).is_err());
+        assert!(router.verify_settle(&settle(SettleSource::CoreproverIndexer).with_tx(tx)).is_ok());
+        assert!(router.verify_settle(&settle(SettleSource::ControllerWatcher)).is_ok());
+
+        // The trust minimum still applies to verified SETTLEs
+        let strict = Router::new().with_min_settle_trust_level(50);
+        assert!(strict.verify_settle(&settle(SettleSource::BuyerNotify).with_tx(tx)).is_err());
+    }
+
+    #[test]
+    fn custom_settlement_verifier_replaces_default() {
+        struct RejectAll;
+        impl SettlementVerifier for RejectAll {
+            fn verify(&self, _settle: &SettleMessage) -> std::result::Result<(), String> {
+                Err("not on chain".into())
+            }
+        }
+
+        let router = Router::new().with_settlement_verifier(RejectAll);
+        let source = SettleSource::ControllerWatcher;
+        let settle = SettleMessage::new("settle-1", "offer-q-1", true, source);
+        let err = router.verify_settle(&settle).unwrap_err();
+        assert_eq!(err.message, "not on chain");
+    }
+
+    const ESCROW: &str = "0x742d35Cc6634C0532925a3b844Bc9e7595f0bEbA";
+
+    struct FixedRoutes(&'static str, Vec<RouteOption>);
+
+    impl RouteProvider for FixedRoutes {
+        fn id(&self) -> &str {
+            self.0
+        }
+
+        fn routes(&self, _query: &QueryMessage) -> Vec<RouteOption> {
+            self.1.clone()
+        }
+    }
+
+    /// Cheap but slow escrow on PulseChain, fast but pricier direct on Base
+    fn two_provider_router() -> Router {
+        Router::new()
+            .with_route_provider(FixedRoutes(
+                "pulse-escrow",
+                vec![RouteOption::new(369, 20, 120).with_coreprover(ESCROW)],
+            ))
+            .with_route_provider(FixedRoutes("base-direct", vec![RouteOption::new(8453, 40, 2)]))
+    }
+
+    /// REQUIRED QUERY; coherent only because the 402 advertised an escrow
+    fn required_zk_query() -> QueryMessage {
+        QueryMessage::with_escrow_from_402(
+            "q-1",
+            "buyer://alice",
+            "seller://bob",
+            "USDC",
+            1_000_000,
+            ESCROW,
+            ZkProfile::Required,
+        )
+    }
+
+    #[test]
+    fn lowest_fee_strategy_picks_cheapest_route() {
+        let offer = two_provider_router().route_query(&query(1_000_000)).unwrap();
+
+        assert_eq!(offer.routes.len(), 1);
+        assert_eq!(offer.routes[0].chain_id, 369);
+        assert_eq!(offer.coreprover_contract.as_deref(), Some(ESCROW));
+    }
+
+    #[test]
+    fn fastest_strategy_picks_quickest_route() {
+        let router = two_provider_router().with_routing_strategy(FastestStrategy);
+        let offer = router.route_query(&query(1_000_000)).unwrap();
+
+        assert_eq!(offer.routes.len(), 1);
+        assert_eq!(offer.routes[0].chain_id, 8453);
+        assert!(offer.coreprover_contract.is_none());
+    }
+
+    #[test]
+    fn required_zk_profile_skips_non_escrow_routes() {
+        let router = two_provider_router().with_routing_strategy(FastestStrategy);
+        let candidates = router.route_candidates(&required_zk_query());
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].provider_id, "pulse-escrow");
+
+        let offer = router.route_query(&required_zk_query()).unwrap();
+        assert_eq!(offer.routes[0].chain_id, 369);
+        assert!(offer.zk_required);
+
+        let direct_only = Router::new()
+            .with_route_provider(FixedRoutes("base-direct", vec![RouteOption::new(8453, 40, 2)]));
+        let err = direct_only.route_query(&required_zk_query()).unwrap_err();
+        assert!(err.to_string().contains("no route for QUERY q-1"), "{}", err);
+    }
+
+    #[test]
+    fn routes_above_fee_ceiling_are_skipped() {
+        let router = Router::new()
+            .with_route_provider(FixedRoutes("pricey", vec![RouteOption::new(1, 80, 1)]))
+            .with_route_provider(FixedRoutes("base-direct", vec![RouteOption::new(8453, 40, 2)]));
+        let offer = router.with_routing_strategy(FastestStrategy).route_query(&query(1_000_000));
+
+        assert_eq!(offer.unwrap().routes[0].chain_id, 8453);
+    }
+}
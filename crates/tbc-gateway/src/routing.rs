@@ -0,0 +1,78 @@
+//! Settlement route selection
+//!
+//! [`RouteProvider`]s propose settlement routes for a QUERY; the router
+//! filters them by the QUERY's policy and lets a [`RoutingStrategy`] pick
+//! the one that goes into the OFFER.
+
+use tbc_core::tgp::messages::QueryMessage;
+use tbc_core::tgp::types::RouteOption;
+
+/// A route proposed by a registered provider
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteCandidate {
+    /// `RouteProvider::id` of the provider that proposed the route
+    pub provider_id: String,
+    pub route: RouteOption,
+}
+
+impl RouteCandidate {
+    pub fn new(provider_id: impl Into<String>, route: RouteOption) -> Self {
+        Self {
+            provider_id: provider_id.into(),
+            route,
+        }
+    }
+
+    /// Whether the route settles through a CoreProver escrow
+    pub fn is_escrow(&self) -> bool {
+        self.route.coreprover_contract.is_some()
+    }
+}
+
+/// Source of settlement routes (a chain, a bridge, a CoreProver deployment)
+pub trait RouteProvider: Send + Sync {
+    fn id(&self) -> &str;
+
+    /// Routes this provider can settle `query` on; empty when it can't
+    fn routes(&self, query: &QueryMessage) -> Vec<RouteOption>;
+}
+
+/// Picks one route out of the candidates the router kept for a QUERY
+pub trait RoutingStrategy: Send + Sync {
+    fn select(&self, query: &QueryMessage, candidates: &[RouteCandidate])
+        -> Option<RouteCandidate>;
+}
+
+/// Cheapest route, with lower latency breaking fee ties (the default)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LowestFeeStrategy;
+
+impl RoutingStrategy for LowestFeeStrategy {
+    fn select(
+        &self,
+        _query: &QueryMessage,
+        candidates: &[RouteCandidate],
+    ) -> Option<RouteCandidate> {
+        candidates
+            .iter()
+            .min_by_key(|c| (c.route.estimated_fee_bps, c.route.estimated_latency_secs))
+            .cloned()
+    }
+}
+
+/// Quickest route to final settlement, with lower fee breaking ties
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FastestStrategy;
+
+impl RoutingStrategy for FastestStrategy {
+    fn select(
+        &self,
+        _query: &QueryMessage,
+        candidates: &[RouteCandidate],
+    ) -> Option<RouteCandidate> {
+        candidates
+            .iter()
+            .min_by_key(|c| (c.route.estimated_latency_secs, c.route.estimated_fee_bps))
+            .cloned()
+    }
+}
@@ -0,0 +1,5 @@
+//! x402 integration (TGP-00 §7)
+
+pub mod parser;
+
+pub use parser::{parse_402_headers, EscrowContractRef, X402Headers};
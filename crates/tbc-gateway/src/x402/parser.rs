@@ -0,0 +1,160 @@
+//! x402 header parsing (TGP-00 §7, x402 Header Mapping)
+//!
+//! Extracts TGP-relevant fields from an HTTP 402 response:
+//!
+//! | x402 Header         | TGP Field                  |
+//! |---------------------|----------------------------|
+//! | `X-Escrow-Contract` | `escrow_contract_from_402` |
+//! | `X-Payment-Asset`   | `asset`                    |
+//! | `X-Payment-Amount`  | `amount`                   |
+//! | `X-Session-ID`      | `session_id`               |
+//!
+//! `X-Escrow-Contract` may be a bare address (assumed to live on the
+//! current chain) or a CAIP-10 account id `namespace:chainref:address`,
+//! e.g. `eip155:369:0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb0`.
+
+use tbc_core::tgp::validation::validate_address;
+
+pub const HEADER_ESCROW_CONTRACT: &str = "X-Escrow-Contract";
+pub const HEADER_PAYMENT_ASSET: &str = "X-Payment-Asset";
+pub const HEADER_PAYMENT_AMOUNT: &str = "X-Payment-Amount";
+pub const HEADER_SESSION_ID: &str = "X-Session-ID";
+
+/// Escrow contract advertised in a 402, resolved to a chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EscrowContractRef {
+    pub chain_id: u64,
+    pub address: String,
+}
+
+impl EscrowContractRef {
+    /// Parse a CAIP-10 `namespace:chainref:address` or a bare address
+    ///
+    /// Bare addresses resolve to `current_chain_id`. Only the `eip155`
+    /// namespace is supported; its addresses must be 0x + 40 hex.
+    pub fn parse(value: &str, current_chain_id: u64) -> Result<Self, String> {
+        let value = value.trim();
+        let parts: Vec<&str> = value.split(':').collect();
+
+        let (chain_id, address) = match parts.as_slice() {
+            [address] => (current_chain_id, *address),
+            [namespace, chain_ref, address] => {
+                if *namespace != "eip155" {
+                    return Err(format!(
+                        "unsupported chain namespace '{}' in {}",
+                        namespace, HEADER_ESCROW_CONTRACT
+                    ));
+                }
+                let chain_id = chain_ref.parse::<u64>().map_err(|_| {
+                    format!(
+                        "invalid chain reference '{}' in {}",
+                        chain_ref, HEADER_ESCROW_CONTRACT
+                    )
+                })?;
+                (chain_id, *address)
+            }
+            _ => {
+                return Err(format!(
+                    "malformed {} value '{}'",
+                    HEADER_ESCROW_CONTRACT, value
+                ))
+            }
+        };
+
+        validate_address(address, "escrow_contract_from_402")?;
+
+        Ok(Self {
+            chain_id,
+            address: address.to_string(),
+        })
+    }
+}
+
+/// TGP-relevant fields extracted from 402 response headers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct X402Headers {
+    pub escrow_contract: Option<EscrowContractRef>,
+    pub asset: Option<String>,
+    pub amount: Option<u64>,
+    pub session_id: Option<String>,
+}
+
+/// Parse 402 response headers (names are matched case-insensitively)
+///
+/// # Errors
+///
+/// Returns an error if a recognized header is present but malformed.
+pub fn parse_402_headers<I, K, V>(headers: I, current_chain_id: u64) -> Result<X402Headers, String>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let mut parsed = X402Headers::default();
+
+    for (name, value) in headers {
+        let name = name.as_ref();
+        let value = value.as_ref().trim();
+
+        if name.eq_ignore_ascii_case(HEADER_ESCROW_CONTRACT) {
+            parsed.escrow_contract = Some(EscrowContractRef::parse(value, current_chain_id)?);
+        } else if name.eq_ignore_ascii_case(HEADER_PAYMENT_ASSET) {
+            parsed.asset = Some(value.to_string());
+        } else if name.eq_ignore_ascii_case(HEADER_PAYMENT_AMOUNT) {
+            let amount = value
+                .parse::<u64>()
+                .map_err(|_| format!("invalid {} value '{}'", HEADER_PAYMENT_AMOUNT, value))?;
+            parsed.amount = Some(amount);
+        } else if name.eq_ignore_ascii_case(HEADER_SESSION_ID) {
+            parsed.session_id = Some(value.to_string());
+        }
+    }
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDR: &str = "0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb0";
+
+    #[test]
+    fn test_caip10_escrow_contract() {
+        let value = format!("eip155:369:{}", ADDR);
+        let headers = parse_402_headers(
+            [("x-escrow-contract", value.as_str()), ("X-Payment-Amount", "1000")],
+            1,
+        )
+        .unwrap();
+
+        assert_eq!(
+            headers.escrow_contract,
+            Some(EscrowContractRef {
+                chain_id: 369,
+                address: ADDR.to_string(),
+            })
+        );
+        assert_eq!(headers.amount, Some(1000));
+    }
+
+    #[test]
+    fn test_bare_address_uses_current_chain() {
+        let headers = parse_402_headers([("X-Escrow-Contract", ADDR)], 8453).unwrap();
+        let contract = headers.escrow_contract.unwrap();
+        assert_eq!(contract.chain_id, 8453);
+        assert_eq!(contract.address, ADDR);
+    }
+
+    #[test]
+    fn test_malformed_chain_reference() {
+        let value = format!("eip155:pulse:{}", ADDR);
+        let err = parse_402_headers([("X-Escrow-Contract", value.as_str())], 1).unwrap_err();
+        assert!(err.contains("chain reference"));
+
+        let value = format!("solana:mainnet:{}", ADDR);
+        assert!(parse_402_headers([("X-Escrow-Contract", value.as_str())], 1).is_err());
+
+        assert!(parse_402_headers([("X-Escrow-Contract", "eip155:1:0x1234")], 1).is_err());
+    }
+}
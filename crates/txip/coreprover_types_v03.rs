@@ -16,6 +16,8 @@ use super::blockchain_types_v03::{BuyerTxIds, ChainId, SellerTxIds};
 // No shadow enums.
 use coreprover_service::engine::EscrowState;
 
+use coreprover_bridge::eip712::{Eip712Domain, ReceiptTypedData};
+
 /// =======================================================================
 /// COREPROVER RECEIPT -- CANONICAL & UNCHANGED
 /// =======================================================================
@@ -30,6 +32,10 @@ pub struct CoreProverReceipt {
     pub fulfillment_unix: u64,
     pub fulfillment_iso: String,
 
+    // fulfillment deadline (unix), 0 if not recorded
+    #[serde(default)]
+    pub fulfillment_deadline_unix: u64,
+
     // settlement timestamps
     pub settlement_mono: u64,
     pub settlement_unix: u64,
@@ -54,8 +60,20 @@ pub struct CoreProverReceipt {
     // optional withdrawal
     pub buyer_withdraw_txid: Option<String>,
 
+    // amount returned to the buyer on refund/withdrawal; None = all of it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub buyer_refund_amount: Option<u128>,
+
     // block height where seller fulfilled
     pub seller_block_height: u64,
+
+    // party identities (off-chain metadata; removed by `redacted`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub buyer_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seller_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seller_business_license: Option<String>,
 }
 
 impl CoreProverReceipt {
@@ -82,6 +100,8 @@ impl CoreProverReceipt {
             fulfillment_unix: fulfillment.unix,
             fulfillment_iso: fulfillment.iso,
 
+            fulfillment_deadline_unix: 0,
+
             settlement_mono: settlement.mono,
             settlement_unix: settlement.unix,
             settlement_iso: settlement.iso,
@@ -99,11 +119,48 @@ impl CoreProverReceipt {
             seller_claim_txid: None,
             seller_refund_txid: None,
             buyer_withdraw_txid: None,
+            buyer_refund_amount: None,
 
             seller_block_height,
+
+            buyer_id: None,
+            seller_id: None,
+            seller_business_license: None,
+        }
+    }
+
+    pub fn with_parties(mut self, buyer_id: String, seller_id: String) -> Self {
+        self.buyer_id = Some(buyer_id);
+        self.seller_id = Some(seller_id);
+        self
+    }
+
+    pub fn with_business_license(mut self, license: String) -> Self {
+        self.seller_business_license = Some(license);
+        self
+    }
+
+    /// Copy suitable for public disclosure.
+    ///
+    /// Removed: `buyer_id`, `seller_id`, `seller_business_license`.
+    ///
+    /// Kept (needed to verify the receipt against chain data): session_id,
+    /// order_amount, all fulfillment/settlement timestamps and deadline,
+    /// discount terms, chain ids, every txid, and seller_block_height.
+    pub fn redacted(&self) -> Self {
+        Self {
+            buyer_id: None,
+            seller_id: None,
+            seller_business_license: None,
+            ..self.clone()
         }
     }
 
+    pub fn with_fulfillment_deadline(mut self, deadline_unix: u64) -> Self {
+        self.fulfillment_deadline_unix = deadline_unix;
+        self
+    }
+
     pub fn with_seller_claim(mut self, txid: String) -> Self {
         self.seller_claim_txid = Some(txid);
         self
@@ -119,6 +176,12 @@ impl CoreProverReceipt {
         self
     }
 
+    /// Partial refund/withdrawal: only `amount` goes back to the buyer
+    pub fn with_buyer_refund_amount(mut self, amount: u128) -> Self {
+        self.buyer_refund_amount = Some(amount);
+        self
+    }
+
     pub fn validate(&self) -> Result<(), String> {
         if self.session_id.is_empty() {
             return Err("session_id is required".into());
@@ -132,9 +195,15 @@ impl CoreProverReceipt {
         if self.seller_accept_txid.is_empty() {
             return Err("seller_accept_txid is required".into());
         }
-        if self.seller_fulfill_txid.is_empty() {
+        // A seller decline refunds before fulfillment: no fulfill txid and
+        // zeroed fulfillment time
+        let declined = self.seller_refund_txid.is_some() && self.fulfillment_unix == 0;
+        if self.seller_fulfill_txid.is_empty() && !declined {
             return Err("seller_fulfill_txid is required".into());
         }
+        if self.buyer_refund_amount.is_some_and(|a| a > self.order_amount) {
+            return Err("buyer_refund_amount exceeds order_amount".into());
+        }
 
         match (&self.seller_claim_txid, &self.seller_refund_txid) {
             (Some(_), None) | (None, Some(_)) => Ok(()),
@@ -143,6 +212,21 @@ impl CoreProverReceipt {
         }
     }
 
+    /// Both chain ids must be among the chains negotiated for the session;
+    /// anything else means an escrow was routed on an unexpected chain.
+    pub fn validate_against_session(&self, chains: &[ChainId]) -> Result<(), String> {
+        let parties = [("buyer", self.buyer_chain_id), ("seller", self.seller_chain_id)];
+        for (party, chain_id) in parties {
+            if !chains.contains(&chain_id) {
+                return Err(format!(
+                    "{}_chain_id {} not negotiated for session (chains {:?})",
+                    party, chain_id, chains
+                ));
+            }
+        }
+        Ok(())
+    }
+
     pub fn has_discount(&self) -> bool {
         self.discount_pct > 0
     }
@@ -155,6 +239,25 @@ impl CoreProverReceipt {
         self.buyer_chain_id != self.seller_chain_id
     }
 
+    /// Seconds past `fulfillment_deadline_unix` that fulfillment occurred.
+    /// None if fulfilled on time.
+    pub fn lateness_seconds(&self, fulfillment_deadline_unix: u64) -> Option<u64> {
+        if self.fulfillment_unix > fulfillment_deadline_unix {
+            Some(self.fulfillment_unix - fulfillment_deadline_unix)
+        } else {
+            None
+        }
+    }
+
+    /// Lateness against the deadline stored on the receipt.
+    /// None if on time or no deadline was recorded.
+    pub fn lateness(&self) -> Option<u64> {
+        if self.fulfillment_deadline_unix == 0 {
+            return None;
+        }
+        self.lateness_seconds(self.fulfillment_deadline_unix)
+    }
+
     pub fn fulfillment_timestamp(&self) -> TripleTimestamp {
         TripleTimestamp::new(
             self.fulfillment_mono,
@@ -170,6 +273,383 @@ impl CoreProverReceipt {
             self.settlement_iso.clone(),
         )
     }
+
+    /// What the buyer paid and got back, for buyer-facing summaries.
+    ///
+    /// A refund or withdrawal returns `buyer_refund_amount` (the whole
+    /// order if unset); a claim returns nothing.
+    pub fn buyer_net_position(&self) -> BuyerPosition {
+        let returned = self.buyer_refund_amount.unwrap_or(self.order_amount);
+        let refunded = if self.seller_refund_txid.is_some() { returned } else { 0 };
+        let withdrawn = if self.buyer_withdraw_txid.is_some() { returned } else { 0 };
+
+        BuyerPosition {
+            paid: self.order_amount,
+            refunded,
+            withdrawn,
+            net: self.order_amount.saturating_sub(refunded + withdrawn),
+            discount_pct: self.discount_pct,
+        }
+    }
+}
+
+/// Buyer's side of a settled receipt: paid − refunded − withdrawn = net,
+/// plus any late-fulfillment coupon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuyerPosition {
+    pub paid: u128,
+    pub refunded: u128,
+    pub withdrawn: u128,
+    pub net: u128,
+    pub discount_pct: u8,
+}
+
+/// =======================================================================
+/// CANONICAL JSON (hashing / anchoring)
+/// =======================================================================
+///
+/// Keys in ascending byte order, no whitespace, `None` as `null`. The
+/// order is fixed here rather than taken from the struct, so reordering
+/// `CoreProverReceipt` never changes a receipt's id. Party identities are
+/// off-chain metadata and are excluded, as in the binary layout.
+pub const CANONICAL_FIELDS: [&str; 21] = [
+    "buyer_chain_id",
+    "buyer_commit_txid",
+    "buyer_refund_amount",
+    "buyer_withdraw_txid",
+    "discount_expiration_unix",
+    "discount_pct",
+    "fulfillment_deadline_unix",
+    "fulfillment_iso",
+    "fulfillment_mono",
+    "fulfillment_unix",
+    "order_amount",
+    "seller_accept_txid",
+    "seller_block_height",
+    "seller_chain_id",
+    "seller_claim_txid",
+    "seller_fulfill_txid",
+    "seller_refund_txid",
+    "session_id",
+    "settlement_iso",
+    "settlement_mono",
+    "settlement_unix",
+];
+
+impl CoreProverReceipt {
+    pub fn canonical_json(&self) -> String {
+        fn json<T: Serialize + ?Sized>(value: &T) -> String {
+            serde_json::to_string(value).expect("receipt fields always serialize")
+        }
+
+        let values: [String; 21] = [
+            json(&self.buyer_chain_id),
+            json(&self.buyer_commit_txid),
+            json(&self.buyer_refund_amount),
+            json(&self.buyer_withdraw_txid),
+            json(&self.discount_expiration_unix),
+            json(&self.discount_pct),
+            json(&self.fulfillment_deadline_unix),
+            json(&self.fulfillment_iso),
+            json(&self.fulfillment_mono),
+            json(&self.fulfillment_unix),
+            json(&self.order_amount),
+            json(&self.seller_accept_txid),
+            json(&self.seller_block_height),
+            json(&self.seller_chain_id),
+            json(&self.seller_claim_txid),
+            json(&self.seller_fulfill_txid),
+            json(&self.seller_refund_txid),
+            json(&self.session_id),
+            json(&self.settlement_iso),
+            json(&self.settlement_mono),
+            json(&self.settlement_unix),
+        ];
+
+        let body: Vec<String> = CANONICAL_FIELDS
+            .iter()
+            .zip(values)
+            .map(|(key, value)| format!("\"{}\":{}", key, value))
+            .collect();
+        format!("{{{}}}", body.join(","))
+    }
+
+    /// UTF-8 bytes of `canonical_json`, the input to every receipt hash.
+    /// Values go through serde_json, whose string escaping (short forms for
+    /// `\b \f \n \r \t`, lowercase `\u00XX` for other control characters,
+    /// everything else raw) matches JCS (RFC 8785).
+    ///
+    /// Integers do NOT follow JCS: every integer field, including the u128
+    /// `order_amount` and u64 values above 2^53, is written as its exact
+    /// unsigned base-10 digits (no sign, exponent, fraction or leading
+    /// zeros). JCS would round such values through an IEEE-754 double, so
+    /// a generic JCS library will not reproduce these bytes.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        self.canonical_json().into_bytes()
+    }
+
+    /// keccak256 of `canonical_bytes`
+    pub fn canonical_hash(&self) -> [u8; 32] {
+        keccak256(&[&self.canonical_bytes()])
+    }
+
+    /// `0x`-prefixed `canonical_hash`
+    pub fn receipt_id(&self) -> String {
+        format!("0x{}", hex::encode(self.canonical_hash()))
+    }
+
+    /// Merkle leaf: keccak256(0x00 || canonical_json). The prefix keeps
+    /// leaves distinct from interior nodes.
+    pub fn merkle_leaf(&self) -> [u8; 32] {
+        keccak256(&[&[0x00], &self.canonical_bytes()])
+    }
+}
+
+fn keccak256(parts: &[&[u8]]) -> [u8; 32] {
+    use tiny_keccak::{Hasher, Keccak};
+
+    let mut hasher = Keccak::v256();
+    for part in parts {
+        hasher.update(part);
+    }
+    let mut digest = [0u8; 32];
+    hasher.finalize(&mut digest);
+    digest
+}
+
+/// =======================================================================
+/// EIP-712 TYPED DATA (on-chain verification)
+/// =======================================================================
+///
+/// Field mapping and type string live in `coreprover_bridge::eip712`,
+/// next to the matching Solidity struct. Unset txids become empty strings
+/// and the refund amount is resolved to what the buyer actually got back.
+
+impl CoreProverReceipt {
+    pub fn to_typed_data(&self) -> ReceiptTypedData {
+        let position = self.buyer_net_position();
+
+        ReceiptTypedData {
+            session_id: self.session_id.clone(),
+            order_amount: self.order_amount,
+            fulfillment_unix: self.fulfillment_unix,
+            fulfillment_deadline_unix: self.fulfillment_deadline_unix,
+            settlement_unix: self.settlement_unix,
+            discount_pct: self.discount_pct,
+            discount_expiration_unix: self.discount_expiration_unix,
+            buyer_chain_id: self.buyer_chain_id,
+            buyer_commit_txid: self.buyer_commit_txid.clone(),
+            seller_chain_id: self.seller_chain_id,
+            seller_accept_txid: self.seller_accept_txid.clone(),
+            seller_fulfill_txid: self.seller_fulfill_txid.clone(),
+            seller_claim_txid: self.seller_claim_txid.clone().unwrap_or_default(),
+            seller_refund_txid: self.seller_refund_txid.clone().unwrap_or_default(),
+            buyer_withdraw_txid: self.buyer_withdraw_txid.clone().unwrap_or_default(),
+            buyer_returned_amount: position.refunded + position.withdrawn,
+            seller_block_height: self.seller_block_height,
+        }
+    }
+
+    /// EIP-712 digest a contract under `domain` verifies a signature against
+    pub fn eip712_digest(&self, domain: &Eip712Domain) -> [u8; 32] {
+        self.to_typed_data().eip712_digest(domain)
+    }
+}
+
+/// =======================================================================
+/// COMPACT BINARY RECEIPT
+/// =======================================================================
+///
+/// Fixed-layout encoding for on-chain storage and hashing. All integers
+/// are big-endian; strings are UTF-8 with a u16 length prefix (longer
+/// strings fail to encode rather than being truncated); optional
+/// strings carry a one-byte presence flag (0 = None, 1 = Some).
+///
+/// Layout (version 1):
+///
+/// | Field                     | Encoding          |
+/// |---------------------------|-------------------|
+/// | format version            | u8 (= 1)          |
+/// | session_id                | str               |
+/// | order_amount              | u128              |
+/// | fulfillment_mono/unix     | u64, u64          |
+/// | fulfillment_iso           | str               |
+/// | fulfillment_deadline_unix | u64               |
+/// | settlement_mono/unix      | u64, u64          |
+/// | settlement_iso            | str               |
+/// | discount_pct              | u8                |
+/// | discount_expiration_unix  | u64               |
+/// | buyer_chain_id            | u64               |
+/// | buyer_commit_txid         | str               |
+/// | seller_chain_id           | u64               |
+/// | seller_accept_txid        | str               |
+/// | seller_fulfill_txid       | str               |
+/// | seller_claim_txid         | opt str           |
+/// | seller_refund_txid        | opt str           |
+/// | buyer_withdraw_txid       | opt str           |
+/// | seller_block_height       | u64               |
+/// | buyer_refund_amount       | opt u128 (v2)     |
+///
+/// Party identities are off-chain metadata and are not encoded.
+/// Version 1 bytes (no refund amount) still decode.
+pub const RECEIPT_BINARY_VERSION: u8 = 2;
+
+impl CoreProverReceipt {
+    /// Fails if a string field is longer than the u16 length prefix allows
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        let mut out = vec![RECEIPT_BINARY_VERSION];
+
+        put_str(&mut out, &self.session_id)?;
+        out.extend_from_slice(&self.order_amount.to_be_bytes());
+        out.extend_from_slice(&self.fulfillment_mono.to_be_bytes());
+        out.extend_from_slice(&self.fulfillment_unix.to_be_bytes());
+        put_str(&mut out, &self.fulfillment_iso)?;
+        out.extend_from_slice(&self.fulfillment_deadline_unix.to_be_bytes());
+        out.extend_from_slice(&self.settlement_mono.to_be_bytes());
+        out.extend_from_slice(&self.settlement_unix.to_be_bytes());
+        put_str(&mut out, &self.settlement_iso)?;
+        out.push(self.discount_pct);
+        out.extend_from_slice(&self.discount_expiration_unix.to_be_bytes());
+        out.extend_from_slice(&self.buyer_chain_id.to_be_bytes());
+        put_str(&mut out, &self.buyer_commit_txid)?;
+        out.extend_from_slice(&self.seller_chain_id.to_be_bytes());
+        put_str(&mut out, &self.seller_accept_txid)?;
+        put_str(&mut out, &self.seller_fulfill_txid)?;
+        put_opt_str(&mut out, &self.seller_claim_txid)?;
+        put_opt_str(&mut out, &self.seller_refund_txid)?;
+        put_opt_str(&mut out, &self.buyer_withdraw_txid)?;
+        out.extend_from_slice(&self.seller_block_height.to_be_bytes());
+        match self.buyer_refund_amount {
+            Some(amount) => {
+                out.push(1);
+                out.extend_from_slice(&amount.to_be_bytes());
+            }
+            None => out.push(0),
+        }
+
+        Ok(out)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut r = ByteReader { bytes, pos: 0 };
+
+        let version = r.u8()?;
+        if version != 1 && version != RECEIPT_BINARY_VERSION {
+            return Err(format!("unsupported receipt format version {}", version));
+        }
+
+        let receipt = Self {
+            session_id: r.str()?,
+            order_amount: r.u128()?,
+            fulfillment_mono: r.u64()?,
+            fulfillment_unix: r.u64()?,
+            fulfillment_iso: r.str()?,
+            fulfillment_deadline_unix: r.u64()?,
+            settlement_mono: r.u64()?,
+            settlement_unix: r.u64()?,
+            settlement_iso: r.str()?,
+            discount_pct: r.u8()?,
+            discount_expiration_unix: r.u64()?,
+            buyer_chain_id: r.u64()?,
+            buyer_commit_txid: r.str()?,
+            seller_chain_id: r.u64()?,
+            seller_accept_txid: r.str()?,
+            seller_fulfill_txid: r.str()?,
+            seller_claim_txid: r.opt_str()?,
+            seller_refund_txid: r.opt_str()?,
+            buyer_withdraw_txid: r.opt_str()?,
+            seller_block_height: r.u64()?,
+            buyer_refund_amount: if version >= 2 { r.opt_u128()? } else { None },
+            buyer_id: None,
+            seller_id: None,
+            seller_business_license: None,
+        };
+
+        if r.pos != bytes.len() {
+            return Err(format!("{} trailing bytes after receipt", bytes.len() - r.pos));
+        }
+
+        Ok(receipt)
+    }
+}
+
+fn put_str(out: &mut Vec<u8>, s: &str) -> Result<(), String> {
+    // Fields are txids, ids and timestamps; anything longer is refused
+    // rather than truncated
+    let len = u16::try_from(s.len()).map_err(|_| {
+        format!("string of {} bytes exceeds the {} byte field limit", s.len(), u16::MAX)
+    })?;
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(s.as_bytes());
+    Ok(())
+}
+
+fn put_opt_str(out: &mut Vec<u8>, s: &Option<String>) -> Result<(), String> {
+    match s {
+        Some(s) => {
+            out.push(1);
+            put_str(out, s)
+        }
+        None => {
+            out.push(0);
+            Ok(())
+        }
+    }
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = self.pos + n;
+        if end > self.bytes.len() {
+            return Err(format!(
+                "receipt truncated: need {} bytes at offset {}, have {}",
+                n,
+                self.pos,
+                self.bytes.len() - self.pos
+            ));
+        }
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn u128(&mut self) -> Result<u128, String> {
+        Ok(u128::from_be_bytes(self.take(16)?.try_into().unwrap()))
+    }
+
+    fn str(&mut self) -> Result<String, String> {
+        let len = u16::from_be_bytes(self.take(2)?.try_into().unwrap()) as usize;
+        String::from_utf8(self.take(len)?.to_vec()).map_err(|e| format!("invalid UTF-8: {}", e))
+    }
+
+    fn opt_str(&mut self) -> Result<Option<String>, String> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(self.str()?)),
+            flag => Err(format!("invalid option flag {}", flag)),
+        }
+    }
+
+    fn opt_u128(&mut self) -> Result<Option<u128>, String> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(self.u128()?)),
+            flag => Err(format!("invalid option flag {}", flag)),
+        }
+    }
 }
 
 /// =======================================================================
@@ -234,4 +714,542 @@ impl EscrowView {
             seller_block_height: e.seller_block_height,
         }
     }
-}
\ No newline at end of file
+
+    /// Check that the TXID provenance is consistent with `state`:
+    /// buyer commit always, seller accept from SellerAccepted, fulfill from
+    /// SellerFulfilled / LateFulfilled, a claim once (partially) claimed,
+    /// exactly the matching claim/refund once settled, and withdraw once
+    /// BuyerWithdrawn / BuyerReclaimed. A SellerRefunded escrow needs a
+    /// fulfill txid only if it was fulfilled; a seller decline refunds
+    /// before fulfillment. Present txids need a chain id.
+    pub fn validate(&self) -> Result<(), String> {
+        let buyer = self
+            .buyer_txids
+            .as_ref()
+            .ok_or("buyer_txids missing (commit is required)")?;
+        if buyer.chain_id == 0 {
+            return Err("buyer chain_id not set".into());
+        }
+        if buyer.commit_txid.trim().is_empty() {
+            return Err("buyer commit_txid is empty".into());
+        }
+
+        let (needs_accept, needs_fulfill) = match self.state {
+            EscrowState::BuyerCommitted | EscrowState::BuyerWithdrawn => (false, false),
+            EscrowState::SellerAccepted | EscrowState::FulfillmentExpired => (true, false),
+            EscrowState::SellerFulfilled
+            | EscrowState::LateFulfilled
+            | EscrowState::PartiallyClaimed
+            | EscrowState::Disputed
+            | EscrowState::SellerClaimed
+            | EscrowState::BuyerReclaimed => (true, true),
+            EscrowState::SellerRefunded => (true, self.fulfillment_time.is_some()),
+        };
+
+        let has = |txid: &Option<String>| txid.as_deref().is_some_and(|t| !t.trim().is_empty());
+
+        match &self.seller_txids {
+            None if needs_accept => {
+                return Err(format!("{:?} requires seller accept_txid", self.state));
+            }
+            None => {}
+            Some(seller) => {
+                if seller.chain_id == 0 {
+                    return Err("seller chain_id not set".into());
+                }
+                if needs_accept && seller.accept_txid.trim().is_empty() {
+                    return Err(format!("{:?} requires seller accept_txid", self.state));
+                }
+                if needs_fulfill && seller.fulfill_txid.trim().is_empty() {
+                    return Err(format!("{:?} requires seller fulfill_txid", self.state));
+                }
+
+                let claimed = has(&seller.claim_txid);
+                let refunded = has(&seller.refund_txid);
+                match self.state {
+                    EscrowState::SellerClaimed | EscrowState::PartiallyClaimed if !claimed => {
+                        return Err(format!("{:?} requires claim_txid", self.state));
+                    }
+                    EscrowState::SellerRefunded if !refunded => {
+                        return Err("SellerRefunded requires refund_txid".into());
+                    }
+                    EscrowState::SellerClaimed
+                    | EscrowState::PartiallyClaimed
+                    | EscrowState::SellerRefunded => {
+                        if claimed && refunded {
+                            return Err("claim_txid and refund_txid are mutually exclusive".into());
+                        }
+                    }
+                    _ if claimed || refunded => {
+                        return Err(format!("{:?} must not have a settlement txid", self.state));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let withdrawn = has(&buyer.withdraw_txid);
+        let buyer_exited = matches!(
+            self.state,
+            EscrowState::BuyerWithdrawn | EscrowState::BuyerReclaimed
+        );
+        if buyer_exited && !withdrawn {
+            return Err(format!("{:?} requires buyer withdraw_txid", self.state));
+        }
+        if !buyer_exited && withdrawn {
+            return Err(format!("{:?} must not have a withdraw_txid", self.state));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn receipt_fulfilled_at(fulfillment_unix: u64) -> CoreProverReceipt {
+        CoreProverReceipt::new(
+            "sess-1".into(),
+            1000,
+            TripleTimestamp::new(100, fulfillment_unix, "2024-11-14T12:00:00Z".into()),
+            TripleTimestamp::new(200, fulfillment_unix + 100, "2024-11-14T12:01:40Z".into()),
+            0,
+            0,
+            1,
+            "0xcommit".into(),
+            369,
+            "0xaccept".into(),
+            "0xfulfill".into(),
+            42,
+        )
+    }
+
+    #[test]
+    fn test_binary_roundtrip() {
+        let receipt = receipt_fulfilled_at(1_731_600_000)
+            .with_fulfillment_deadline(1_731_599_000)
+            .with_seller_claim("0xclaim".into());
+
+        let bytes = receipt.to_bytes().unwrap();
+        assert_eq!(bytes[0], RECEIPT_BINARY_VERSION);
+        assert_eq!(CoreProverReceipt::from_bytes(&bytes).unwrap(), receipt);
+    }
+
+    #[test]
+    fn test_canonical_json_ignores_field_order() {
+        // Local copy of the receipt with its fields declared in reverse
+        #[derive(Serialize)]
+        struct Reordered<'a> {
+            seller_block_height: u64,
+            buyer_refund_amount: Option<u128>,
+            buyer_withdraw_txid: &'a Option<String>,
+            seller_refund_txid: &'a Option<String>,
+            seller_claim_txid: &'a Option<String>,
+            seller_fulfill_txid: &'a str,
+            seller_accept_txid: &'a str,
+            seller_chain_id: ChainId,
+            buyer_commit_txid: &'a str,
+            buyer_chain_id: ChainId,
+            discount_expiration_unix: u64,
+            discount_pct: u8,
+            settlement_iso: &'a str,
+            settlement_unix: u64,
+            settlement_mono: u64,
+            fulfillment_deadline_unix: u64,
+            fulfillment_iso: &'a str,
+            fulfillment_unix: u64,
+            fulfillment_mono: u64,
+            order_amount: u128,
+            session_id: &'a str,
+        }
+
+        let receipt = receipt_fulfilled_at(1_731_600_000)
+            .with_fulfillment_deadline(1_731_599_000)
+            .with_seller_refund("0xrefund".into())
+            .with_buyer_refund_amount(400);
+        let r = &receipt;
+        let reordered = Reordered {
+            seller_block_height: r.seller_block_height,
+            buyer_refund_amount: r.buyer_refund_amount,
+            buyer_withdraw_txid: &r.buyer_withdraw_txid,
+            seller_refund_txid: &r.seller_refund_txid,
+            seller_claim_txid: &r.seller_claim_txid,
+            seller_fulfill_txid: &r.seller_fulfill_txid,
+            seller_accept_txid: &r.seller_accept_txid,
+            seller_chain_id: r.seller_chain_id,
+            buyer_commit_txid: &r.buyer_commit_txid,
+            buyer_chain_id: r.buyer_chain_id,
+            discount_expiration_unix: r.discount_expiration_unix,
+            discount_pct: r.discount_pct,
+            settlement_iso: &r.settlement_iso,
+            settlement_unix: r.settlement_unix,
+            settlement_mono: r.settlement_mono,
+            fulfillment_deadline_unix: r.fulfillment_deadline_unix,
+            fulfillment_iso: &r.fulfillment_iso,
+            fulfillment_unix: r.fulfillment_unix,
+            fulfillment_mono: r.fulfillment_mono,
+            order_amount: r.order_amount,
+            session_id: &r.session_id,
+        };
+
+        let reordered_json = serde_json::to_string(&reordered).unwrap();
+        assert_ne!(reordered_json, serde_json::to_string(&receipt).unwrap());
+
+        let decoded: CoreProverReceipt = serde_json::from_str(&reordered_json).unwrap();
+        assert_eq!(decoded.canonical_json(), receipt.canonical_json());
+        assert_eq!(decoded.receipt_id(), receipt.receipt_id());
+        assert_eq!(decoded.merkle_leaf(), receipt.merkle_leaf());
+
+        let canonical = receipt.canonical_json();
+        assert!(canonical.starts_with(r#"{"buyer_chain_id":1,"buyer_commit_txid":"0xcommit","#));
+        assert!(canonical.contains(r#""buyer_refund_amount":400,"buyer_withdraw_txid":null,"#));
+
+        // Identities are not part of the canonical form
+        let with_parties = receipt.clone().with_parties("buyer://a".into(), "seller://b".into());
+        assert_eq!(with_parties.receipt_id(), receipt.receipt_id());
+    }
+
+    #[test]
+    fn test_canonical_bytes_ignore_construction_order() {
+        let base = receipt_fulfilled_at(1_731_600_000);
+        let a = base
+            .clone()
+            .with_fulfillment_deadline(1_731_599_000)
+            .with_seller_refund("0xrefund".into())
+            .with_buyer_refund_amount(400);
+        let b = base
+            .with_buyer_refund_amount(400)
+            .with_seller_refund("0xrefund".into())
+            .with_fulfillment_deadline(1_731_599_000);
+
+        // A struct literal with its fields in a different order again
+        let c = CoreProverReceipt {
+            seller_business_license: None,
+            seller_id: None,
+            buyer_id: None,
+            seller_block_height: 42,
+            buyer_refund_amount: Some(400),
+            buyer_withdraw_txid: None,
+            seller_refund_txid: Some("0xrefund".into()),
+            seller_claim_txid: None,
+            seller_fulfill_txid: "0xfulfill".into(),
+            seller_accept_txid: "0xaccept".into(),
+            seller_chain_id: 369,
+            buyer_commit_txid: "0xcommit".into(),
+            buyer_chain_id: 1,
+            discount_expiration_unix: 0,
+            discount_pct: 0,
+            settlement_iso: "2024-11-14T12:01:40Z".into(),
+            settlement_unix: 1_731_600_100,
+            settlement_mono: 200,
+            fulfillment_deadline_unix: 1_731_599_000,
+            fulfillment_iso: "2024-11-14T12:00:00Z".into(),
+            fulfillment_unix: 1_731_600_000,
+            fulfillment_mono: 100,
+            order_amount: 1000,
+            session_id: "sess-1".into(),
+        };
+
+        assert_eq!(a.canonical_bytes(), b.canonical_bytes());
+        assert_eq!(a.canonical_bytes(), c.canonical_bytes());
+        assert_eq!(a.canonical_hash(), b.canonical_hash());
+        assert_eq!(a.canonical_hash(), c.canonical_hash());
+        assert_eq!(a.receipt_id(), c.receipt_id());
+
+        let bytes = a.canonical_bytes();
+        assert!(!bytes.iter().any(|b| b.is_ascii_whitespace()));
+        assert_eq!(bytes.first(), Some(&b'{'));
+
+        // Any field change moves the hash
+        let claimed = a.clone().with_seller_claim("0xclaim".into());
+        assert_ne!(claimed.canonical_hash(), a.canonical_hash());
+    }
+
+    #[test]
+    fn test_canonical_json_large_integers_are_exact() {
+        let mut receipt = receipt_fulfilled_at(1_731_600_000).with_seller_claim("0xclaim".into());
+        receipt.order_amount = u128::MAX;
+        receipt.seller_block_height = (1 << 53) + 1;
+
+        let canonical = receipt.canonical_json();
+        assert!(canonical.contains(r#""order_amount":340282366920938463463374607431768211455,"#));
+        assert!(canonical.contains(r#""seller_block_height":9007199254740993,"#));
+    }
+
+    #[test]
+    fn test_eip712_digest_known_vector() {
+        let vault = "0x5FbDB2315678afecb367f032d93F642f64180aa3".parse().unwrap();
+        let domain = Eip712Domain::new("CoreProver", "0.3", 369, vault);
+        let receipt = receipt_fulfilled_at(1_731_600_000)
+            .with_fulfillment_deadline(1_731_599_000)
+            .with_seller_claim("0xclaim".into());
+
+        // Same vector as coreprover_bridge::eip712
+        assert_eq!(
+            hex::encode(receipt.eip712_digest(&domain)),
+            "e2e45efbb090e7a6dfeaef99962837312f366ebc6fb76198c03853f2368884cf"
+        );
+
+        // Identities stay off-chain; a refund of part of the order is bound
+        let with_parties = receipt.clone().with_parties("buyer://a".into(), "seller://b".into());
+        assert_eq!(with_parties.eip712_digest(&domain), receipt.eip712_digest(&domain));
+
+        let refund = |amount| {
+            receipt_fulfilled_at(1_731_600_000)
+                .with_seller_refund("0xrefund".into())
+                .with_buyer_refund_amount(amount)
+        };
+        assert_eq!(refund(1000).to_typed_data().buyer_returned_amount, 1000);
+        assert_ne!(refund(400).eip712_digest(&domain), refund(1000).eip712_digest(&domain));
+    }
+
+    #[test]
+    fn test_binary_v1_still_decodes() {
+        let receipt = receipt_fulfilled_at(1_731_600_000).with_seller_claim("0xclaim".into());
+
+        // v1 = v2 layout without the trailing refund-amount flag
+        let mut v1 = receipt.to_bytes().unwrap();
+        v1.pop();
+        v1[0] = 1;
+        assert_eq!(CoreProverReceipt::from_bytes(&v1).unwrap(), receipt);
+
+        let partial = receipt_fulfilled_at(1_731_600_000)
+            .with_seller_refund("0xrefund".into())
+            .with_buyer_refund_amount(400);
+        assert_eq!(CoreProverReceipt::from_bytes(&partial.to_bytes().unwrap()).unwrap(), partial);
+    }
+
+    #[test]
+    fn test_binary_truncated_errors() {
+        let bytes = receipt_fulfilled_at(1_731_600_000).to_bytes().unwrap();
+
+        for len in [0, 1, 10, bytes.len() - 1] {
+            assert!(CoreProverReceipt::from_bytes(&bytes[..len]).is_err());
+        }
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[0] = 99;
+        assert!(CoreProverReceipt::from_bytes(&wrong_version).is_err());
+    }
+
+    #[test]
+    fn test_binary_oversize_string_errors() {
+        let mut receipt = receipt_fulfilled_at(1_731_600_000).with_seller_claim("0xclaim".into());
+
+        receipt.session_id = "x".repeat(u16::MAX as usize);
+        let bytes = receipt.to_bytes().unwrap();
+        assert_eq!(CoreProverReceipt::from_bytes(&bytes).unwrap(), receipt);
+
+        // One byte over, ending mid multi-byte character: refused, not cut
+        receipt.session_id = format!("{}é", "x".repeat(u16::MAX as usize - 1));
+        let err = receipt.to_bytes().unwrap_err();
+        assert!(err.contains("65536 bytes"), "{}", err);
+
+        receipt.session_id = "sess-1".into();
+        receipt.seller_claim_txid = Some("0".repeat(u16::MAX as usize + 1));
+        assert!(receipt.to_bytes().is_err());
+    }
+
+    #[test]
+    fn test_redacted_omits_identities() {
+        let receipt = receipt_fulfilled_at(1_731_600_000)
+            .with_seller_claim("0xclaim".into())
+            .with_parties("buyer://alice".into(), "seller://bob".into())
+            .with_business_license("LIC-12345".into());
+
+        let redacted = receipt.redacted();
+        assert!(redacted.validate().is_ok());
+        assert_eq!(redacted.buyer_id, None);
+        assert_eq!(redacted.seller_id, None);
+        assert_eq!(redacted.seller_business_license, None);
+
+        // Verification fields survive
+        assert_eq!(redacted.seller_claim_txid, receipt.seller_claim_txid);
+        assert_eq!(redacted.order_amount, receipt.order_amount);
+        assert_eq!(redacted.fulfillment_timestamp(), receipt.fulfillment_timestamp());
+
+        let json = serde_json::to_string(&redacted).unwrap();
+        assert!(!json.contains("alice"));
+        assert!(!json.contains("LIC-12345"));
+    }
+
+    #[test]
+    fn test_lateness_on_time() {
+        let deadline = 1_731_600_000;
+        let receipt = receipt_fulfilled_at(deadline - 10).with_fulfillment_deadline(deadline);
+
+        assert_eq!(receipt.lateness_seconds(deadline), None);
+        assert_eq!(receipt.lateness(), None);
+    }
+
+    #[test]
+    fn test_lateness_300_seconds_late() {
+        let deadline = 1_731_600_000;
+        let receipt = receipt_fulfilled_at(deadline + 300).with_fulfillment_deadline(deadline);
+
+        assert_eq!(receipt.lateness_seconds(deadline), Some(300));
+        assert_eq!(receipt.lateness(), Some(300));
+    }
+
+    #[test]
+    fn test_buyer_net_position_claim() {
+        let receipt = receipt_fulfilled_at(1_731_600_000).with_seller_claim("0xclaim".into());
+        let position = receipt.buyer_net_position();
+        assert_eq!(position.paid, 1000);
+        assert_eq!(position.refunded, 0);
+        assert_eq!(position.withdrawn, 0);
+        assert_eq!(position.net, 1000);
+    }
+
+    #[test]
+    fn test_buyer_net_position_refund() {
+        let receipt = receipt_fulfilled_at(1_731_600_000).with_seller_refund("0xrefund".into());
+        let position = receipt.buyer_net_position();
+        assert_eq!(position.refunded, 1000);
+        assert_eq!(position.net, 0);
+    }
+
+    #[test]
+    fn test_buyer_net_position_partial_refund() {
+        let mut receipt = receipt_fulfilled_at(1_731_600_000)
+            .with_seller_refund("0xrefund".into())
+            .with_buyer_refund_amount(400);
+        receipt.discount_pct = 10;
+        assert!(receipt.validate().is_ok());
+
+        assert_eq!(
+            receipt.buyer_net_position(),
+            BuyerPosition {
+                paid: 1000,
+                refunded: 400,
+                withdrawn: 0,
+                net: 600,
+                discount_pct: 10,
+            }
+        );
+
+        let over = receipt.with_buyer_refund_amount(1001);
+        assert!(over.validate().is_err());
+    }
+
+    fn view(state: EscrowState) -> EscrowView {
+        let ts = TripleTimestamp::new(100, 1_731_600_000, "2024-11-14T12:00:00Z".into());
+        EscrowView {
+            order_id: "order-1".into(),
+            session_id: "sess-1".into(),
+            state,
+            amount: 1000,
+            asset: "USDC".into(),
+            created_at: ts.clone(),
+            acceptance_deadline: ts.clone(),
+            fulfillment_deadline: None,
+            claim_deadline: None,
+            buyer_txids: Some(BuyerTxIds::new(1, "0xcommit".into())),
+            seller_txids: Some(SellerTxIds::new(369, "0xaccept".into(), "0xfulfill".into(), 42)),
+            late_fulfilled: false,
+            discount_pct: 0,
+            fulfillment_time: Some(ts.clone()),
+            settlement_time: Some(ts),
+            seller_block_height: Some(42),
+        }
+    }
+
+    #[test]
+    fn test_escrow_view_validate_consistent() {
+        let mut claimed = view(EscrowState::SellerClaimed);
+        claimed.seller_txids.as_mut().unwrap().claim_txid = Some("0xclaim".into());
+        assert!(claimed.validate().is_ok());
+
+        assert!(view(EscrowState::SellerFulfilled).validate().is_ok());
+
+        let mut committed = view(EscrowState::BuyerCommitted);
+        committed.seller_txids = None;
+        assert!(committed.validate().is_ok());
+    }
+
+    #[test]
+    fn test_escrow_view_validate_claimed_without_claim_txid() {
+        let err = view(EscrowState::SellerClaimed).validate().unwrap_err();
+        assert!(err.contains("claim_txid"));
+
+        let mut no_chain = view(EscrowState::SellerFulfilled);
+        no_chain.buyer_txids.as_mut().unwrap().chain_id = 0;
+        assert!(no_chain.validate().is_err());
+    }
+
+    #[test]
+    fn test_escrow_view_validate_later_states() {
+        for state in [EscrowState::LateFulfilled, EscrowState::Disputed] {
+            assert!(view(state).validate().is_ok(), "{:?}", state);
+
+            let mut unfulfilled = view(state);
+            unfulfilled.seller_txids.as_mut().unwrap().fulfill_txid = String::new();
+            let err = unfulfilled.validate().unwrap_err();
+            assert!(err.contains("fulfill_txid"), "{}", err);
+        }
+
+        let err = view(EscrowState::PartiallyClaimed).validate().unwrap_err();
+        assert!(err.contains("PartiallyClaimed requires claim_txid"), "{}", err);
+        let mut partial = view(EscrowState::PartiallyClaimed);
+        partial.seller_txids.as_mut().unwrap().claim_txid = Some("0xpart1".into());
+        assert!(partial.validate().is_ok());
+
+        let err = view(EscrowState::BuyerReclaimed).validate().unwrap_err();
+        assert!(err.contains("BuyerReclaimed requires buyer withdraw_txid"), "{}", err);
+        let mut reclaimed = view(EscrowState::BuyerReclaimed);
+        reclaimed.buyer_txids.as_mut().unwrap().withdraw_txid = Some("0xreclaim".into());
+        assert!(reclaimed.validate().is_ok());
+
+        let mut stray = view(EscrowState::Disputed);
+        stray.buyer_txids.as_mut().unwrap().withdraw_txid = Some("0xreclaim".into());
+        assert!(stray.validate().is_err());
+    }
+
+    #[test]
+    fn test_escrow_view_validate_declined() {
+        // seller_decline: refunded straight from SellerAccepted
+        let mut declined = view(EscrowState::SellerRefunded);
+        declined.fulfillment_time = None;
+        let seller = declined.seller_txids.as_mut().unwrap();
+        seller.fulfill_txid = String::new();
+        seller.refund_txid = Some("0xrefund".into());
+        assert!(declined.validate().is_ok());
+
+        // A fulfilled refund still needs its fulfill txid
+        let mut refunded = declined.clone();
+        refunded.fulfillment_time = Some(refunded.created_at.clone());
+        let err = refunded.validate().unwrap_err();
+        assert!(err.contains("SellerRefunded requires seller fulfill_txid"), "{}", err);
+
+        let receipt = CoreProverReceipt::new(
+            "sess-1".into(),
+            1000,
+            TripleTimestamp::new(0, 0, String::new()),
+            TripleTimestamp::new(200, 1_731_600_100, "2024-11-14T12:01:40Z".into()),
+            0,
+            0,
+            1,
+            "0xcommit".into(),
+            369,
+            "0xaccept".into(),
+            String::new(),
+            42,
+        );
+        assert!(receipt.validate().is_err());
+        assert!(receipt.with_seller_refund("0xrefund".into()).validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_against_session_chains() {
+        let receipt = receipt_fulfilled_at(1_731_600_000);
+
+        assert!(receipt.validate_against_session(&[1, 369]).is_ok());
+        assert!(receipt.validate_against_session(&[369, 10, 1]).is_ok());
+
+        let err = receipt.validate_against_session(&[1, 10]).unwrap_err();
+        assert!(err.contains("seller_chain_id 369"), "{}", err);
+        let err = receipt.validate_against_session(&[369]).unwrap_err();
+        assert!(err.contains("buyer_chain_id 1"), "{}", err);
+    }
+}
@@ -49,6 +49,16 @@ impl TripleTimestamp {
         
         Ok(())
     }
+
+    /// Render for display: the ISO string if present and well-formed,
+    /// otherwise the unix seconds (e.g. receipt stubs before settlement)
+    pub fn display_iso_or_unix(&self) -> String {
+        if !self.iso.is_empty() && self.validate_iso().is_ok() {
+            self.iso.clone()
+        } else {
+            format!("unix:{}", self.unix)
+        }
+    }
 }
 
 /// Timestamp provider trait
@@ -187,6 +197,18 @@ mod tests {
         assert!(invalid_no_tz.validate_iso().is_err());
     }
 
+    #[test]
+    fn test_display_iso_or_unix() {
+        let ts = create_test_timestamp();
+        assert_eq!(ts.display_iso_or_unix(), "2024-11-14T12:00:00Z");
+
+        let empty = TripleTimestamp::new(1000, 1731600000, String::new());
+        assert_eq!(empty.display_iso_or_unix(), "unix:1731600000");
+
+        let garbage = TripleTimestamp::new(1000, 1731600000, "not-a-date".to_string());
+        assert_eq!(garbage.display_iso_or_unix(), "unix:1731600000");
+    }
+
     #[test]
     fn test_deadline() {
         let ts = create_test_timestamp();
@@ -6,11 +6,17 @@
 // NO Instant, Duration, or SystemTime usage.
 
 use axum::{
+    body::{Body, Bytes},
     extract::{Json, State},
-    http::StatusCode,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
 };
+use flate2::read::{GzDecoder, ZlibDecoder};
+use flate2::write::{GzEncoder, ZlibEncoder};
+use flate2::Compression;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
 use std::sync::Arc;
 
 use super::timestamp_types_v03::TimestampProvider;
@@ -31,13 +37,177 @@ pub struct MessageAcceptedResponse {
     pub msg_id: String,
 }
 
+/// Outcome of one envelope in a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchItemStatus {
+    /// Processed successfully
+    Accepted,
+    /// `msg_id` already seen on the session; not processed again
+    Duplicate,
+    /// Failed; `error` says why and the rest of the batch was skipped
+    Rejected,
+    /// Not processed because an earlier envelope was rejected
+    Skipped,
+}
+
+/// Per-envelope entry in a batch response, in request order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchItemResult {
+    pub msg_id: String,
+    pub status: BatchItemStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorPayload>,
+}
+
+/// Largest request body accepted after decompression (guards against
+/// compression bombs)
+const MAX_DECODED_BODY_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Most envelopes accepted in one batch
+const MAX_BATCH_LEN: usize = 100;
+
 /// Handle incoming TxIP message via HTTP POST
 /// 
 /// Timestamps are validated from the envelope (client-provided)
 /// but session timing uses engine's TimestampProvider
+///
+/// A `Content-Encoding: gzip` or `deflate` body is decompressed before
+/// parsing, and the response is compressed with the best coding the
+/// client lists in `Accept-Encoding`. Without those headers, bodies are
+/// identity-encoded both ways.
 pub async fn handle_txip_message<T: TimestampProvider + Send + Sync + 'static>(
     State(state): State<Arc<HttpHandlerState<T>>>,
-    Json(envelope): Json<TxipEnvelope>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let response_coding = ContentCoding::negotiate(&headers);
+
+    let response = match decode_body::<TxipEnvelope>(&headers, &body) {
+        Ok(envelope) => process_envelope(state, envelope).await,
+        Err((status, details)) => error_response(
+            &state,
+            "",
+            ErrorCode::TxipInvalidEnvelope,
+            status,
+            None,
+            details,
+            false,
+        ),
+    };
+
+    encode_response(response, response_coding).await
+}
+
+/// Handle a JSON array of TxIP envelopes via HTTP POST
+///
+/// Envelopes are processed one at a time in array order, each with the
+/// same validation, idempotency and sequence checks as
+/// [`handle_txip_message`], so a later envelope sees every effect of the
+/// earlier ones. Processing stops at the first rejected envelope; it and
+/// everything after it are reported, the rest as `skipped`, so the client
+/// can resend from that point. Replies such as WELCOME are not returned;
+/// send HELLO on its own.
+///
+/// The response is always `200` with one [`BatchItemResult`] per envelope,
+/// unless the body itself cannot be decoded or holds no envelopes or more
+/// than 100 of them. Content coding works as for single messages.
+pub async fn handle_txip_batch<T: TimestampProvider + Send + Sync + 'static>(
+    State(state): State<Arc<HttpHandlerState<T>>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let response_coding = ContentCoding::negotiate(&headers);
+
+    let envelopes = decode_body::<Vec<TxipEnvelope>>(&headers, &body).and_then(|envelopes| {
+        if envelopes.is_empty() || envelopes.len() > MAX_BATCH_LEN {
+            Err((400, format!("Batch must hold 1 to {} envelopes", MAX_BATCH_LEN)))
+        } else {
+            Ok(envelopes)
+        }
+    });
+
+    let response = match envelopes {
+        Ok(envelopes) => Json(process_batch(state, envelopes).await).into_response(),
+        Err((status, details)) => error_response(
+            &state,
+            "",
+            ErrorCode::TxipInvalidEnvelope,
+            status,
+            None,
+            details,
+            false,
+        ),
+    };
+
+    encode_response(response, response_coding).await
+}
+
+async fn process_batch<T: TimestampProvider + Send + Sync + 'static>(
+    state: Arc<HttpHandlerState<T>>,
+    envelopes: Vec<TxipEnvelope>,
+) -> Vec<BatchItemResult> {
+    let mut results = Vec::with_capacity(envelopes.len());
+    let mut rejected = false;
+
+    for envelope in envelopes {
+        let msg_id = envelope.msg_id.clone();
+        if rejected {
+            results.push(BatchItemResult {
+                msg_id,
+                status: BatchItemStatus::Skipped,
+                error: None,
+            });
+            continue;
+        }
+
+        let duplicate = envelope.validate().is_ok()
+            && state
+                .session_manager
+                .is_duplicate_message(&envelope.session_id, &envelope.msg_id);
+        if duplicate {
+            results.push(BatchItemResult {
+                msg_id,
+                status: BatchItemStatus::Duplicate,
+                error: None,
+            });
+            continue;
+        }
+
+        let response = process_envelope(state.clone(), envelope).await;
+        let result = if response.status().is_success() {
+            BatchItemResult {
+                msg_id,
+                status: BatchItemStatus::Accepted,
+                error: None,
+            }
+        } else {
+            rejected = true;
+            BatchItemResult {
+                msg_id,
+                status: BatchItemStatus::Rejected,
+                error: error_payload(response).await,
+            }
+        };
+        results.push(result);
+    }
+
+    results
+}
+
+/// Error payload carried by an error response, if any
+async fn error_payload(response: Response) -> Option<ErrorPayload> {
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.ok()?;
+    match serde_json::from_slice::<TxipEnvelope>(&bytes).ok()?.payload {
+        Payload::Error(error) => Some(error),
+        _ => None,
+    }
+}
+
+/// Process one parsed envelope
+async fn process_envelope<T: TimestampProvider + Send + Sync + 'static>(
+    state: Arc<HttpHandlerState<T>>,
+    envelope: TxipEnvelope,
 ) -> Response {
     // Validate envelope structure
     if let Err(e) = envelope.validate() {
@@ -58,6 +228,30 @@ pub async fn handle_txip_message<T: TimestampProvider + Send + Sync + 'static>(
         return accepted_response(&envelope.msg_id);
     }
 
+    // Reject replayed or reordered envelopes. A fresh HELLO starts a new
+    // session, so it is not held to the old session's sequence.
+    let fresh_hello = matches!(
+        &envelope.payload,
+        Payload::Control(ControlPayload::Hello(hello)) if !hello.resume
+    );
+    if !fresh_hello {
+        if let Err(e) = state.session_manager.check_sequence(
+            &envelope.session_id,
+            &envelope.msg_id,
+            envelope.seq,
+        ) {
+            return error_response(
+                &state,
+                &envelope.session_id,
+                ErrorCode::TxipSequenceViolation,
+                409,
+                Some(envelope.msg_id.clone()),
+                e,
+                false,
+            );
+        }
+    }
+
     // Route based on message type
     match &envelope.message_type {
         MessageType::Control => handle_control_message(state, envelope).await,
@@ -135,6 +329,7 @@ fn handle_hello<T: TimestampProvider + Send + Sync + 'static>(
                 state.tbc_id.clone(),
                 session_info.negotiated_tgp_version,
                 session_info.negotiated_chains,
+                session_info.negotiated_assets,
                 session_info.features,
                 state.session_manager.heartbeat_interval_sec(),
                 now,
@@ -142,14 +337,25 @@ fn handle_hello<T: TimestampProvider + Send + Sync + 'static>(
 
             Json(welcome).into_response()
         }
+        // The client must start over with a fresh HELLO
+        Err(e) if hello.resume => error_response(
+            &state,
+            &session_id,
+            ErrorCode::TxipSessionNotResumable,
+            404,
+            Some(msg_id),
+            format!("Failed to resume session: {}", e),
+            false,
+        ),
+        // A fresh HELLO only fails negotiation; retrying it cannot succeed
         Err(e) => error_response(
             &state,
             &session_id,
-            ErrorCode::TxipInternalError,
-            500,
+            ErrorCode::TxipNegotiationFailed,
+            400,
             Some(msg_id),
             format!("Failed to create session: {}", e),
-            true,
+            false,
         ),
     }
 }
@@ -183,10 +389,12 @@ fn handle_heartbeat<T: TimestampProvider + Send + Sync + 'static>(
 fn handle_close<T: TimestampProvider + Send + Sync + 'static>(
     state: Arc<HttpHandlerState<T>>,
     session_id: String,
-    _close: &ClosePayload,
+    close: &ClosePayload,
 ) -> Response {
-    // Close session
-    let _ = state.session_manager.close_session(&session_id);
+    // Close session, recording the reason for monitoring
+    let _ = state
+        .session_manager
+        .close_session_with_reason(&session_id, &close.reason);
 
     // Return 200 with no body
     StatusCode::OK.into_response()
@@ -259,6 +467,161 @@ async fn handle_tgp_message<T: TimestampProvider + Send + Sync + 'static>(
     }
 }
 
+// ============================================================================
+// Content Coding
+// ============================================================================
+
+/// Body encodings understood by the HTTP handler
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContentCoding {
+    Identity,
+    Gzip,
+    /// HTTP `deflate`, i.e. zlib-wrapped DEFLATE
+    Deflate,
+}
+
+impl ContentCoding {
+    fn parse(token: &str) -> Option<Self> {
+        match token.trim().to_ascii_lowercase().as_str() {
+            "" | "identity" => Some(ContentCoding::Identity),
+            "gzip" | "x-gzip" => Some(ContentCoding::Gzip),
+            "deflate" => Some(ContentCoding::Deflate),
+            _ => None,
+        }
+    }
+
+    fn header_value(self) -> Option<HeaderValue> {
+        match self {
+            ContentCoding::Identity => None,
+            ContentCoding::Gzip => Some(HeaderValue::from_static("gzip")),
+            ContentCoding::Deflate => Some(HeaderValue::from_static("deflate")),
+        }
+    }
+
+    /// Response coding from `Accept-Encoding`: gzip, then deflate, then
+    /// identity. Codings with `q=0` are skipped.
+    fn negotiate(headers: &HeaderMap) -> Self {
+        let accepted: Vec<ContentCoding> = headers
+            .get_all(header::ACCEPT_ENCODING)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .filter_map(|item| {
+                let mut parts = item.split(';');
+                let token = parts.next()?.trim();
+                let refused = parts.any(|p| {
+                    p.trim()
+                        .strip_prefix("q=")
+                        .and_then(|q| q.trim().parse::<f32>().ok())
+                        == Some(0.0)
+                });
+                if refused {
+                    return None;
+                }
+                if token == "*" {
+                    return Some(ContentCoding::Gzip);
+                }
+                ContentCoding::parse(token)
+            })
+            .collect();
+
+        [ContentCoding::Gzip, ContentCoding::Deflate]
+            .into_iter()
+            .find(|coding| accepted.contains(coding))
+            .unwrap_or(ContentCoding::Identity)
+    }
+
+    fn decode(self, body: &[u8]) -> std::io::Result<Vec<u8>> {
+        let reader: Box<dyn Read + '_> = match self {
+            ContentCoding::Identity => return Ok(body.to_vec()),
+            ContentCoding::Gzip => Box::new(GzDecoder::new(body)),
+            ContentCoding::Deflate => Box::new(ZlibDecoder::new(body)),
+        };
+
+        let mut decoded = Vec::new();
+        reader
+            .take(MAX_DECODED_BODY_BYTES + 1)
+            .read_to_end(&mut decoded)?;
+        if decoded.len() as u64 > MAX_DECODED_BODY_BYTES {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("decoded body exceeds {} bytes", MAX_DECODED_BODY_BYTES),
+            ));
+        }
+        Ok(decoded)
+    }
+
+    fn encode(self, body: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            ContentCoding::Identity => Ok(body.to_vec()),
+            ContentCoding::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(body)?;
+                encoder.finish()
+            }
+            ContentCoding::Deflate => {
+                let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(body)?;
+                encoder.finish()
+            }
+        }
+    }
+}
+
+/// Decompress (per `Content-Encoding`) and parse a request body
+fn decode_body<B: DeserializeOwned>(headers: &HeaderMap, body: &[u8]) -> Result<B, (u16, String)> {
+    let encoding = headers
+        .get(header::CONTENT_ENCODING)
+        .map(|value| value.to_str().unwrap_or("<non-ascii>"))
+        .unwrap_or_default();
+    let coding = ContentCoding::parse(encoding)
+        .ok_or_else(|| (415, format!("Unsupported Content-Encoding: {}", encoding)))?;
+
+    let decoded = coding
+        .decode(body)
+        .map_err(|e| (400, format!("Failed to decode {:?} body: {}", coding, e)))?;
+
+    serde_json::from_slice(&decoded).map_err(|e| (400, format!("Invalid TxIP envelope: {}", e)))
+}
+
+/// Compress a response body with `coding`
+///
+/// Empty bodies and responses that already carry a `Content-Encoding` are
+/// passed through unchanged.
+async fn encode_response(response: Response, coding: ContentCoding) -> Response {
+    let Some(encoding) = coding.header_value() else {
+        return response;
+    };
+    if response.headers().contains_key(header::CONTENT_ENCODING) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!("Failed to buffer TxIP response for compression: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    parts.headers.insert(header::VARY, HeaderValue::from_static("accept-encoding"));
+    if bytes.is_empty() {
+        return Response::from_parts(parts, Body::from(bytes));
+    }
+
+    match coding.encode(&bytes) {
+        Ok(encoded) => {
+            parts.headers.insert(header::CONTENT_ENCODING, encoding);
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Response::from_parts(parts, Body::from(encoded))
+        }
+        Err(e) => {
+            tracing::warn!("Failed to compress TxIP response, sending identity: {}", e);
+            Response::from_parts(parts, Body::from(bytes))
+        }
+    }
+}
+
 /// Create a success response
 fn accepted_response(msg_id: &str) -> Response {
     let response = MessageAcceptedResponse {
@@ -347,6 +710,51 @@ mod tests {
         }
     }
 
+    fn hello_envelope() -> TxipEnvelope {
+        let hello = HelloPayload {
+            agent_id: "buyer://alice".to_string(),
+            supported_tgp_versions: vec!["2.0".to_string()],
+            supported_transports: vec!["HTTP".to_string()],
+            supported_chains: vec![1, 369],
+            supported_assets: vec!["USDC".to_string()],
+            features: Features {
+                zk_discount_proofs: true,
+                receipt_ownership_proofs: true,
+                late_discount_support: true,
+                cross_chain_support: true,
+            },
+            auth: AuthInfo {
+                scheme: AuthScheme::None,
+                token: None,
+            },
+            resume: false,
+        };
+        TxipEnvelope::new(
+            "msg-hello".to_string(),
+            "sess-gz".to_string(),
+            Direction::ClientToTbc,
+            Role::BuyerAgent,
+            MessageType::Control,
+            TgpPhase::None,
+            TripleTimestamp::new(1000, 1731600000, "2024-11-14T12:00:00Z".to_string()),
+            Payload::Control(ControlPayload::Hello(hello)),
+        )
+    }
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        ContentCoding::Gzip.encode(bytes).unwrap()
+    }
+
+    async fn response_envelope(response: Response) -> TxipEnvelope {
+        let coding = response
+            .headers()
+            .get(header::CONTENT_ENCODING)
+            .map(|v| ContentCoding::parse(v.to_str().unwrap()).unwrap())
+            .unwrap_or(ContentCoding::Identity);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&coding.decode(&body).unwrap()).unwrap()
+    }
+
     fn create_test_state() -> Arc<HttpHandlerState<TestTimestampProvider>> {
         let provider = Arc::new(TestTimestampProvider::new(1000, 1731600000));
         let session_manager = Arc::new(SessionManager::new(Default::default(), provider));
@@ -377,6 +785,7 @@ mod tests {
                 scheme: AuthScheme::None,
                 token: None,
             },
+            resume: false,
         };
 
         let response = handle_hello(
@@ -389,6 +798,192 @@ mod tests {
 
         // Verify WELCOME was returned (status 200)
         assert_eq!(response.status(), StatusCode::OK);
+
+        let resume = HelloPayload {
+            resume: true,
+            ..hello_payload
+        };
+        let response = handle_hello(
+            state.clone(),
+            "sess-456".to_string(),
+            "msg-124".to_string(),
+            &resume,
+            Role::BuyerAgent,
+        );
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Unknown session: not resumable
+        let response = handle_hello(
+            state,
+            "sess-gone".to_string(),
+            "msg-125".to_string(),
+            &resume,
+            Role::BuyerAgent,
+        );
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_hello_negotiation_failure_is_not_retryable() {
+        let state = create_test_state();
+
+        for mutate in [
+            |h: &mut HelloPayload| h.supported_transports = vec!["CARRIER-PIGEON".to_string()],
+            |h: &mut HelloPayload| h.supported_assets = vec!["DOGE".to_string()],
+        ] {
+            let mut envelope = hello_envelope();
+            if let Payload::Control(ControlPayload::Hello(ref mut hello)) = envelope.payload {
+                mutate(hello);
+            }
+            let body = serde_json::to_vec(&envelope).unwrap();
+
+            let response =
+                handle_txip_message(State(state.clone()), HeaderMap::new(), body.into()).await;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+            let error = response_envelope(response).await;
+            assert!(matches!(
+                error.payload,
+                Payload::Error(ref e)
+                    if e.error_code == ErrorCode::TxipNegotiationFailed && !e.retryable
+            ));
+        }
+        assert!(state.session_manager.get_session("sess-gz").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_gzipped_hello_gets_welcome() {
+        let state = create_test_state();
+        let body = gzip(&serde_json::to_vec(&hello_envelope()).unwrap());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+        headers.insert(header::ACCEPT_ENCODING, HeaderValue::from_static("br;q=1, gzip;q=0.8"));
+
+        let response = handle_txip_message(State(state.clone()), headers, body.into()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+
+        let welcome = response_envelope(response).await;
+        assert!(matches!(
+            welcome.payload,
+            Payload::Control(ControlPayload::Welcome(ref w)) if w.session_id == "sess-gz"
+        ));
+        assert!(state.session_manager.get_session("sess-gz").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_identity_without_encoding_headers() {
+        let state = create_test_state();
+        let body = serde_json::to_vec(&hello_envelope()).unwrap();
+
+        let response = handle_txip_message(State(state), HeaderMap::new(), body.into()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+        let welcome = response_envelope(response).await;
+        assert_eq!(welcome.message_type, MessageType::Control);
+    }
+
+    #[tokio::test]
+    async fn test_malformed_gzip_is_invalid_envelope() {
+        let state = create_test_state();
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+
+        let body = Bytes::from_static(b"definitely not gzip");
+        let response = handle_txip_message(State(state), headers, body).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let error = response_envelope(response).await;
+        assert!(matches!(
+            error.payload,
+            Payload::Error(ref e) if e.error_code == ErrorCode::TxipInvalidEnvelope
+        ));
+    }
+
+    fn tgp_envelope(msg_id: &str, seq: u64) -> TxipEnvelope {
+        TxipEnvelope::tgp(
+            msg_id.to_string(),
+            "sess-gz".to_string(),
+            Direction::ClientToTbc,
+            Role::BuyerAgent,
+            TgpPhase::Query,
+            TripleTimestamp::new(1000, 1731600000, "2024-11-14T12:00:00Z".to_string()),
+            serde_json::json!({"phase": "QUERY", "id": msg_id}),
+        )
+        .with_seq(seq)
+    }
+
+    async fn post_batch(
+        state: Arc<HttpHandlerState<TestTimestampProvider>>,
+        envelopes: &[TxipEnvelope],
+    ) -> Vec<BatchItemResult> {
+        let body = serde_json::to_vec(envelopes).unwrap();
+        let response = handle_txip_batch(State(state), HeaderMap::new(), body.into()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_batch_with_duplicate_and_invalid() {
+        let state = create_test_state();
+        let body = serde_json::to_vec(&hello_envelope()).unwrap();
+        handle_txip_message(State(state.clone()), HeaderMap::new(), body.into()).await;
+
+        let mut invalid = tgp_envelope("msg-3", 3);
+        invalid.txip_version = "9.9".to_string();
+        let batch = [
+            tgp_envelope("msg-1", 1),
+            tgp_envelope("msg-2", 2),
+            tgp_envelope("msg-1", 1),
+            invalid,
+            tgp_envelope("msg-4", 4),
+        ];
+
+        let results = post_batch(state.clone(), &batch).await;
+        let statuses: Vec<_> = results.iter().map(|r| (r.msg_id.as_str(), r.status)).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("msg-1", BatchItemStatus::Accepted),
+                ("msg-2", BatchItemStatus::Accepted),
+                ("msg-1", BatchItemStatus::Duplicate),
+                ("msg-3", BatchItemStatus::Rejected),
+                ("msg-4", BatchItemStatus::Skipped),
+            ]
+        );
+        let error = results[3].error.as_ref().unwrap();
+        assert_eq!(error.error_code, ErrorCode::TxipInvalidEnvelope);
+        assert!(!state.session_manager.is_duplicate_message("sess-gz", "msg-4"));
+
+        // Sequence checks apply per envelope: msg-2 already took seq 2
+        let results = post_batch(state, &[tgp_envelope("msg-5", 2)]).await;
+        assert_eq!(results[0].status, BatchItemStatus::Rejected);
+        let error = results[0].error.as_ref().unwrap();
+        assert_eq!(error.error_code, ErrorCode::TxipSequenceViolation);
+    }
+
+    #[tokio::test]
+    async fn test_empty_batch_rejected() {
+        let response =
+            handle_txip_batch(State(create_test_state()), HeaderMap::new(), Bytes::from("[]"))
+                .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_accept_encoding_negotiation() {
+        let negotiate = |value: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::ACCEPT_ENCODING, HeaderValue::from_static(value));
+            ContentCoding::negotiate(&headers)
+        };
+        assert_eq!(negotiate("deflate, gzip"), ContentCoding::Gzip);
+        assert_eq!(negotiate("gzip;q=0, deflate"), ContentCoding::Deflate);
+        assert_eq!(negotiate("br"), ContentCoding::Identity);
+        assert_eq!(negotiate("*"), ContentCoding::Gzip);
+        assert_eq!(ContentCoding::negotiate(&HeaderMap::new()), ContentCoding::Identity);
     }
 
     #[test]
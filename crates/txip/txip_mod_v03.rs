@@ -25,7 +25,7 @@ pub use blockchain_types_v03::{
 
 // Re-export CoreProver types
 pub use coreprover_types_v03::{
-    CoreProverReceipt, EscrowState, EscrowRecord,
+    CoreProverReceipt, BuyerPosition, EscrowState, EscrowRecord,
 };
 
 // Re-export TxIP types
@@ -37,10 +37,13 @@ pub use txip_types_v03::{
 };
 
 // Re-export session types
-pub use txip_session_v03::{SessionManager, SessionInfo, SessionConfig};
+pub use txip_session_v03::{SessionManager, SessionInfo, SessionConfig, CloseStats};
 
 // Re-export handler types
-pub use txip_http_handler_v03::{HttpHandlerState, handle_txip_message, MessageAcceptedResponse};
+pub use txip_http_handler_v03::{
+    HttpHandlerState, handle_txip_message, handle_txip_batch, MessageAcceptedResponse,
+    BatchItemResult, BatchItemStatus,
+};
 pub use txip_websocket_handler_v03::{WebSocketHandlerState, handle_websocket_upgrade};
 
 /// Prelude module for common imports
@@ -236,6 +239,7 @@ mod integration_tests {
                 scheme: AuthScheme::None,
                 token: None,
             },
+            resume: false,
         };
 
         let session_info = manager.handle_hello(
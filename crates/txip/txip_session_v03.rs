@@ -7,7 +7,8 @@
 // All timing decisions must be made by comparing monotonic/unix timestamps
 // provided by the engine's TimestampProvider.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
 
 use super::blockchain_types_v03::ChainId;
@@ -33,7 +34,16 @@ pub struct SessionInfo {
     
     pub negotiated_tgp_version: String,
     pub negotiated_chains: Vec<ChainId>,
+    pub negotiated_transport: String,
+    /// Assets both sides support, in TBC preference order
+    pub negotiated_assets: Vec<String>,
     pub features: Features,
+
+    /// Idle timeout for this session (base timeout with jitter applied)
+    pub timeout_seconds: u64,
+
+    /// Highest envelope `seq` accepted on this session (0 = none yet)
+    pub last_seq: u64,
 }
 
 impl SessionInfo {
@@ -61,19 +71,91 @@ impl SessionInfo {
     }
 }
 
+/// Message IDs seen on one session, oldest first
+///
+/// IDs are recorded with the monotonic time they were first seen. Because
+/// that clock never goes backwards, insertion order is also age order, so
+/// both TTL and size eviction pop from the front.
+#[derive(Debug, Default)]
+struct MessageCache {
+    seen: HashSet<String>,
+    order: VecDeque<(String, u64)>,
+}
+
+impl MessageCache {
+    fn contains(&self, msg_id: &str) -> bool {
+        self.seen.contains(msg_id)
+    }
+
+    /// Record `msg_id` at `mono`, then drop the oldest IDs beyond `max_len`
+    fn insert(&mut self, msg_id: &str, mono: u64, max_len: usize) {
+        if self.seen.insert(msg_id.to_string()) {
+            self.order.push_back((msg_id.to_string(), mono));
+        }
+        while self.order.len() > max_len {
+            self.pop_oldest();
+        }
+    }
+
+    /// Drop IDs recorded more than `ttl_seconds` before `current_mono`
+    fn evict_older_than(&mut self, current_mono: u64, ttl_seconds: u64) {
+        while let Some(recorded) = self.order.front().map(|(_, mono)| *mono) {
+            if current_mono <= recorded.saturating_add(ttl_seconds) {
+                break;
+            }
+            self.pop_oldest();
+        }
+    }
+
+    fn pop_oldest(&mut self) {
+        if let Some((msg_id, _)) = self.order.pop_front() {
+            self.seen.remove(&msg_id);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.order.len()
+    }
+}
+
+/// Notified of each session [`SessionManager::cleanup_expired_with_listener`]
+/// reaps, so the controller can tell the client (CLOSE / ERROR) instead of
+/// letting it find out from a rejected request
+pub trait SessionExpiryListener {
+    /// `last_activity` is the reaped session's last activity
+    fn on_session_expired(&self, session_id: &str, last_activity: &TripleTimestamp);
+}
+
+impl<F: Fn(&str, &TripleTimestamp)> SessionExpiryListener for F {
+    fn on_session_expired(&self, session_id: &str, last_activity: &TripleTimestamp) {
+        self(session_id, last_activity)
+    }
+}
+
 /// Session manager
 pub struct SessionManager<T: TimestampProvider> {
     /// Active sessions
     sessions: Arc<RwLock<HashMap<String, SessionInfo>>>,
     
     /// Message ID tracking per session (for idempotency)
-    message_cache: Arc<RwLock<HashMap<String, HashSet<String>>>>,
+    message_cache: Arc<RwLock<HashMap<String, MessageCache>>>,
     
     /// Configuration
     config: SessionConfig,
     
     /// Timestamp provider (engine)
     timestamp_provider: Arc<T>,
+
+    /// Counters of client CLOSEs, split by `CloseReason::is_normal`
+    normal_closes: AtomicU64,
+    abnormal_closes: AtomicU64,
+}
+
+/// Snapshot of CLOSE counters for monitoring
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CloseStats {
+    pub normal: u64,
+    pub abnormal: u64,
 }
 
 /// Session configuration
@@ -84,9 +166,27 @@ pub struct SessionConfig {
     
     /// How long to keep message IDs in cache (seconds)
     pub message_cache_ttl_seconds: u64,
+
+    /// Most message IDs kept per session; the oldest are evicted first
+    pub max_cached_messages_per_session: usize,
     
-    /// Heartbeat interval (seconds)
-    pub heartbeat_interval_seconds: u64,
+    /// Heartbeat interval (seconds), or None if heartbeats are not required.
+    /// Sessions always expire on `session_timeout_seconds` of inactivity.
+    pub heartbeat_interval_seconds: Option<u64>,
+
+    /// Transports this TBC accepts, in order of preference
+    pub supported_transports: Vec<String>,
+
+    /// Assets this TBC settles, in order of preference
+    pub supported_assets: Vec<String>,
+
+    /// +/- percentage applied to `session_timeout_seconds` per session so
+    /// sessions created together do not all expire in the same tick (0 = off)
+    pub timeout_jitter_pct: u8,
+
+    /// Seed for the jitter; the same seed and session ID always yield the
+    /// same timeout
+    pub timeout_jitter_seed: u64,
 }
 
 impl Default for SessionConfig {
@@ -94,7 +194,12 @@ impl Default for SessionConfig {
         Self {
             session_timeout_seconds: 300,     // 5 minutes
             message_cache_ttl_seconds: 600,   // 10 minutes
-            heartbeat_interval_seconds: 30,   // 30 seconds
+            max_cached_messages_per_session: 10_000,
+            heartbeat_interval_seconds: Some(30), // 30 seconds
+            supported_transports: vec!["HTTP".to_string(), "WEBSOCKET".to_string()],
+            supported_assets: vec!["USDC".to_string()],
+            timeout_jitter_pct: 0,
+            timeout_jitter_seed: 0,
         }
     }
 }
@@ -107,16 +212,25 @@ impl<T: TimestampProvider> SessionManager<T> {
             message_cache: Arc::new(RwLock::new(HashMap::new())),
             config,
             timestamp_provider,
+            normal_closes: AtomicU64::new(0),
+            abnormal_closes: AtomicU64::new(0),
         }
     }
 
     /// Create or update a session from a HELLO message
+    ///
+    /// A HELLO with `resume` set is handed to [`Self::resume_session`]
+    /// instead of creating a fresh session.
     pub fn handle_hello(
         &self,
         hello: &HelloPayload,
         session_id: String,
         role: Role,
     ) -> Result<SessionInfo, String> {
+        if hello.resume {
+            return self.resume_session(&session_id, &hello.agent_id);
+        }
+
         let now = self.timestamp_provider.now();
         
         // Negotiate TGP version
@@ -124,7 +238,15 @@ impl<T: TimestampProvider> SessionManager<T> {
         
         // Negotiate chains
         let negotiated_chains = Self::negotiate_chains(&hello.supported_chains)?;
+
+        // Negotiate transport
+        let negotiated_transport = self.negotiate_transport(&hello.supported_transports)?;
+
+        // Negotiate assets
+        let negotiated_assets = self.negotiate_assets(&hello.supported_assets)?;
         
+        let timeout_seconds = self.jittered_timeout(&session_id);
+
         let session_info = SessionInfo {
             session_id: session_id.clone(),
             agent_id: hello.agent_id.clone(),
@@ -137,7 +259,11 @@ impl<T: TimestampProvider> SessionManager<T> {
             last_activity_iso: now.iso,
             negotiated_tgp_version,
             negotiated_chains,
+            negotiated_transport,
+            negotiated_assets,
             features: hello.features.clone(),
+            timeout_seconds,
+            last_seq: 0,
         };
 
         // Store session
@@ -146,11 +272,45 @@ impl<T: TimestampProvider> SessionManager<T> {
 
         // Initialize message cache for this session
         let mut cache = self.message_cache.write().unwrap();
-        cache.insert(session_id, HashSet::new());
+        cache.insert(session_id, MessageCache::default());
 
         Ok(session_info)
     }
 
+    /// Resume an existing session after a reconnect
+    ///
+    /// The session keeps its negotiated parameters, `created_*` timestamps
+    /// and message cache; only `last_activity_*` is updated. A session that
+    /// is unknown, timed out, or owned by another agent cannot be resumed,
+    /// and the client must send a fresh HELLO. A timed-out session is
+    /// removed on the spot.
+    pub fn resume_session(&self, session_id: &str, agent_id: &str) -> Result<SessionInfo, String> {
+        let now = self.timestamp_provider.now();
+        let mut sessions = self.sessions.write().unwrap();
+
+        let session = sessions.get_mut(session_id).ok_or_else(|| {
+            format!("Session not found: {}; send a fresh HELLO", session_id)
+        })?;
+
+        if session.agent_id != agent_id {
+            return Err(format!(
+                "Session {} belongs to another agent; send a fresh HELLO",
+                session_id
+            ));
+        }
+
+        if session.is_timed_out(now.mono, session.timeout_seconds) {
+            sessions.remove(session_id);
+            self.message_cache.write().unwrap().remove(session_id);
+            return Err(format!("Session expired: {}; send a fresh HELLO", session_id));
+        }
+
+        session.last_activity_mono = now.mono;
+        session.last_activity_unix = now.unix;
+        session.last_activity_iso = now.iso;
+        Ok(session.clone())
+    }
+
     /// Check if a message ID has been seen before (idempotency check)
     pub fn is_duplicate_message(&self, session_id: &str, msg_id: &str) -> bool {
         let cache = self.message_cache.read().unwrap();
@@ -163,17 +323,54 @@ impl<T: TimestampProvider> SessionManager<T> {
     }
 
     /// Record a message ID (for idempotency tracking)
+    ///
+    /// If the session already holds `max_cached_messages_per_session` IDs,
+    /// the oldest one is evicted.
     pub fn record_message(&self, session_id: &str, msg_id: &str) -> Result<(), String> {
+        let now = self.timestamp_provider.now();
         let mut cache = self.message_cache.write().unwrap();
         
         let msg_ids = cache.entry(session_id.to_string())
-            .or_insert_with(HashSet::new);
+            .or_default();
         
-        msg_ids.insert(msg_id.to_string());
+        msg_ids.insert(msg_id, now.mono, self.config.max_cached_messages_per_session);
         
         Ok(())
     }
 
+    /// Number of message IDs currently cached for a session
+    pub fn cached_message_count(&self, session_id: &str) -> usize {
+        let cache = self.message_cache.read().unwrap();
+        cache.get(session_id).map_or(0, MessageCache::len)
+    }
+
+    /// Check an envelope's sequence number and, if accepted, advance the
+    /// session's `last_seq`
+    ///
+    /// `seq` 0 is unsequenced and always accepted, as is a retransmit of a
+    /// `msg_id` already in the message cache. Otherwise `seq` must exceed
+    /// the last accepted one, which rejects replays and reordering. Unknown
+    /// sessions are left to the caller's session check.
+    pub fn check_sequence(&self, session_id: &str, msg_id: &str, seq: u64) -> Result<(), String> {
+        if seq == 0 || self.is_duplicate_message(session_id, msg_id) {
+            return Ok(());
+        }
+
+        let mut sessions = self.sessions.write().unwrap();
+        let Some(session) = sessions.get_mut(session_id) else {
+            return Ok(());
+        };
+
+        if seq <= session.last_seq {
+            return Err(format!(
+                "Sequence violation on {}: seq {} is not above last accepted {}",
+                session_id, seq, session.last_seq
+            ));
+        }
+        session.last_seq = seq;
+        Ok(())
+    }
+
     /// Update session activity timestamp
     pub fn touch_session(&self, session_id: &str) -> Result<(), String> {
         let now = self.timestamp_provider.now();
@@ -206,25 +403,104 @@ impl<T: TimestampProvider> SessionManager<T> {
         Ok(())
     }
 
+    /// Close a session on a client CLOSE, recording why
+    ///
+    /// Logs the closing session's final state with the reason code and
+    /// bumps the normal / abnormal counter. Returns the removed session.
+    pub fn close_session_with_reason(
+        &self,
+        session_id: &str,
+        reason: &CloseReason,
+    ) -> Result<Option<SessionInfo>, String> {
+        let now = self.timestamp_provider.now();
+        let closed = self.get_session(session_id);
+        self.close_session(session_id)?;
+
+        let lifetime = closed.as_ref().map(|s| now.mono.saturating_sub(s.created_mono));
+        if reason.is_normal() {
+            self.normal_closes.fetch_add(1, Ordering::Relaxed);
+            tracing::info!(
+                "TxIP session closed: session={}, reason={:?} ({}), lifetime={:?}s",
+                session_id,
+                reason,
+                reason.code(),
+                lifetime,
+            );
+        } else {
+            self.abnormal_closes.fetch_add(1, Ordering::Relaxed);
+            tracing::warn!(
+                "TxIP session closed abnormally: session={}, reason={:?} ({}), lifetime={:?}s",
+                session_id,
+                reason,
+                reason.code(),
+                lifetime,
+            );
+        }
+
+        Ok(closed)
+    }
+
+    /// CLOSE counters since startup
+    pub fn close_stats(&self) -> CloseStats {
+        CloseStats {
+            normal: self.normal_closes.load(Ordering::Relaxed),
+            abnormal: self.abnormal_closes.load(Ordering::Relaxed),
+        }
+    }
+
     /// Clean up expired sessions based on current time from provider
+    ///
+    /// Also evicts message IDs older than `message_cache_ttl_seconds` from
+    /// the sessions that remain.
     pub fn cleanup_expired(&self) {
+        self.reap_expired();
+    }
+
+    /// [`cleanup_expired`](Self::cleanup_expired), then call `listener`
+    /// once for every session it removed
+    ///
+    /// The listener runs after the manager's locks are released, so it may
+    /// call back into the manager.
+    pub fn cleanup_expired_with_listener(&self, listener: &dyn SessionExpiryListener) {
+        for session in self.reap_expired() {
+            listener.on_session_expired(&session.session_id, &session.last_activity_timestamp());
+        }
+    }
+
+    /// Remove expired sessions and stale message IDs; returns the removed
+    /// sessions
+    fn reap_expired(&self) -> Vec<SessionInfo> {
         let now = self.timestamp_provider.now();
         
         // Remove expired sessions
         let mut sessions = self.sessions.write().unwrap();
-        sessions.retain(|_, session| {
-            !session.is_timed_out(now.mono, self.config.session_timeout_seconds)
-        });
+        let expired_ids: Vec<String> = sessions
+            .values()
+            .filter(|session| session.is_timed_out(now.mono, session.timeout_seconds))
+            .map(|session| session.session_id.clone())
+            .collect();
+        let expired: Vec<SessionInfo> = expired_ids
+            .iter()
+            .filter_map(|session_id| sessions.remove(session_id))
+            .collect();
 
         // Remove message caches for inactive sessions
         let active_sessions: HashSet<String> = sessions.keys().cloned().collect();
         
         let mut cache = self.message_cache.write().unwrap();
         cache.retain(|session_id, _| active_sessions.contains(session_id));
+
+        // Expire old message IDs in the surviving sessions
+        let ttl = self.config.message_cache_ttl_seconds;
+        for msg_ids in cache.values_mut() {
+            msg_ids.evict_older_than(now.mono, ttl);
+        }
+
+        expired
     }
 
-    /// Get heartbeat interval for negotiation
-    pub fn heartbeat_interval_sec(&self) -> u64 {
+    /// Get heartbeat interval for negotiation (None = heartbeats disabled)
+    pub fn heartbeat_interval_sec(&self) -> Option<u64> {
         self.config.heartbeat_interval_seconds
     }
 
@@ -233,6 +509,25 @@ impl<T: TimestampProvider> SessionManager<T> {
         self.timestamp_provider.now()
     }
 
+    /// Per-session timeout: base timeout +/- `timeout_jitter_pct`, derived
+    /// deterministically from the seed and session ID
+    fn jittered_timeout(&self, session_id: &str) -> u64 {
+        let base = self.config.session_timeout_seconds;
+        let spread = base * self.config.timeout_jitter_pct.min(100) as u64 / 100;
+        if spread == 0 {
+            return base;
+        }
+
+        // FNV-1a over seed + session ID
+        let mut hash: u64 = 0xcbf29ce484222325 ^ self.config.timeout_jitter_seed;
+        for byte in session_id.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+
+        base - spread + hash % (2 * spread + 1)
+    }
+
     /// Negotiate TGP version
     fn negotiate_tgp_version(supported: &[String]) -> Result<String, String> {
         // For now, only support TGP 2.0
@@ -243,6 +538,41 @@ impl<T: TimestampProvider> SessionManager<T> {
         }
     }
 
+    /// Negotiate transport: first TBC-preferred transport the client supports
+    fn negotiate_transport(&self, supported: &[String]) -> Result<String, String> {
+        self.config
+            .supported_transports
+            .iter()
+            .find(|t| supported.iter().any(|c| c.eq_ignore_ascii_case(t)))
+            .cloned()
+            .ok_or_else(|| {
+                format!(
+                    "No compatible transport: client supports {:?}, TBC accepts {:?}",
+                    supported, self.config.supported_transports
+                )
+            })
+    }
+
+    /// Negotiate assets: every TBC-supported asset the client also supports
+    fn negotiate_assets(&self, supported: &[String]) -> Result<Vec<String>, String> {
+        let assets: Vec<String> = self
+            .config
+            .supported_assets
+            .iter()
+            .filter(|a| supported.iter().any(|c| c.eq_ignore_ascii_case(a)))
+            .cloned()
+            .collect();
+
+        if assets.is_empty() {
+            Err(format!(
+                "No compatible asset: client supports {:?}, TBC accepts {:?}",
+                supported, self.config.supported_assets
+            ))
+        } else {
+            Ok(assets)
+        }
+    }
+
     /// Negotiate chains
     fn negotiate_chains(supported: &[ChainId]) -> Result<Vec<ChainId>, String> {
         // For now, accept any chains the client supports
@@ -326,6 +656,7 @@ mod tests {
                 scheme: AuthScheme::None,
                 token: None,
             },
+            resume: false,
         }
     }
 
@@ -400,4 +731,466 @@ mod tests {
         manager.cleanup_expired();
         assert!(manager.get_session("sess-123").is_none());
     }
+
+    #[test]
+    fn test_cleanup_expired_notifies_listener_once() {
+        let provider = Arc::new(TestTimestampProvider::new(1000, 1731600000));
+        let mut config = SessionConfig::default();
+        config.session_timeout_seconds = 60;
+
+        let manager = SessionManager::new(config, provider.clone());
+        let hello = create_test_hello();
+
+        manager.handle_hello(&hello, "sess-old".to_string(), Role::BuyerAgent).unwrap();
+        provider.advance(30);
+        manager.handle_hello(&hello, "sess-new".to_string(), Role::BuyerAgent).unwrap();
+
+        let reaped = std::sync::Mutex::new(Vec::new());
+        let listener = |session_id: &str, last_activity: &TripleTimestamp| {
+            reaped.lock().unwrap().push((session_id.to_string(), last_activity.mono));
+        };
+
+        // Only sess-old is past its timeout; a second sweep finds nothing new
+        provider.advance(31);
+        manager.cleanup_expired_with_listener(&listener);
+        manager.cleanup_expired_with_listener(&listener);
+        assert_eq!(*reaped.lock().unwrap(), vec![("sess-old".to_string(), 1000)]);
+        assert!(manager.get_session("sess-old").is_none());
+        assert!(manager.get_session("sess-new").is_some());
+
+        provider.advance(30);
+        manager.cleanup_expired_with_listener(&listener);
+        assert_eq!(
+            *reaped.lock().unwrap(),
+            vec![("sess-old".to_string(), 1000), ("sess-new".to_string(), 1030)]
+        );
+    }
+
+    #[test]
+    fn test_heartbeats_disabled() {
+        let provider = Arc::new(TestTimestampProvider::new(1000, 1731600000));
+        let config = SessionConfig {
+            session_timeout_seconds: 60,
+            heartbeat_interval_seconds: None,
+            ..SessionConfig::default()
+        };
+
+        let manager = SessionManager::new(config, provider.clone());
+        let hello = create_test_hello();
+
+        let session = manager
+            .handle_hello(&hello, "sess-123".to_string(), Role::BuyerAgent)
+            .unwrap();
+        assert_eq!(manager.heartbeat_interval_sec(), None);
+
+        // WELCOME carries no interval
+        let welcome = TxipEnvelope::welcome(
+            "msg-welcome".to_string(),
+            "sess-123".to_string(),
+            "tbc://test".to_string(),
+            session.negotiated_tgp_version,
+            session.negotiated_chains,
+            session.negotiated_assets,
+            session.features,
+            manager.heartbeat_interval_sec(),
+            manager.now(),
+        );
+        let json = serde_json::to_value(&welcome).unwrap();
+        assert!(json["payload"].get("heartbeat_interval_sec").is_none());
+
+        // Idle timeout still applies
+        provider.advance(61);
+        manager.cleanup_expired();
+        assert!(manager.get_session("sess-123").is_none());
+    }
+
+    #[test]
+    fn test_transport_negotiation_overlap() {
+        let provider = Arc::new(TestTimestampProvider::new(1000, 1731600000));
+        let manager = SessionManager::new(SessionConfig::default(), provider);
+        let mut hello = create_test_hello();
+        hello.supported_transports = vec!["WEBSOCKET".to_string(), "HTTP".to_string()];
+
+        let session = manager
+            .handle_hello(&hello, "sess-123".to_string(), Role::BuyerAgent)
+            .unwrap();
+
+        // TBC preference order wins
+        assert_eq!(session.negotiated_transport, "HTTP");
+        assert_eq!(manager.get_session("sess-123").unwrap().negotiated_transport, "HTTP");
+    }
+
+    #[test]
+    fn test_transport_negotiation_http_only_tbc() {
+        let provider = Arc::new(TestTimestampProvider::new(1000, 1731600000));
+        let config = SessionConfig {
+            supported_transports: vec!["HTTP".to_string()],
+            ..SessionConfig::default()
+        };
+        let manager = SessionManager::new(config, provider);
+        let mut hello = create_test_hello();
+        hello.supported_transports = vec!["WEBSOCKET".to_string()];
+
+        assert!(manager
+            .handle_hello(&hello, "sess-123".to_string(), Role::BuyerAgent)
+            .is_err());
+        assert!(manager.get_session("sess-123").is_none());
+    }
+
+    #[test]
+    fn test_transport_negotiation_ws_only_tbc() {
+        let provider = Arc::new(TestTimestampProvider::new(1000, 1731600000));
+        let config = SessionConfig {
+            supported_transports: vec!["WEBSOCKET".to_string()],
+            ..SessionConfig::default()
+        };
+        let manager = SessionManager::new(config, provider);
+        let hello = create_test_hello(); // HTTP only
+
+        assert!(manager
+            .handle_hello(&hello, "sess-123".to_string(), Role::BuyerAgent)
+            .is_err());
+    }
+
+    #[test]
+    fn test_timeout_jitter_spreads_expiry() {
+        let provider = Arc::new(TestTimestampProvider::new(1000, 1731600000));
+        let config = SessionConfig {
+            session_timeout_seconds: 300,
+            timeout_jitter_pct: 10,
+            timeout_jitter_seed: 42,
+            ..SessionConfig::default()
+        };
+
+        let manager = SessionManager::new(config, provider.clone());
+        let hello = create_test_hello();
+
+        let a = manager
+            .handle_hello(&hello, "sess-a".to_string(), Role::BuyerAgent)
+            .unwrap();
+        let b = manager
+            .handle_hello(&hello, "sess-b".to_string(), Role::BuyerAgent)
+            .unwrap();
+
+        for t in [a.timeout_seconds, b.timeout_seconds] {
+            assert!((270..=330).contains(&t));
+        }
+        assert_ne!(a.timeout_seconds, b.timeout_seconds);
+
+        // Deterministic for the same seed and session ID
+        let again = manager
+            .handle_hello(&hello, "sess-a".to_string(), Role::BuyerAgent)
+            .unwrap();
+        assert_eq!(again.timeout_seconds, a.timeout_seconds);
+
+        // Advance to just past the earlier expiry: only that session is cleaned
+        let (first, second) = if a.timeout_seconds < b.timeout_seconds {
+            ("sess-a", "sess-b")
+        } else {
+            ("sess-b", "sess-a")
+        };
+        provider.advance(a.timeout_seconds.min(b.timeout_seconds) + 1);
+        manager.cleanup_expired();
+        assert!(manager.get_session(first).is_none());
+        assert!(manager.get_session(second).is_some());
+    }
+
+    #[test]
+    fn test_timeout_without_jitter() {
+        let provider = Arc::new(TestTimestampProvider::new(1000, 1731600000));
+        let manager = SessionManager::new(SessionConfig::default(), provider);
+        let session = manager
+            .handle_hello(&create_test_hello(), "sess-123".to_string(), Role::BuyerAgent)
+            .unwrap();
+        assert_eq!(session.timeout_seconds, 300);
+    }
+
+    #[test]
+    fn test_abnormal_close_counted() {
+        let provider = Arc::new(TestTimestampProvider::new(1000, 1731600000));
+        let manager = SessionManager::new(SessionConfig::default(), provider);
+        let hello = create_test_hello();
+
+        manager.handle_hello(&hello, "sess-1".to_string(), Role::BuyerAgent).unwrap();
+        manager.handle_hello(&hello, "sess-2".to_string(), Role::BuyerAgent).unwrap();
+
+        let closed = manager
+            .close_session_with_reason("sess-1", &CloseReason::ClientShutdown)
+            .unwrap();
+        assert_eq!(closed.unwrap().session_id, "sess-1");
+        assert_eq!(manager.close_stats(), CloseStats { normal: 1, abnormal: 0 });
+
+        manager
+            .close_session_with_reason("sess-2", &CloseReason::ProtocolError)
+            .unwrap();
+        assert_eq!(manager.close_stats(), CloseStats { normal: 1, abnormal: 1 });
+        assert!(manager.get_session("sess-2").is_none());
+    }
+
+    fn resume_hello() -> HelloPayload {
+        HelloPayload {
+            resume: true,
+            ..create_test_hello()
+        }
+    }
+
+    #[test]
+    fn test_resume_keeps_cache_and_created_time() {
+        let provider = Arc::new(TestTimestampProvider::new(1000, 1731600000));
+        let manager = SessionManager::new(SessionConfig::default(), provider.clone());
+
+        manager
+            .handle_hello(&create_test_hello(), "sess-123".to_string(), Role::BuyerAgent)
+            .unwrap();
+        manager.record_message("sess-123", "msg-1").unwrap();
+
+        provider.advance(120);
+        let resumed = manager
+            .handle_hello(&resume_hello(), "sess-123".to_string(), Role::BuyerAgent)
+            .unwrap();
+
+        assert_eq!(resumed.created_mono, 1000);
+        assert_eq!(resumed.last_activity_mono, 1120);
+        assert_eq!(manager.get_session("sess-123").unwrap().last_activity_mono, 1120);
+        assert!(manager.is_duplicate_message("sess-123", "msg-1"));
+
+        // A non-resuming HELLO still starts over
+        manager
+            .handle_hello(&create_test_hello(), "sess-123".to_string(), Role::BuyerAgent)
+            .unwrap();
+        assert!(!manager.is_duplicate_message("sess-123", "msg-1"));
+    }
+
+    #[test]
+    fn test_resume_unknown_session() {
+        let provider = Arc::new(TestTimestampProvider::new(1000, 1731600000));
+        let manager = SessionManager::new(SessionConfig::default(), provider);
+
+        let err = manager
+            .handle_hello(&resume_hello(), "sess-404".to_string(), Role::BuyerAgent)
+            .unwrap_err();
+        assert!(err.contains("send a fresh HELLO"));
+        assert!(manager.get_session("sess-404").is_none());
+
+        manager
+            .handle_hello(&create_test_hello(), "sess-123".to_string(), Role::BuyerAgent)
+            .unwrap();
+        let mut other = resume_hello();
+        other.agent_id = "buyer://mallory".to_string();
+        assert!(manager
+            .handle_hello(&other, "sess-123".to_string(), Role::BuyerAgent)
+            .is_err());
+    }
+
+    #[test]
+    fn test_resume_after_timeout() {
+        let provider = Arc::new(TestTimestampProvider::new(1000, 1731600000));
+        let config = SessionConfig {
+            session_timeout_seconds: 60,
+            ..SessionConfig::default()
+        };
+        let manager = SessionManager::new(config, provider.clone());
+
+        manager
+            .handle_hello(&create_test_hello(), "sess-123".to_string(), Role::BuyerAgent)
+            .unwrap();
+        manager.record_message("sess-123", "msg-1").unwrap();
+
+        // Not yet cleaned up, but already past its timeout
+        provider.advance(61);
+        let err = manager
+            .handle_hello(&resume_hello(), "sess-123".to_string(), Role::BuyerAgent)
+            .unwrap_err();
+        assert!(err.contains("Session expired"));
+        assert!(manager.get_session("sess-123").is_none());
+        assert!(!manager.is_duplicate_message("sess-123", "msg-1"));
+    }
+
+    #[test]
+    fn test_message_cache_ttl_eviction() {
+        let provider = Arc::new(TestTimestampProvider::new(1000, 1731600000));
+        let config = SessionConfig {
+            session_timeout_seconds: 3600,
+            message_cache_ttl_seconds: 60,
+            ..SessionConfig::default()
+        };
+        let manager = SessionManager::new(config, provider.clone());
+        manager
+            .handle_hello(&create_test_hello(), "sess-123".to_string(), Role::BuyerAgent)
+            .unwrap();
+
+        for i in 0..100 {
+            manager.record_message("sess-123", &format!("old-{}", i)).unwrap();
+        }
+        provider.advance(45);
+        for i in 0..100 {
+            manager.record_message("sess-123", &format!("new-{}", i)).unwrap();
+        }
+
+        // Still inside the TTL for both batches
+        manager.cleanup_expired();
+        assert_eq!(manager.cached_message_count("sess-123"), 200);
+        assert!(manager.is_duplicate_message("sess-123", "old-0"));
+
+        // Past the TTL for the first batch only
+        provider.advance(30);
+        manager.cleanup_expired();
+        assert_eq!(manager.cached_message_count("sess-123"), 100);
+        assert!(!manager.is_duplicate_message("sess-123", "old-0"));
+        assert!(!manager.is_duplicate_message("sess-123", "old-99"));
+        assert!(manager.is_duplicate_message("sess-123", "new-0"));
+        assert!(manager.is_duplicate_message("sess-123", "new-99"));
+    }
+
+    #[test]
+    fn test_message_cache_size_cap() {
+        let provider = Arc::new(TestTimestampProvider::new(1000, 1731600000));
+        let config = SessionConfig {
+            max_cached_messages_per_session: 3,
+            ..SessionConfig::default()
+        };
+        let manager = SessionManager::new(config, provider.clone());
+        manager
+            .handle_hello(&create_test_hello(), "sess-123".to_string(), Role::BuyerAgent)
+            .unwrap();
+
+        for i in 0..5 {
+            manager.record_message("sess-123", &format!("msg-{}", i)).unwrap();
+            provider.advance(1);
+        }
+        // Re-recording a cached ID does not count twice
+        manager.record_message("sess-123", "msg-4").unwrap();
+
+        assert_eq!(manager.cached_message_count("sess-123"), 3);
+        assert!(!manager.is_duplicate_message("sess-123", "msg-1"));
+        for id in ["msg-2", "msg-3", "msg-4"] {
+            assert!(manager.is_duplicate_message("sess-123", id));
+        }
+    }
+
+    #[test]
+    fn test_sequence_in_order() {
+        let provider = Arc::new(TestTimestampProvider::new(1000, 1731600000));
+        let manager = SessionManager::new(SessionConfig::default(), provider);
+        manager
+            .handle_hello(&create_test_hello(), "sess-123".to_string(), Role::BuyerAgent)
+            .unwrap();
+
+        for (seq, msg_id) in [(1, "msg-1"), (2, "msg-2"), (5, "msg-5")] {
+            manager.check_sequence("sess-123", msg_id, seq).unwrap();
+            manager.record_message("sess-123", msg_id).unwrap();
+        }
+        assert_eq!(manager.get_session("sess-123").unwrap().last_seq, 5);
+
+        // Unsequenced envelopes bypass the check
+        manager.check_sequence("sess-123", "msg-x", 0).unwrap();
+        assert_eq!(manager.get_session("sess-123").unwrap().last_seq, 5);
+    }
+
+    #[test]
+    fn test_sequence_duplicate_seq() {
+        let provider = Arc::new(TestTimestampProvider::new(1000, 1731600000));
+        let manager = SessionManager::new(SessionConfig::default(), provider);
+        manager
+            .handle_hello(&create_test_hello(), "sess-123".to_string(), Role::BuyerAgent)
+            .unwrap();
+
+        manager.check_sequence("sess-123", "msg-1", 1).unwrap();
+        manager.record_message("sess-123", "msg-1").unwrap();
+
+        // Retransmit of the same message is allowed through
+        manager.check_sequence("sess-123", "msg-1", 1).unwrap();
+
+        // A different message reusing the seq is a replay
+        let err = manager.check_sequence("sess-123", "msg-2", 1).unwrap_err();
+        assert!(err.contains("Sequence violation"));
+        assert_eq!(manager.get_session("sess-123").unwrap().last_seq, 1);
+    }
+
+    #[test]
+    fn test_sequence_regression() {
+        let provider = Arc::new(TestTimestampProvider::new(1000, 1731600000));
+        let manager = SessionManager::new(SessionConfig::default(), provider);
+        manager
+            .handle_hello(&create_test_hello(), "sess-123".to_string(), Role::BuyerAgent)
+            .unwrap();
+
+        manager.check_sequence("sess-123", "msg-10", 10).unwrap();
+        let err = manager.check_sequence("sess-123", "msg-9", 9).unwrap_err();
+        assert!(err.contains("seq 9 is not above last accepted 10"));
+
+        // Sequence survives a resume, so old envelopes stay rejected
+        let resume = HelloPayload {
+            resume: true,
+            ..create_test_hello()
+        };
+        manager
+            .handle_hello(&resume, "sess-123".to_string(), Role::BuyerAgent)
+            .unwrap();
+        assert!(manager.check_sequence("sess-123", "msg-3", 3).is_err());
+        manager.check_sequence("sess-123", "msg-11", 11).unwrap();
+    }
+
+    fn asset_manager(assets: &[&str]) -> SessionManager<TestTimestampProvider> {
+        let provider = Arc::new(TestTimestampProvider::new(1000, 1731600000));
+        let config = SessionConfig {
+            supported_assets: assets.iter().map(|a| a.to_string()).collect(),
+            ..SessionConfig::default()
+        };
+        SessionManager::new(config, provider)
+    }
+
+    #[test]
+    fn test_asset_negotiation_full_overlap() {
+        let manager = asset_manager(&["USDC", "DAI"]);
+        let mut hello = create_test_hello();
+        hello.supported_assets = vec!["dai".to_string(), "USDC".to_string()];
+
+        let session = manager
+            .handle_hello(&hello, "sess-123".to_string(), Role::BuyerAgent)
+            .unwrap();
+
+        // TBC spelling and preference order
+        assert_eq!(session.negotiated_assets, vec!["USDC", "DAI"]);
+
+        let welcome = TxipEnvelope::welcome(
+            "msg-welcome".to_string(),
+            "sess-123".to_string(),
+            "tbc://test".to_string(),
+            session.negotiated_tgp_version,
+            session.negotiated_chains,
+            session.negotiated_assets,
+            session.features,
+            manager.heartbeat_interval_sec(),
+            manager.now(),
+        );
+        let json = serde_json::to_value(&welcome).unwrap();
+        assert_eq!(json["payload"]["supported_assets"], serde_json::json!(["USDC", "DAI"]));
+    }
+
+    #[test]
+    fn test_asset_negotiation_partial_overlap() {
+        let manager = asset_manager(&["USDC", "DAI", "WETH"]);
+        let mut hello = create_test_hello();
+        hello.supported_assets = vec!["WETH".to_string(), "DOGE".to_string()];
+
+        let session = manager
+            .handle_hello(&hello, "sess-123".to_string(), Role::BuyerAgent)
+            .unwrap();
+        assert_eq!(session.negotiated_assets, vec!["WETH"]);
+    }
+
+    #[test]
+    fn test_asset_negotiation_no_overlap() {
+        let manager = asset_manager(&["USDC"]);
+        let mut hello = create_test_hello();
+        hello.supported_assets = vec!["DOGE".to_string()];
+
+        let err = manager
+            .handle_hello(&hello, "sess-123".to_string(), Role::BuyerAgent)
+            .unwrap_err();
+        assert!(err.contains("No compatible asset"));
+        assert!(err.contains("DOGE") && err.contains("USDC"));
+        assert!(manager.get_session("sess-123").is_none());
+    }
 }
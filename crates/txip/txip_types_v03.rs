@@ -23,6 +23,10 @@ pub struct TxipEnvelope {
     
     /// Unique message identifier (UUID v4 recommended)
     pub msg_id: String,
+
+    /// Per-session sequence number, strictly increasing (0 = unsequenced)
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub seq: u64,
     
     /// Session identifier tying related messages together
     pub session_id: String,
@@ -124,6 +128,10 @@ pub struct HelloPayload {
     pub supported_assets: Vec<String>,
     pub features: Features,
     pub auth: AuthInfo,
+    /// Resume the session named by the envelope's `session_id`, keeping its
+    /// idempotency history, instead of starting a fresh one
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub resume: bool,
 }
 
 /// Feature flags
@@ -160,8 +168,13 @@ pub struct WelcomePayload {
     pub session_id: String,
     pub negotiated_tgp_version: String,
     pub negotiated_chains: Vec<ChainId>,
+    /// Assets the TBC will settle for this session (intersection with HELLO)
+    #[serde(default)]
+    pub supported_assets: Vec<String>,
     pub negotiated_features: Features,
-    pub heartbeat_interval_sec: u64,
+    /// Omitted when the TBC does not require heartbeats
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heartbeat_interval_sec: Option<u64>,
 }
 
 /// HEARTBEAT control message
@@ -183,9 +196,29 @@ pub enum CloseReason {
     IdleTimeout,
     ClientShutdown,
     ProtocolError,
+    /// Client hit an unrecoverable internal error
+    ClientError,
     Other,
 }
 
+impl CloseReason {
+    /// Numeric reason code (WebSocket close-code numbering)
+    pub fn code(&self) -> u16 {
+        match self {
+            CloseReason::ClientShutdown => 1000,
+            CloseReason::IdleTimeout => 1001,
+            CloseReason::ProtocolError => 1002,
+            CloseReason::ClientError => 1011,
+            CloseReason::Other => 4000,
+        }
+    }
+
+    /// Orderly close; anything else should be alerted on
+    pub fn is_normal(&self) -> bool {
+        matches!(self, CloseReason::ClientShutdown | CloseReason::IdleTimeout)
+    }
+}
+
 /// TGP message payload
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TgpPayload {
@@ -216,6 +249,18 @@ pub enum ErrorCode {
     TxipRateLimited,
     TxipUpstreamUnavailable,
     TxipMalformedTgpPayload,
+    /// A resuming HELLO named a session that is gone or expired; the client
+    /// must send a fresh HELLO
+    TxipSessionNotResumable,
+    /// `seq` was not above the last sequence number accepted on the session
+    TxipSequenceViolation,
+    /// A HELLO shared no TGP version, chain, transport or asset with the TBC;
+    /// resending the same HELLO will fail again
+    TxipNegotiationFailed,
+}
+
+fn is_zero(seq: &u64) -> bool {
+    *seq == 0
 }
 
 impl TxipEnvelope {
@@ -233,6 +278,7 @@ impl TxipEnvelope {
         Self {
             txip_version: TXIP_VERSION.to_string(),
             msg_id,
+            seq: 0,
             session_id,
             direction,
             role,
@@ -306,13 +352,14 @@ impl TxipEnvelope {
         tbc_id: String,
         negotiated_tgp_version: String,
         negotiated_chains: Vec<ChainId>,
+        supported_assets: Vec<String>,
         negotiated_features: Features,
-        heartbeat_interval_sec: u64,
+        heartbeat_interval_sec: Option<u64>,
         timestamp: TripleTimestamp,
     ) -> Self {
         Self::new(
             msg_id,
-            session_id,
+            session_id.clone(),
             Direction::TbcToClient,
             Role::Tbc,
             MessageType::Control,
@@ -323,6 +370,7 @@ impl TxipEnvelope {
                 session_id,
                 negotiated_tgp_version,
                 negotiated_chains,
+                supported_assets,
                 negotiated_features,
                 heartbeat_interval_sec,
             })),
@@ -335,6 +383,12 @@ impl TxipEnvelope {
         self
     }
 
+    /// Set the per-session sequence number
+    pub fn with_seq(mut self, seq: u64) -> Self {
+        self.seq = seq;
+        self
+    }
+
     /// Add TGP event type
     pub fn with_tgp_type(mut self, tgp_type: String) -> Self {
         self.tgp_type = Some(tgp_type);
@@ -109,6 +109,23 @@ impl std::fmt::Display for ValidationError {
 
 impl std::error::Error for ValidationError {}
 
+// ============================================================================
+// CrossChainLink - Buyer-chain commit <-> seller-chain order
+// ============================================================================
+
+/// Which buyer-chain commit tx funds which seller-chain order.
+///
+/// Recorded by `TestContext::commit`; `accept_txid` is filled in by
+/// `TestContext::accept`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrossChainLink {
+    pub order: HarnessOrderId,
+    pub buyer_chain: MockChainId,
+    pub commit_txid: String,
+    pub seller_chain: MockChainId,
+    pub accept_txid: Option<String>,
+}
+
 // ============================================================================
 // TestContext - Hybrid multi-chain + single-driver orchestrator
 // ============================================================================
@@ -140,6 +157,9 @@ pub struct TestContext {
     /// Mock chains for TXID + block height simulation
     chains: HashMap<MockChainId, MockChain>,
 
+    /// Buyer-chain commit of every order placed through `commit`
+    links: HashMap<HarnessOrderId, CrossChainLink>,
+
     /// Session + order counters for reproducible test IDs
     session_counter: u64,
     order_counter: u64,
@@ -168,6 +188,7 @@ impl TestContext {
             time,
             engines: HashMap::new(),
             chains: HashMap::new(),
+            links: HashMap::new(),
             session_counter: 1,
             order_counter: 1,
         }
@@ -376,6 +397,17 @@ impl TestContext {
             commit_tx.txid.clone(),
         );
 
+        self.links.insert(
+            order.clone(),
+            CrossChainLink {
+                order: order.clone(),
+                buyer_chain,
+                commit_txid: commit_tx.txid,
+                seller_chain,
+                accept_txid: None,
+            },
+        );
+
         order
     }
 
@@ -387,7 +419,11 @@ impl TestContext {
 
         let oid = order.to_bytes().unwrap();
         let engine = self.engines.get_mut(&seller_chain).unwrap();
-        let _ = engine.seller_accept(&oid, tx.txid);
+        if engine.seller_accept(&oid, tx.txid.clone()).is_ok() {
+            if let Some(link) = self.links.get_mut(order) {
+                link.accept_txid = Some(tx.txid);
+            }
+        }
     }
 
     pub fn fulfill(&mut self, order: &HarnessOrderId, seller_chain: MockChainId) {
@@ -444,6 +480,72 @@ impl TestContext {
         engine.get_receipt(&oid).map(|m| m.to_public_receipt())
     }
 
+    // =========================================================================
+    // Cross-Chain Verification
+    // =========================================================================
+
+    pub fn cross_chain_link(&self, order: &HarnessOrderId) -> Option<&CrossChainLink> {
+        self.links.get(order)
+    }
+
+    /// Confirm both legs of `order` exist and the seller-chain receipt
+    /// points back at the buyer-chain commit.
+    pub fn verify_cross_chain(&self, order: &HarnessOrderId) -> Result<(), String> {
+        let link = self
+            .links
+            .get(order)
+            .ok_or_else(|| format!("{}: no cross-chain link recorded", order.as_str()))?;
+        let accept_txid = link.accept_txid.as_deref().ok_or_else(|| {
+            format!(
+                "{}: not accepted on seller chain {}",
+                order.as_str(),
+                link.seller_chain.0
+            )
+        })?;
+
+        let receipt = self.receipt(order, link.seller_chain).ok_or_else(|| {
+            format!(
+                "{}: no receipt on seller chain {}",
+                order.as_str(),
+                link.seller_chain.0
+            )
+        })?;
+
+        if !receipt.is_cross_chain() {
+            return Err(format!(
+                "{}: receipt is not cross-chain (both legs on chain {})",
+                order.as_str(),
+                receipt.seller_chain_id
+            ));
+        }
+        if receipt.buyer_chain_id != link.buyer_chain.0 {
+            return Err(format!(
+                "{}: receipt buyer chain {} != commit chain {}",
+                order.as_str(),
+                receipt.buyer_chain_id,
+                link.buyer_chain.0
+            ));
+        }
+        if receipt.buyer_commit_txid != link.commit_txid {
+            return Err(format!(
+                "{}: receipt commit tx {} != buyer-chain commit {}",
+                order.as_str(),
+                receipt.buyer_commit_txid,
+                link.commit_txid
+            ));
+        }
+        if receipt.seller_accept_txid != accept_txid {
+            return Err(format!(
+                "{}: receipt accept tx {} != seller-chain accept {}",
+                order.as_str(),
+                receipt.seller_accept_txid,
+                accept_txid
+            ));
+        }
+
+        Ok(())
+    }
+
     // =========================================================================
     // State Queries
     // =========================================================================
@@ -532,6 +634,37 @@ mod tests {
         assert_eq!(receipt.order_amount, 1000);
     }
 
+    #[test]
+    fn cross_chain_link_recorded_and_verified() {
+        let mut ctx = TestContext::default();
+
+        let buyer_chain = ctx.chain(MockChainId(1));
+        let seller_chain = ctx.chain(MockChainId(369));
+
+        let order = ctx.commit(buyer_chain, seller_chain, 1000, "bridge");
+        assert!(
+            ctx.verify_cross_chain(&order).is_err(),
+            "unaccepted order has only one leg"
+        );
+
+        ctx.advance_time(5);
+        ctx.accept(&order, seller_chain);
+        ctx.advance_time(10);
+        ctx.fulfill(&order, seller_chain);
+        ctx.advance_time(20);
+        ctx.claim(&order, seller_chain);
+
+        let link = ctx.cross_chain_link(&order).expect("link must be recorded");
+        assert_eq!(link.buyer_chain, buyer_chain);
+        assert_eq!(link.seller_chain, seller_chain);
+        assert!(link.accept_txid.is_some());
+
+        let receipt = ctx.receipt(&order, seller_chain).unwrap();
+        assert_eq!(receipt.buyer_commit_txid, link.commit_txid);
+
+        ctx.verify_cross_chain(&order).expect("cross-chain link should verify");
+    }
+
     #[test]
     fn single_chain_via_driver() {
         let mut ctx = TestContext::default();
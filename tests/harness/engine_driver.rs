@@ -178,12 +178,14 @@ impl EngineDriver {
         });
         
         self.tracer.record(TraceEvent::StateTransition {
-            order_id,
+            order_id: order_id.clone(),
             from: state_before,
             to: state_after,
             timestamp: self.time.current_triple(),
         });
         
+        self.record_withdrawal_lock(order_id, true, "accept");
+        
         Ok(())
     }
     
@@ -210,7 +212,7 @@ impl EngineDriver {
         let state_after = self.engine.get_state(&order_id_bytes)
             .map_err(EngineError::from)?;
         
-        let is_late = state_after == EscrowState::FulfillmentExpired;
+        let is_late = state_after == EscrowState::LateFulfilled;
         
         self.tracer.record(TraceEvent::SellerFulfilled {
             order_id: order_id.clone(),
@@ -226,6 +228,10 @@ impl EngineDriver {
             timestamp: self.time.current_triple(),
         });
         
+        if is_late {
+            self.record_withdrawal_lock(order_id.clone(), true, "late_fulfill");
+        }
+        
         self.tracer.record(TraceEvent::ReceiptCreated {
             order_id,
             timestamp: self.time.current_triple(),
@@ -251,7 +257,7 @@ impl EngineDriver {
         let state_before = self.engine.get_state(&order_id_bytes)
             .map_err(EngineError::from)?;
         
-        let amount = self.engine.seller_claim(&order_id_bytes, claim_txid.clone().into_string())
+        let amount = self.engine.seller_claim_amount(&order_id_bytes, claim_txid.clone().into_string())
             .map_err(EngineError::from)?;
         
         let state_after = self.engine.get_state(&order_id_bytes)
@@ -428,6 +434,18 @@ impl EngineDriver {
             timestamp: self.time.current_triple(),
         });
         
+        // Expired fulfillment windows free the buyer's funds again
+        for (order_id_bytes, _deadline) in self.engine.sweep_expirations() {
+            let order_id = HarnessOrderId::from_bytes(order_id_bytes);
+            self.tracer.record(TraceEvent::StateTransition {
+                order_id: order_id.clone(),
+                from: EscrowState::SellerAccepted,
+                to: EscrowState::FulfillmentExpired,
+                timestamp: self.time.current_triple(),
+            });
+            self.record_withdrawal_lock(order_id, false, "expiry");
+        }
+        
         debug_assert_eq!(
             self.time.current_mono(),
             self.engine.current_mono,
@@ -445,6 +463,15 @@ impl EngineDriver {
         self.advance_time(Duration::from_secs(secs));
     }
     
+    fn record_withdrawal_lock(&mut self, order_id: HarnessOrderId, locked: bool, reason: &str) {
+        self.tracer.record(TraceEvent::WithdrawalLockChanged {
+            order_id,
+            locked,
+            reason: reason.into(),
+            timestamp: self.time.current_triple(),
+        });
+    }
+    
     pub fn current_time(&self) -> HarnessTimestamp {
         self.time.current_timestamp()
     }
@@ -532,6 +559,15 @@ impl EngineDriver {
         }
     }
     
+    // ========================================================================
+    // Engine Access
+    // ========================================================================
+    
+    /// Read-only view of the wrapped engine, for engine-wide invariants
+    pub fn engine(&self) -> &CoreProverEngine {
+        &self.engine
+    }
+    
     // ========================================================================
     // Trace Access
     // ========================================================================
@@ -586,4 +622,38 @@ mod tests {
         assert_eq!(digital.timing.fulfillment_window_secs, 300);
         assert!(!digital.enables_late_discount);
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn late_fulfillment_relocks_withdrawal() {
+        let mut driver = EngineDriver::new(DriverConfig::default());
+        let profile = driver.profile_for("pizza");
+        
+        let order_id = driver
+            .buyer_commit(
+                CommitParams::new("buyer".into(), "seller".into(), 1000).with_profile(profile),
+            )
+            .unwrap();
+        driver.seller_accept(order_id.clone(), None).unwrap();
+        
+        // Past the 3600s fulfillment window
+        driver.advance_time(Duration::from_secs(3601));
+        assert_eq!(driver.get_state(&order_id).unwrap(), EscrowState::FulfillmentExpired);
+        
+        driver.seller_fulfill(order_id.clone(), None).unwrap();
+        
+        let locks: Vec<(bool, &str)> = driver
+            .get_trace()
+            .iter()
+            .filter_map(|e| match e {
+                TraceEvent::WithdrawalLockChanged { order_id: id, locked, reason, .. }
+                    if *id == order_id => Some((*locked, reason.as_str())),
+                _ => None,
+            })
+            .collect();
+        
+        assert_eq!(
+            locks,
+            vec![(true, "accept"), (false, "expiry"), (true, "late_fulfill")]
+        );
+    }
+}
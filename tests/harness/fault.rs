@@ -9,6 +9,7 @@
 //!   - skew timestamps (mono + unix)
 //!   - force deadline violations
 //!   - inject random noise events
+//!   - drop or delay every message of one phase (network loss/reordering)
 //!
 //! Deterministic when a seed is provided.
 //!
@@ -31,6 +32,28 @@ pub enum FaultType {
     CorruptChainIds(f32),         // % chance per event
     ForceLateFulfillment,         // modify deadlines
     InjectNoise(f32),             // % chance to insert fake events
+
+    /// Lose every message of one phase between buyer and controller.
+    /// `match_phase` is the call name, e.g. "SellerAccept".
+    ///
+    /// Invariants are checked against what the engine received, so a
+    /// dropped message is not a violation: a dropped SellerClaim (the
+    /// SETTLE) leaves the escrow un-settled and without a final receipt,
+    /// and later calls that depended on the lost one fail as invalid.
+    DropMessage { match_phase: String },
+
+    /// Deliver every message of one phase `by_secs` late. Delayed messages
+    /// are re-ordered behind anything now earlier, so a delay past a window
+    /// fails the call the same way a slow network would.
+    DelayMessage { match_phase: String, by_secs: u64 },
+}
+
+impl FaultType {
+    /// Whether this fault acts on individual messages (honored by replay)
+    /// rather than mutating a recorded trace
+    pub fn is_message_fault(&self) -> bool {
+        matches!(self, FaultType::DropMessage { .. } | FaultType::DelayMessage { .. })
+    }
 }
 
 /// A single applied fault (log entry)
@@ -137,6 +160,25 @@ impl FaultInjector {
                         description: format!("Injected ~{}% noise events", p * 100.0),
                     });
                 }
+
+                FaultType::DropMessage { match_phase } => {
+                    trace.events.retain(|evt| kind_phase(&evt.kind) != match_phase);
+                    log.push(FaultApplied {
+                        fault: fault.clone(),
+                        description: format!("Dropped every {} message", match_phase),
+                    });
+                }
+
+                FaultType::DelayMessage { match_phase, by_secs } => {
+                    self.delay_message(&mut trace, match_phase, *by_secs);
+                    log.push(FaultApplied {
+                        fault: fault.clone(),
+                        description: format!(
+                            "Delayed every {} message by {}s",
+                            match_phase, by_secs
+                        ),
+                    });
+                }
             }
         }
 
@@ -263,6 +305,17 @@ impl FaultInjector {
         trace.events = out;
     }
 
+    /// Shift matching events later, then restore time order
+    fn delay_message(&mut self, trace: &mut HarnessEventTrace, phase: &str, by_secs: u64) {
+        for evt in trace.events.iter_mut() {
+            if kind_phase(&evt.kind) == phase {
+                evt.offset_mono += by_secs;
+                evt.offset_unix += by_secs;
+            }
+        }
+        trace.events.sort_by_key(|evt| evt.offset_mono);
+    }
+
     // =========================================================================
     // Helper: mutate a string to make it invalid
     // =========================================================================
@@ -274,4 +327,19 @@ impl FaultInjector {
         }
         String::from_utf8_lossy(&bytes).to_string()
     }
-}
\ No newline at end of file
+}
+
+/// Phase name of a recorded event, as matched by message faults
+fn kind_phase(kind: &HarnessEventKind) -> &'static str {
+    match kind {
+        HarnessEventKind::AdvanceTime { .. } => "AdvanceTime",
+        HarnessEventKind::BuyerCommit { .. } => "BuyerCommit",
+        HarnessEventKind::SellerAccept { .. } => "SellerAccept",
+        HarnessEventKind::SellerFulfill { .. } => "SellerFulfill",
+        HarnessEventKind::SellerClaim { .. } => "SellerClaim",
+        HarnessEventKind::SellerRefund { .. } => "SellerRefund",
+        HarnessEventKind::BuyerWithdraw { .. } => "BuyerWithdraw",
+        HarnessEventKind::TimedRelease { .. } => "TimedRelease",
+        HarnessEventKind::UpdateState { .. } => "UpdateState",
+    }
+}
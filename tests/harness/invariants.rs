@@ -0,0 +1,246 @@
+// ============================================================================
+// tests/harness/invariants.rs
+// CoreProver v0.3 Test Harness - Engine-wide Invariants
+// ============================================================================
+//
+// ModelChecker::validate_transition only looks at one state change at a
+// time. The invariants here look at every escrow the engine knows about and
+// check properties of its whole history.
+//
+//   * value_conservation - per order, claimed + refunded + withdrawn never
+//     exceeds the committed amount, and the order settles at most once
+//
+// Invariants read each order's EscrowEvent log, so a hand-built log can be
+// checked with the same code that checks a live engine.
+//
+// ============================================================================
+
+use coreprover_service::engine::CoreProverEngine;
+use coreprover_service::types::{DisputeOutcome, EscrowEvent};
+
+// ============================================================================
+// Invariant / InvariantResult
+// ============================================================================
+
+/// A named check over every escrow in an engine.
+#[derive(Debug, Clone, Copy)]
+pub struct Invariant {
+    pub name: &'static str,
+    check: fn(&[u8; 32], &[EscrowEvent]) -> Vec<String>,
+}
+
+/// Outcome of one invariant over one engine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvariantResult {
+    pub invariant: &'static str,
+    pub violations: Vec<String>,
+}
+
+impl InvariantResult {
+    pub fn passed(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+impl Invariant {
+    /// Check every order whose escrow is still held by `engine`. Pruned
+    /// escrows have no event log left and are skipped.
+    pub fn check(&self, engine: &CoreProverEngine) -> InvariantResult {
+        let mut seen: Vec<[u8; 32]> = Vec::new();
+        for event in engine.all_events() {
+            if !seen.contains(&event.order_id) {
+                seen.push(event.order_id);
+            }
+        }
+
+        let violations = seen
+            .iter()
+            .filter_map(|id| engine.get_events(id).ok().map(|log| (self.check)(id, log)))
+            .flatten()
+            .collect();
+
+        InvariantResult {
+            invariant: self.name,
+            violations,
+        }
+    }
+
+    /// Check one order's event log.
+    pub fn check_log(&self, order_id: &[u8; 32], log: &[EscrowEvent]) -> InvariantResult {
+        InvariantResult {
+            invariant: self.name,
+            violations: (self.check)(order_id, log),
+        }
+    }
+}
+
+// ============================================================================
+// value_conservation
+// ============================================================================
+
+/// Claimed + refunded + withdrawn <= committed, and at most one settlement
+/// per order.
+pub fn value_conservation() -> Invariant {
+    Invariant {
+        name: "value_conservation",
+        check: check_value_conservation,
+    }
+}
+
+/// Walks the log and tallies what each settling event paid out. A settling
+/// event pays whatever the partial claims left behind; withdrawals count in
+/// full, since the seller's penalty comes out of the buyer's commitment.
+fn check_value_conservation(order_id: &[u8; 32], log: &[EscrowEvent]) -> Vec<String> {
+    let order = super::hex_encode(&order_id[24..]);
+    let mut violations = Vec::new();
+
+    let Some(committed) = log.iter().find_map(|e| match e {
+        EscrowEvent::BuyerCommitted { amount, .. } => Some(*amount),
+        _ => None,
+    }) else {
+        return vec![format!("order {}: event log has no BuyerCommitted", order)];
+    };
+
+    let mut partial: u64 = 0;
+    let mut claimed: u64 = 0;
+    let mut refunded: u64 = 0;
+    let mut withdrawn: u64 = 0;
+    let mut settlements: Vec<&'static str> = Vec::new();
+
+    for event in log {
+        let remaining = committed.saturating_sub(partial);
+        match event {
+            EscrowEvent::SellerClaimedPartial { amount, .. } => {
+                partial = partial.saturating_add(*amount);
+                claimed = claimed.saturating_add(*amount);
+                if partial >= committed {
+                    settlements.push("SellerClaimedPartial");
+                }
+            }
+            EscrowEvent::SellerClaimed { .. } => {
+                claimed = claimed.saturating_add(remaining);
+                settlements.push("SellerClaimed");
+            }
+            EscrowEvent::SellerRefunded { .. } => {
+                refunded = refunded.saturating_add(remaining);
+                settlements.push("SellerRefunded");
+            }
+            EscrowEvent::DisputeResolved { outcome, .. } => {
+                match outcome {
+                    DisputeOutcome::PaySeller => claimed = claimed.saturating_add(remaining),
+                    DisputeOutcome::RefundBuyer => refunded = refunded.saturating_add(remaining),
+                }
+                settlements.push("DisputeResolved");
+            }
+            EscrowEvent::BuyerWithdrew { .. } => {
+                withdrawn = withdrawn.saturating_add(remaining);
+                settlements.push("BuyerWithdrew");
+            }
+            EscrowEvent::BuyerReclaimed { .. } => {
+                withdrawn = withdrawn.saturating_add(remaining);
+                settlements.push("BuyerReclaimed");
+            }
+            _ => {}
+        }
+    }
+
+    let paid = claimed.saturating_add(refunded).saturating_add(withdrawn);
+    if paid > committed {
+        violations.push(format!(
+            "order {}: claimed {} + refunded {} + withdrawn {} = {} exceeds committed {}",
+            order, claimed, refunded, withdrawn, paid, committed
+        ));
+    }
+    if settlements.len() > 1 {
+        violations.push(format!(
+            "order {}: settled {} times: {:?}",
+            order,
+            settlements.len(),
+            settlements
+        ));
+    }
+
+    violations
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coreprover_service::types::PaymentProfile;
+
+    fn claimed_order() -> (CoreProverEngine, [u8; 32]) {
+        let mut engine = CoreProverEngine::new(1, 12, 1_700_000_000);
+        let id = engine
+            .buyer_commit(
+                "buyer".into(),
+                "seller".into(),
+                1_000,
+                PaymentProfile::pizza_delivery(),
+                1,
+                "0xcommit".into(),
+            )
+            .unwrap();
+        engine.seller_accept(&id, "0xaccept".into()).unwrap();
+        engine.seller_fulfill(&id, "0xfulfill".into()).unwrap();
+        engine.seller_claim(&id, "0xclaim".into()).unwrap();
+        (engine, id)
+    }
+
+    #[test]
+    fn value_conservation_holds_for_engine_lifecycle() {
+        let (engine, _) = claimed_order();
+        let result = value_conservation().check(&engine);
+        assert!(result.passed(), "{:?}", result.violations);
+    }
+
+    #[test]
+    fn value_conservation_catches_double_settlement() {
+        let (engine, id) = claimed_order();
+
+        // A buggy engine that also refunds the buyer after the seller claimed
+        let mut log = engine.get_events(&id).unwrap().to_vec();
+        log.push(EscrowEvent::SellerRefunded {
+            txid: "0xrefund".into(),
+            block_height: 13,
+            at_mono: log.last().unwrap().at_mono(),
+        });
+
+        let result = value_conservation().check_log(&id, &log);
+        assert!(!result.passed());
+        assert!(
+            result.violations[0].contains("refunded 1000"),
+            "{:?}",
+            result.violations
+        );
+        assert!(result.violations[0].contains("exceeds committed 1000"));
+        assert!(result.violations[1].contains("settled 2 times"));
+    }
+
+    #[test]
+    fn partial_claims_count_toward_committed() {
+        let (engine, id) = claimed_order();
+        let mut log = engine.get_events(&id).unwrap()[..3].to_vec();
+        for amount in [400, 600] {
+            log.push(EscrowEvent::SellerClaimedPartial {
+                txid: format!("0xpartial{}", amount),
+                amount,
+                block_height: 13,
+                at_mono: 0,
+            });
+        }
+        assert!(value_conservation().check_log(&id, &log).passed());
+
+        log.push(EscrowEvent::SellerClaimed {
+            txid: "0xclaim".into(),
+            block_height: 14,
+            at_mono: 0,
+        });
+        let result = value_conservation().check_log(&id, &log);
+        assert_eq!(result.violations.len(), 1, "{:?}", result.violations);
+        assert!(result.violations[0].contains("settled 2 times"));
+    }
+}
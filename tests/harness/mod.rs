@@ -26,6 +26,7 @@ pub mod trace_source;
 pub mod faults;
 pub mod invariants;
 pub mod replay;
+pub mod simulate;
 
 // Re-export for convenience
 pub use engine_driver::EngineDriver;
@@ -33,6 +34,7 @@ pub use trace_source::{TraceSource, TraceEvent, TraceFrame};
 pub use faults::{Fault, FaultType};
 pub use invariants::{Invariant, InvariantResult};
 pub use replay::{ReplayController, ReplayConfig};
+pub use simulate::simulate;
 
 // ============================================================================
 // Shared harness types
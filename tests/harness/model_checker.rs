@@ -96,7 +96,10 @@ impl ModelCheckReport {
 // ModelChecker
 // ============================================================================
 
-pub struct ModelChecker;
+pub struct ModelChecker {
+    /// Engine-wide invariants run by `check_invariants`
+    invariants: Vec<Invariant>,
+}
 
 impl ModelChecker {
     pub fn check(trace: &EscrowTrace, snapshot: &EngineSnapshot) -> ModelCheckReport {
@@ -241,16 +244,37 @@ impl ModelChecker {
 
 use coreprover_types_v03::EscrowState;
 use crate::harness::engine_driver::EngineDriver;
+use crate::harness::invariants::{self, Invariant};
+use coreprover_service::engine::CoreProverEngine;
 
 impl ModelChecker {
     /// Empty model checker (no validation)
     pub fn new() -> Self {
-        ModelChecker
+        ModelChecker { invariants: Vec::new() }
     }
 
     /// Full v0.3 rule set (this version)
     pub fn new_with_v03_rules() -> Self {
-        ModelChecker
+        ModelChecker {
+            invariants: vec![invariants::value_conservation()],
+        }
+    }
+
+    /// Run every configured invariant over all escrows in `engine`
+    pub fn check_invariants(&self, engine: &CoreProverEngine) -> Result<(), String> {
+        let violations: Vec<String> = self
+            .invariants
+            .iter()
+            .map(|inv| inv.check(engine))
+            .filter(|r| !r.passed())
+            .map(|r| format!("{}: {}", r.invariant, r.violations.join("; ")))
+            .collect();
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("invariant violations: {}", violations.join(" | ")))
+        }
     }
 
     /// Validate transition (standalone)
@@ -264,10 +288,43 @@ impl ModelChecker {
             // Allowed:
             (EscrowState::BuyerCommitted, EscrowState::SellerAccepted) => Ok(()),
             (EscrowState::SellerAccepted, EscrowState::SellerFulfilled) => Ok(()),
+            (EscrowState::SellerAccepted, EscrowState::FulfillmentExpired) => Ok(()),
+            (EscrowState::SellerAccepted, EscrowState::LateFulfilled) => Ok(()),
+            (EscrowState::FulfillmentExpired, EscrowState::LateFulfilled) => Ok(()),
+            (EscrowState::LateFulfilled, EscrowState::SellerClaimed) => Ok(()),
+            (EscrowState::LateFulfilled, EscrowState::SellerRefunded) => Ok(()),
             (EscrowState::SellerFulfilled, EscrowState::SellerClaimed) => Ok(()),
             (EscrowState::SellerFulfilled, EscrowState::SellerRefunded) => Ok(()),
             (EscrowState::FulfillmentExpired, EscrowState::SellerClaimed) => Ok(()),
             (EscrowState::FulfillmentExpired, EscrowState::SellerRefunded) => Ok(()),
+            (EscrowState::SellerFulfilled, EscrowState::PartiallyClaimed) => Ok(()),
+            (EscrowState::LateFulfilled, EscrowState::PartiallyClaimed) => Ok(()),
+            (EscrowState::PartiallyClaimed, EscrowState::PartiallyClaimed) => Ok(()),
+            (EscrowState::PartiallyClaimed, EscrowState::SellerClaimed) => Ok(()),
+
+            (EscrowState::SellerFulfilled, EscrowState::Disputed) => Ok(()),
+            (EscrowState::LateFulfilled, EscrowState::Disputed) => Ok(()),
+            (EscrowState::Disputed, EscrowState::SellerClaimed) => Ok(()),
+            (EscrowState::Disputed, EscrowState::SellerRefunded) => Ok(()),
+
+            // Unclaimed fulfillment, buyer dispute window lapsed
+            (EscrowState::SellerFulfilled, EscrowState::BuyerReclaimed) => Ok(()),
+            (EscrowState::LateFulfilled, EscrowState::BuyerReclaimed) => Ok(()),
+
+            // Disputed funds move only by resolve_dispute
+            (EscrowState::Disputed, EscrowState::BuyerWithdrawn) => {
+                Err("invalid transition: buyer cannot withdraw a disputed escrow".into())
+            }
+
+            // Partially claimed funds belong to the seller
+            (EscrowState::PartiallyClaimed, EscrowState::BuyerWithdrawn) => {
+                Err("invalid transition: buyer cannot withdraw a partially claimed escrow".into())
+            }
+
+            // Late fulfillment re-locks the buyer's funds
+            (EscrowState::LateFulfilled, EscrowState::BuyerWithdrawn) => {
+                Err("invalid transition: withdrawal is locked after late fulfillment".into())
+            }
 
             // Buyer can withdraw anytime after window expires
             (from, EscrowState::BuyerWithdrawn) => Ok(()),
@@ -278,6 +335,7 @@ impl ModelChecker {
                 EscrowState::SellerClaimed
                     | EscrowState::SellerRefunded
                     | EscrowState::BuyerWithdrawn
+                    | EscrowState::BuyerReclaimed
             ) => Ok(()),
 
             // Everything else rejected
@@ -291,6 +349,7 @@ impl ModelChecker {
         let snapshot = driver.get_snapshot();
 
         let report = ModelChecker::check(&trace, &snapshot);
+        self.check_invariants(driver.engine())?;
 
         if report.passed {
             Ok(())
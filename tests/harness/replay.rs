@@ -0,0 +1,316 @@
+// ============================================================================
+// tests/harness/replay.rs
+// CoreProver v0.3 Test Harness - Replay Controller
+// ============================================================================
+//
+// Replays a TraceSource against a fresh CoreProverEngine and records, for
+// every frame, the outcome of the call and the checksum of the engine
+// snapshot taken right after it.
+//
+// Because the snapshot covers all escrows, receipts and clocks, two replays
+// of the same scenario must produce identical step lists. Any difference
+// points at hidden nondeterminism (map iteration order, wall-clock reads).
+// The engine has no genuinely random state, so nothing is masked.
+//
+// Message faults (FaultType::DropMessage / DelayMessage) in the config are
+// applied to the frames before delivery: dropped frames never reach the
+// engine and produce no step, delayed frames are time-shifted and re-sorted.
+// Steps and invariants therefore describe what the controller actually
+// received; a dropped SellerClaim leaves the escrow un-settled.
+//
+// ============================================================================
+
+use super::trace_source::{TraceFrame, TraceSource};
+use super::{FaultType, HResult, HarnessError, HarnessEvent};
+use coreprover_service::engine::CoreProverEngine;
+use coreprover_service::types::PaymentProfile;
+use std::collections::HashMap;
+
+// ============================================================================
+// ReplayConfig
+// ============================================================================
+
+#[derive(Debug, Clone)]
+pub struct ReplayConfig {
+    pub chain_id: u64,
+    pub block_interval_secs: u64,
+    /// Profile used for every BuyerCommit in the trace
+    pub profile: PaymentProfile,
+    /// Message faults applied on delivery; other fault types are ignored
+    pub faults: Vec<FaultType>,
+}
+
+impl Default for ReplayConfig {
+    fn default() -> Self {
+        Self {
+            chain_id: 1,
+            block_interval_secs: 12,
+            profile: PaymentProfile::pizza_delivery(),
+            faults: Vec::new(),
+        }
+    }
+}
+
+// ============================================================================
+// ReplayStep - one replayed frame
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayStep {
+    pub tx_hash: String,
+    pub outcome: Result<(), String>,
+    /// Checksum of the engine snapshot after this frame
+    pub snapshot_checksum: String,
+}
+
+// ============================================================================
+// ReplayController
+// ============================================================================
+
+#[derive(Debug, Clone, Default)]
+pub struct ReplayController {
+    pub config: ReplayConfig,
+}
+
+impl ReplayController {
+    pub fn new(config: ReplayConfig) -> Self {
+        Self { config }
+    }
+
+    /// Replay `scenario` against a fresh engine genesised at the first frame.
+    /// Engine errors are recorded in the step, not raised.
+    pub fn run(&self, scenario: &TraceSource) -> HResult<Vec<ReplayStep>> {
+        self.run_with_engine(scenario).map(|(steps, _, _)| steps)
+    }
+
+    /// Like `run`, also returning the final engine and the map from
+    /// on-chain order ids to engine order ids.
+    pub fn run_with_engine(
+        &self,
+        scenario: &TraceSource,
+    ) -> HResult<(Vec<ReplayStep>, CoreProverEngine, HashMap<[u8; 32], [u8; 32]>)> {
+        let genesis = scenario.frames.first().map(|f| f.timestamp).unwrap_or(0);
+        let mut engine =
+            CoreProverEngine::new(self.config.chain_id, self.config.block_interval_secs, genesis);
+
+        // on-chain order id -> engine order id
+        let mut ids: HashMap<[u8; 32], [u8; 32]> = HashMap::new();
+        let mut last_ts = genesis;
+        let frames = self.deliver(scenario);
+        let mut steps = Vec::with_capacity(frames.len());
+
+        for frame in &frames {
+            engine.advance_time(frame.timestamp.saturating_sub(last_ts));
+            last_ts = last_ts.max(frame.timestamp);
+
+            let outcome = self.apply(&mut engine, &mut ids, frame);
+            let snapshot = engine
+                .snapshot()
+                .map_err(|e| HarnessError::new(format!("replay: snapshot failed: {}", e)))?;
+
+            steps.push(ReplayStep {
+                tx_hash: frame.tx_hash.clone(),
+                outcome,
+                snapshot_checksum: snapshot.checksum,
+            });
+        }
+
+        Ok((steps, engine, ids))
+    }
+
+    /// Frames in the order the controller receives them once the
+    /// configured message faults are applied.
+    fn deliver(&self, scenario: &TraceSource) -> Vec<TraceFrame> {
+        let mut frames = scenario.frames.clone();
+        for fault in &self.config.faults {
+            match fault {
+                FaultType::DropMessage { match_phase } => {
+                    frames.retain(|f| f.kind.phase() != match_phase);
+                }
+                FaultType::DelayMessage { match_phase, by_secs } => {
+                    for f in frames.iter_mut().filter(|f| f.kind.phase() == match_phase) {
+                        f.timestamp += by_secs;
+                    }
+                }
+                _ => {}
+            }
+        }
+        // Stable: undelayed frames keep their capture order
+        frames.sort_by_key(|f| f.timestamp);
+        frames
+    }
+
+    /// Run `scenario` twice on fresh engines and fail at the first step
+    /// where the two runs disagree.
+    pub fn assert_deterministic(&self, scenario: &TraceSource) -> HResult<()> {
+        let first = self.run(scenario)?;
+        let second = self.run(scenario)?;
+        diff_steps(&scenario.name, &first, &second)
+    }
+
+    fn apply(
+        &self,
+        engine: &mut CoreProverEngine,
+        ids: &mut HashMap<[u8; 32], [u8; 32]>,
+        frame: &TraceFrame,
+    ) -> Result<(), String> {
+        if let HarnessEvent::BuyerCommit {
+            buyer_id,
+            seller_id,
+            amount,
+            ..
+        } = &frame.event
+        {
+            let amount = u64::try_from(*amount).map_err(|_| "amount exceeds u64".to_string())?;
+            let id = engine.buyer_commit(
+                buyer_id.clone(),
+                seller_id.clone(),
+                amount,
+                self.config.profile.clone(),
+                self.config.chain_id,
+                frame.tx_hash.clone(),
+            )
+            .map_err(|e| e.to_string())?;
+            ids.insert(frame.order_id, id);
+            return Ok(());
+        }
+
+        let id = *ids
+            .get(&frame.order_id)
+            .ok_or_else(|| format!("unknown order 0x{}", super::hex_encode(&frame.order_id)))?;
+
+        match &frame.event {
+            HarnessEvent::SellerAccept { commitment, .. } => {
+                engine.seller_accept(&id, commitment.clone())
+            }
+            HarnessEvent::SellerFulfill { proof, .. } => engine.seller_fulfill(&id, proof.clone()),
+            HarnessEvent::SellerClaim { proof, .. } => {
+                engine.seller_claim(&id, proof.clone()).map(|_| ())
+            }
+            HarnessEvent::BuyerWithdraw { .. } => engine
+                .buyer_withdraw(&id, Some(frame.tx_hash.clone()))
+                .map(|_| ()),
+            HarnessEvent::BuyerCommit { .. } | HarnessEvent::AdvanceTime { .. } => Ok(()),
+        }
+        .map_err(|e| e.to_string())
+    }
+}
+
+/// Fail at the first step where two replays of `name` disagree.
+fn diff_steps(name: &str, first: &[ReplayStep], second: &[ReplayStep]) -> HResult<()> {
+    if let Some(i) = first.iter().zip(second).position(|(a, b)| a != b) {
+        return Err(HarnessError::new(format!(
+            "replay '{}' diverged at step {} (tx {}): {:?} vs {:?}",
+            name, i, first[i].tx_hash, first[i], second[i]
+        )));
+    }
+    if first.len() != second.len() {
+        return Err(HarnessError::new(format!(
+            "replay '{}' diverged in length: {} vs {} steps",
+            name,
+            first.len(),
+            second.len()
+        )));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coreprover_service::types::EscrowState;
+
+    const PIZZA_EXPORT: &str = include_str!("../scenarios/rpc_export_pizza.json");
+
+    #[test]
+    fn pizza_replay_is_deterministic() {
+        let source = TraceSource::from_rpc_export(PIZZA_EXPORT).unwrap();
+        let controller = ReplayController::default();
+
+        controller.assert_deterministic(&source).unwrap();
+
+        let steps = controller.run(&source).unwrap();
+        assert_eq!(steps.len(), source.frames.len());
+        assert!(steps.iter().all(|s| s.outcome.is_ok()));
+    }
+
+    #[test]
+    fn divergent_runs_report_the_step() {
+        let source = TraceSource::from_rpc_export(PIZZA_EXPORT).unwrap();
+        let controller = ReplayController::default();
+
+        // Simulate a nondeterministic second run
+        let first = controller.run(&source).unwrap();
+        let mut second = first.clone();
+        second[2].snapshot_checksum = "0xdead".into();
+
+        let err = diff_steps("pizza", &first, &second).unwrap_err();
+        assert!(err.msg.contains("diverged at step 2"), "{}", err);
+
+        assert!(diff_steps("pizza", &first, &first[..3]).is_err());
+    }
+
+    fn faulty(faults: Vec<FaultType>) -> ReplayController {
+        ReplayController::new(ReplayConfig {
+            faults,
+            ..ReplayConfig::default()
+        })
+    }
+
+    #[test]
+    fn dropped_accept_leaves_order_committed() {
+        let source = TraceSource::from_rpc_export(PIZZA_EXPORT).unwrap();
+        let controller = faulty(vec![FaultType::DropMessage {
+            match_phase: "SellerAccept".into(),
+        }]);
+
+        let (steps, engine, ids) = controller.run_with_engine(&source).unwrap();
+        assert_eq!(steps.len(), source.frames.len() - 1);
+        assert!(steps[0].outcome.is_ok());
+        assert!(steps[1..].iter().all(|s| s.outcome.is_err()));
+
+        let order_id = ids[&source.frames[0].order_id];
+        assert_eq!(engine.get_state(&order_id).unwrap(), EscrowState::BuyerCommitted);
+        assert!(engine.get_receipt(&order_id).is_none());
+    }
+
+    #[test]
+    fn dropped_settle_leaves_order_unsettled() {
+        let source = TraceSource::from_rpc_export(PIZZA_EXPORT).unwrap();
+        let controller = faulty(vec![FaultType::DropMessage {
+            match_phase: "SellerClaim".into(),
+        }]);
+
+        let (steps, engine, ids) = controller.run_with_engine(&source).unwrap();
+        assert!(steps.iter().all(|s| s.outcome.is_ok()));
+
+        let order_id = ids[&source.frames[0].order_id];
+        assert_eq!(engine.get_state(&order_id).unwrap(), EscrowState::SellerFulfilled);
+        assert!(engine.get_receipt(&order_id).unwrap().seller_claim_txid.is_none());
+    }
+
+    #[test]
+    fn delayed_accept_misses_the_window() {
+        let source = TraceSource::from_rpc_export(PIZZA_EXPORT).unwrap();
+        // Accept lands at +1820s: past the 1800s window, after fulfill and claim
+        let controller = faulty(vec![FaultType::DelayMessage {
+            match_phase: "SellerAccept".into(),
+            by_secs: 1_700,
+        }]);
+
+        let (steps, engine, ids) = controller.run_with_engine(&source).unwrap();
+        let order: Vec<&str> = steps.iter().map(|s| s.tx_hash.as_str()).collect();
+        let hash = |i: usize| source.frames[i].tx_hash.as_str();
+        assert_eq!(order, vec![hash(0), hash(2), hash(3), hash(1)]);
+        assert!(steps[3].outcome.as_ref().unwrap_err().contains("acceptance window expired"));
+
+        let order_id = ids[&source.frames[0].order_id];
+        assert_eq!(engine.get_state(&order_id).unwrap(), EscrowState::BuyerCommitted);
+
+        controller.assert_deterministic(&source).unwrap();
+    }
+}
@@ -0,0 +1,208 @@
+// ============================================================================
+// tests/harness/simulate.rs
+// CoreProver v0.3 Test Harness - Seeded State-Machine Fuzzer
+// ============================================================================
+//
+// simulate(seed, steps) drives a fresh engine with pseudo-random actions
+// (commit / accept / fulfill / claim / refund / withdraw / advance), picked
+// from a ChaCha20 stream so every run is reproducible from its seed.
+//
+// Actions are "valid-ish": they target a random known order, so many are
+// rejected by the engine. After every step the harness checks:
+//
+//   * every order's state change is allowed by ModelChecker::validate_transition
+//   * a rejected action left its order's state untouched
+//   * receipts never carry both a claim and a refund, and the terminal
+//     state matches the settlement txid
+//   * late fulfillment and discount always go together
+//   * the v0.3 invariants (value conservation) hold across all orders
+//   * the engine's triple clock is consistent
+//
+// Any violation fails with the seed and step so it can be replayed.
+//
+// ============================================================================
+
+use super::model_checker::ModelChecker;
+use super::{HResult, HarnessError};
+use coreprover_service::engine::{CoreProverEngine, EngineError};
+use coreprover_service::types::PaymentProfile;
+use coreprover_types_v03::EscrowState;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+const GENESIS_UNIX: u64 = 1_700_000_000;
+
+/// Largest single time jump; above the pizza fulfillment window so
+/// deadlines are regularly crossed.
+const MAX_ADVANCE_SECS: u64 = 4_000;
+
+// ============================================================================
+// Actions
+// ============================================================================
+
+#[derive(Debug, Clone, Copy)]
+enum Action {
+    Commit,
+    Accept,
+    Fulfill,
+    Claim,
+    Refund,
+    Withdraw,
+    Advance,
+}
+
+const ACTIONS: [Action; 7] = [
+    Action::Commit,
+    Action::Accept,
+    Action::Fulfill,
+    Action::Claim,
+    Action::Refund,
+    Action::Withdraw,
+    Action::Advance,
+];
+
+// ============================================================================
+// simulate
+// ============================================================================
+
+/// Run `steps` seeded random actions against a fresh engine, checking
+/// invariants after each one. The same seed always replays the same run.
+pub fn simulate(seed: u64, steps: usize) -> HResult<()> {
+    let mut rng = ChaCha20Rng::seed_from_u64(seed);
+    let mut engine = CoreProverEngine::new(1, 12, GENESIS_UNIX);
+    let checker = ModelChecker::new_with_v03_rules();
+
+    let mut orders: Vec<[u8; 32]> = Vec::new();
+    let mut states: Vec<EscrowState> = Vec::new();
+
+    for step in 0..steps {
+        let fail = |msg: String| {
+            HarnessError::new(format!("simulate seed {} step {}: {}", seed, step, msg))
+        };
+
+        let action = ACTIONS[rng.gen_range(0..ACTIONS.len())];
+        let txid = format!("0x{:016x}", rng.gen::<u64>());
+
+        // Without any orders yet, every non-advance action becomes a commit
+        let target = match action {
+            Action::Commit | Action::Advance => None,
+            _ if orders.is_empty() => None,
+            _ => Some(rng.gen_range(0..orders.len())),
+        };
+
+        let result: Result<(), EngineError> = match (action, target) {
+            (Action::Advance, _) => {
+                engine.advance_time(rng.gen_range(0..MAX_ADVANCE_SECS));
+                engine.sweep_expirations();
+                Ok(())
+            }
+            (_, None) => engine
+                .buyer_commit(
+                    "buyer".into(),
+                    "seller".into(),
+                    rng.gen_range(1..=10_000),
+                    PaymentProfile::pizza_delivery(),
+                    1,
+                    txid,
+                )
+                .map(|id| {
+                    orders.push(id);
+                    states.push(EscrowState::BuyerCommitted);
+                }),
+            (Action::Accept, Some(i)) => engine.seller_accept(&orders[i], txid),
+            (Action::Fulfill, Some(i)) => engine.seller_fulfill(&orders[i], txid),
+            (Action::Claim, Some(i)) => engine.seller_claim(&orders[i], txid).map(|_| ()),
+            (Action::Refund, Some(i)) => engine.seller_refund(&orders[i], txid).map(|_| ()),
+            (Action::Withdraw, Some(i)) => {
+                engine.buyer_withdraw(&orders[i], Some(txid)).map(|_| ())
+            }
+            (Action::Commit, Some(_)) => unreachable!("commit never targets an order"),
+        };
+
+        // Rejected actions must not move their order
+        if let (Err(e), Some(i)) = (&result, target) {
+            let now = engine.get_state(&orders[i]).map_err(|e| fail(e.to_string()))?;
+            if now != states[i] {
+                return Err(fail(format!(
+                    "{:?} was rejected ({}) but moved order {} {:?} -> {:?}",
+                    action, e, i, states[i], now
+                )));
+            }
+        }
+
+        for (i, id) in orders.iter().enumerate() {
+            let now = engine.get_state(id).map_err(|e| fail(e.to_string()))?;
+            if now != states[i] {
+                checker
+                    .validate_transition(states[i], now)
+                    .map_err(|e| fail(format!("{:?} on order {}: {}", action, i, e)))?;
+                states[i] = now;
+            }
+            check_receipt(&engine, id, now).map_err(|e| fail(format!("order {}: {}", i, e)))?;
+        }
+
+        checker.check_invariants(&engine).map_err(fail)?;
+        engine.verify_clock_consistency().map_err(|e| fail(e.to_string()))?;
+    }
+
+    Ok(())
+}
+
+/// Settlement invariants on the receipt for one order.
+fn check_receipt(
+    engine: &CoreProverEngine,
+    id: &[u8; 32],
+    state: EscrowState,
+) -> Result<(), String> {
+    let Some(r) = engine.get_receipt(id) else {
+        return match state {
+            EscrowState::SellerClaimed
+            | EscrowState::SellerRefunded
+            | EscrowState::BuyerReclaimed => Err(format!("{:?} without a receipt", state)),
+            _ => Ok(()),
+        };
+    };
+
+    if r.seller_claim_txid.is_some() && r.seller_refund_txid.is_some() {
+        return Err("receipt carries both claim and refund txids".into());
+    }
+    if state == EscrowState::SellerClaimed && r.seller_claim_txid.is_none() {
+        return Err("SellerClaimed without claim txid".into());
+    }
+    if state == EscrowState::SellerRefunded && r.seller_refund_txid.is_none() {
+        return Err("SellerRefunded without refund txid".into());
+    }
+    if state == EscrowState::BuyerReclaimed && r.buyer_withdraw_txid.is_none() {
+        return Err("BuyerReclaimed without buyer txid".into());
+    }
+    // The pizza profile always grants a discount on late fulfillment
+    if r.late_fulfilled != (r.discount_pct > 0) {
+        return Err(format!(
+            "late_fulfilled={} but discount_pct={}",
+            r.late_fulfilled, r.discount_pct
+        ));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simulate_holds_invariants_across_seeds() {
+        for seed in [1, 7, 42, 1337, 0xdead_beef] {
+            simulate(seed, 500).unwrap();
+        }
+    }
+
+    #[test]
+    fn simulate_is_reproducible() {
+        let run = || simulate(2024, 200).map_err(|e| e.msg);
+        assert_eq!(run(), run());
+    }
+}
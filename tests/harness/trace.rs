@@ -12,6 +12,8 @@
 //!
 //! ============================================================================
 
+use crate::harness::types::{HarnessOrderId, TxId};
+use coreprover_types_v03::{EscrowState, TripleTimestamp};
 use serde::{Deserialize, Serialize};
 
 /// Result of a single engine transition attempt.
@@ -216,4 +218,111 @@ impl HarnessEventTrace {
         let encoded = bincode::serialize(self).expect("trace bincode");
         blake3::hash(&encoded)
     }
-}
\ No newline at end of file
+}
+
+// ============================================================================
+// Driver trace
+//
+// EngineDriver records what actually happened (post-transition), as opposed
+// to HarnessEvent which records what was requested.
+// ============================================================================
+
+/// A single observed engine event, recorded by EngineDriver.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceEvent {
+    BuyerCommitted {
+        order_id: HarnessOrderId,
+        buyer: String,
+        seller: String,
+        amount: u64,
+        txid: TxId,
+        timestamp: TripleTimestamp,
+    },
+    SellerAccepted {
+        order_id: HarnessOrderId,
+        txid: TxId,
+        timestamp: TripleTimestamp,
+    },
+    SellerFulfilled {
+        order_id: HarnessOrderId,
+        txid: TxId,
+        late: bool,
+        timestamp: TripleTimestamp,
+    },
+    SellerClaimed {
+        order_id: HarnessOrderId,
+        amount: u64,
+        txid: TxId,
+        timestamp: TripleTimestamp,
+    },
+    SellerRefunded {
+        order_id: HarnessOrderId,
+        amount: u64,
+        txid: TxId,
+        timestamp: TripleTimestamp,
+    },
+    BuyerWithdrew {
+        order_id: HarnessOrderId,
+        amount: u64,
+        txid: Option<TxId>,
+        timestamp: TripleTimestamp,
+    },
+    TimedRelease {
+        order_id: HarnessOrderId,
+        amount: u64,
+        timestamp: TripleTimestamp,
+    },
+    StateTransition {
+        order_id: HarnessOrderId,
+        from: EscrowState,
+        to: EscrowState,
+        timestamp: TripleTimestamp,
+    },
+    /// Buyer withdrawal became blocked (`locked`) or available again.
+    /// `reason` is "accept", "expiry" or "late_fulfill".
+    WithdrawalLockChanged {
+        order_id: HarnessOrderId,
+        locked: bool,
+        reason: String,
+        timestamp: TripleTimestamp,
+    },
+    ReceiptCreated {
+        order_id: HarnessOrderId,
+        timestamp: TripleTimestamp,
+    },
+    SettlementFinalized {
+        order_id: HarnessOrderId,
+        amount: u64,
+        settlement_type: String,
+        timestamp: TripleTimestamp,
+    },
+    TimeAdvanced {
+        secs: u64,
+        new_block: u64,
+        timestamp: TripleTimestamp,
+    },
+}
+
+/// Append-only recorder for driver TraceEvents.
+#[derive(Debug, Clone, Default)]
+pub struct Tracer {
+    events: Vec<TraceEvent>,
+}
+
+impl Tracer {
+    pub fn new() -> Self {
+        Self { events: Vec::new() }
+    }
+
+    pub fn record(&mut self, event: TraceEvent) {
+        self.events.push(event);
+    }
+
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.events
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+}
@@ -175,10 +175,11 @@ impl From<TxId> for String {
 // ============================================================================
 // EngineError - Typed error variants
 //
-// The canonical engine returns Result<T, String>. This enum categorizes
-// those string errors into typed variants for better error handling in tests.
+// The canonical engine returns its own typed error. This enum folds those
+// variants into the harness categories used by scenarios and assertions.
 //
-// The From<String> impl below parses engine error messages into variants.
+// From<engine::EngineError> maps engine variants directly; From<String>
+// still parses free-form messages (trace sources, parsed order ids).
 // ============================================================================
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -257,6 +258,33 @@ impl std::fmt::Display for EngineError {
 
 impl std::error::Error for EngineError {}
 
+/// Convert the engine's typed errors into harness variants
+impl From<coreprover_service::engine::EngineError> for EngineError {
+    fn from(e: coreprover_service::engine::EngineError) -> Self {
+        use coreprover_service::engine::EngineError as Engine;
+
+        match e {
+            Engine::EscrowNotFound => EngineError::EscrowNotFound { order_id: String::new() },
+            Engine::InvalidState { current, .. } => EngineError::InvalidState {
+                expected: Vec::new(),
+                actual: current,
+            },
+            Engine::MissingTxid(action) => EngineError::TxIdRequired { action: action.into() },
+            Engine::WindowExpired(window) => EngineError::DeadlineExpired {
+                deadline_type: window.into(),
+                deadline: 0,
+                current: 0,
+            },
+            Engine::WindowNotExpired("acceptance") => {
+                EngineError::AcceptanceWindowNotExpired { remaining_secs: 0 }
+            }
+            Engine::WindowNotExpired(_) => EngineError::ClaimWindowNotExpired { remaining_secs: 0 },
+            Engine::TimedReleaseDisabled => EngineError::TimedReleaseDisabled,
+            other => EngineError::InvalidOperation(other.to_string()),
+        }
+    }
+}
+
 /// Convert engine's String errors into typed EngineError variants
 impl From<String> for EngineError {
     fn from(s: String) -> Self {